@@ -1,14 +1,53 @@
 use std::{
-    env,
-    path::PathBuf,
-    process::{exit, Command},
+    env, fs,
+    path::{Path, PathBuf},
+    process::{exit, Command, Stdio},
+    time::SystemTime,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use flux_bin::utils::{
     get_flux_driver_path, get_rust_toolchain, get_rustc_driver_lib_path, prepend_path_to_env_var,
     EXIT_ERR, LIB_PATH,
 };
+use itertools::Itertools;
+use serde::Deserialize;
+
+/// Must be kept in sync with the extension of the text reports written by `flux_driver::coverage`
+const COVERAGE_TXT: &str = ".coverage.txt";
+
+const USAGE: &str = "\
+Verify a package with flux
+
+Usage: cargo flux [FLUX OPTIONS] [CARGO CHECK OPTIONS]
+
+Flux options:
+      --check-def <SEL>     Only check definitions selected by SEL: a path where `*` matches any
+                            sequence of characters or `file:line`. Can be a comma separated list
+      --check-file <FILE>   Only check definitions in FILE, relative to the workspace root. Can be
+                            repeated
+      --dump <KINDS>        Write debugging information to the log directory. KINDS is a comma
+                            separated list of: constraint, checker-trace, timings, mir, fhir, rty
+      --cache               Cache the results of queries to the fixpoint solver
+      --explain             List the assumptions leading to each refinement type error
+      --report <KINDS>      Print reports after checking. KINDS is a comma separated list of:
+                            coverage
+      --coverage            Shorthand for `--report coverage`
+  -h, --help                Print help
+
+Any other option is passed to `cargo check`. These options override the configuration in
+`flux.toml` and in the `[package.metadata.flux]` table of each package.
+";
+
+/// Values accepted by `--dump` and the environment variable enabling each of them
+const DUMP_KINDS: &[(&str, &str)] = &[
+    ("constraint", "FLUX_DUMP_CONSTRAINT"),
+    ("checker-trace", "FLUX_DUMP_CHECKER_TRACE"),
+    ("timings", "FLUX_DUMP_TIMINGS"),
+    ("mir", "FLUX_DUMP_MIR"),
+    ("fhir", "FLUX_DUMP_FHIR"),
+    ("rty", "FLUX_DUMP_RTY"),
+];
 
 fn main() {
     let exit_code = match run() {
@@ -22,28 +61,49 @@ fn main() {
 }
 
 fn run() -> Result<i32> {
-    let flux_driver_path = get_flux_driver_path()?;
-    let rust_toolchain = get_rust_toolchain()?;
-    let ld_library_path = get_rustc_driver_lib_path(&rust_toolchain)?;
-    let extended_lib_path = prepend_path_to_env_var(LIB_PATH, ld_library_path)?;
-
     // Cargo can be called like `cargo [OPTIONS] flux`, so we skip all arguments until `flux` is
     // found.
     let args = env::args()
         .skip_while(|arg| arg != "flux")
         .skip(1)
         .collect::<Vec<_>>();
+    let (opts, cargo_args) = FluxOpts::parse(args)?;
+    if opts.help {
+        print!("{USAGE}");
+        return Ok(0);
+    }
+
+    let flux_driver_path = get_flux_driver_path()?;
+    let rust_toolchain = get_rust_toolchain()?;
+    let ld_library_path = get_rustc_driver_lib_path(&rust_toolchain)?;
+    let extended_lib_path = prepend_path_to_env_var(LIB_PATH, ld_library_path)?;
+
+    // Cargo runs the driver from the root of the workspace, so we make the log directory absolute
+    // to know where the reports are written.
+    let log_dir: PathBuf = env::current_dir()?
+        .join(flux_config::log_dir())
+        .components()
+        .collect();
 
     let cargo_path = env::var("CARGO_PATH").unwrap_or_else(|_| "cargo".to_string());
     let cargo_target = env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
     let cargo_target = PathBuf::from_iter([cargo_target, "flux".to_string()]);
 
-    let exit_code = Command::new(cargo_path)
+    let workspace_members = workspace_members(&cargo_path, &cargo_args)?;
+
+    let start = SystemTime::now();
+    let mut cmd = Command::new(cargo_path);
+    cmd.envs(opts.env_vars());
+    if opts.coverage {
+        cmd.env("FLUX_LOG_DIR", &log_dir);
+    }
+    let exit_code = cmd
         .arg("check")
-        .args(args)
+        .args(cargo_args)
         .env(LIB_PATH, extended_lib_path)
         .env("FLUX_BUILD_SYSROOT", "1")
         .env("FLUX_CARGO", "1")
+        .env("FLUX_WORKSPACE_MEMBERS", env::join_paths(workspace_members)?)
         .env("RUST_TOOLCHAIN", rust_toolchain.clone())
         .env("RUSTUP_TOOLCHAIN", rust_toolchain)
         .env("RUSTC", flux_driver_path)
@@ -51,5 +111,244 @@ fn run() -> Result<i32> {
         .status()?
         .code();
 
+    if opts.coverage {
+        print_coverage_reports(&log_dir, start)?;
+    }
+
     Ok(exit_code.unwrap_or(EXIT_ERR))
 }
+
+/// Options understood by `cargo flux`. They are passed to the driver through the environment
+/// variables read by `flux_config`, which take precedence over the config files.
+#[derive(Default)]
+struct FluxOpts {
+    check_def: Option<String>,
+    check_files: Vec<String>,
+    dump: Vec<&'static str>,
+    cache: bool,
+    explain: bool,
+    coverage: bool,
+    help: bool,
+}
+
+impl FluxOpts {
+    /// Splits the arguments into the options for flux and the ones for `cargo check`. Arguments
+    /// after `--` are passed to cargo untouched.
+    fn parse(args: Vec<String>) -> Result<(FluxOpts, Vec<String>)> {
+        let mut opts = FluxOpts::default();
+        let mut cargo_args = vec![];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            // Options with a value can be written as `--opt value` or `--opt=value`
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| anyhow!("missing value for `{name}`"))
+            };
+            match name {
+                "--check-def" => opts.check_def = Some(value()?),
+                "--check-file" => opts.check_files.push(value()?),
+                "--dump" => {
+                    for kind in value()?.split(',').map(str::trim) {
+                        let (kind, _) = DUMP_KINDS
+                            .iter()
+                            .find(|(name, _)| *name == kind)
+                            .ok_or_else(|| anyhow!("unknown kind for `--dump`: `{kind}`"))?;
+                        opts.dump.push(kind);
+                    }
+                }
+                "--cache" => opts.cache = true,
+                "--explain" => opts.explain = true,
+                "--report" => {
+                    for kind in value()?.split(',').map(str::trim) {
+                        match kind {
+                            "coverage" => opts.coverage = true,
+                            _ => return Err(anyhow!("unknown kind for `--report`: `{kind}`")),
+                        }
+                    }
+                }
+                "--coverage" => opts.coverage = true,
+                "-h" | "--help" => opts.help = true,
+                "--" => {
+                    cargo_args.push(arg);
+                    cargo_args.extend(args);
+                    break;
+                }
+                _ => cargo_args.push(arg),
+            }
+        }
+        Ok((opts, cargo_args))
+    }
+
+    fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = vec![];
+        if let Some(check_def) = &self.check_def {
+            vars.push(("FLUX_CHECK_DEF", check_def.clone()));
+        }
+        if !self.check_files.is_empty() {
+            vars.push(("FLUX_CHECK_FILES", self.check_files.join(",")));
+        }
+        for (kind, var) in DUMP_KINDS {
+            if self.dump.contains(kind) {
+                vars.push((var, "1".to_string()));
+            }
+        }
+        if self.cache {
+            vars.push(("FLUX_CACHE", "1".to_string()));
+        }
+        if self.explain {
+            vars.push(("FLUX_EXPLAIN", "1".to_string()));
+        }
+        if self.coverage {
+            vars.push(("FLUX_COVERAGE", "1".to_string()));
+        }
+        vars
+    }
+}
+
+/// The subset of the output of `cargo metadata` we care about
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    manifest_path: PathBuf,
+}
+
+/// Returns the directories of the packages in the workspace. They are passed to the driver, which
+/// verifies them and only checks the specs of every other package.
+fn workspace_members(cargo_path: &str, cargo_args: &[String]) -> Result<Vec<PathBuf>> {
+    let mut cmd = Command::new(cargo_path);
+    cmd.args(["metadata", "--no-deps", "--format-version=1"]);
+    let mut args = cargo_args.iter().take_while(|arg| *arg != "--");
+    while let Some(arg) = args.next() {
+        if arg == "--manifest-path" {
+            cmd.arg(arg).args(args.next());
+        } else if arg.starts_with("--manifest-path=") {
+            cmd.arg(arg);
+        }
+    }
+    let output = cmd.stderr(Stdio::inherit()).output()?;
+    if !output.status.success() {
+        return Err(anyhow!("`cargo metadata` failed"));
+    }
+    let metadata: Metadata = serde_json::from_slice(&output.stdout)?;
+    Ok(metadata
+        .packages
+        .into_iter()
+        .filter_map(|package| package.manifest_path.parent().map(Path::to_path_buf))
+        .collect())
+}
+
+/// Prints the coverage reports in the log directory written since `start`, i.e., the ones of the
+/// crates checked in this run. Reports of crates that cargo didn't check again because they were
+/// fresh are left over from a previous run and may not match the current configuration.
+fn print_coverage_reports(log_dir: &Path, start: SystemTime) -> Result<()> {
+    let Ok(entries) = fs::read_dir(log_dir) else { return Ok(()) };
+    let reports = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified >= start)
+        })
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(COVERAGE_TXT))
+        })
+        .sorted()
+        .collect_vec();
+    for report in &reports {
+        println!("{}", fs::read_to_string(report)?);
+    }
+    if !reports.is_empty() {
+        println!("json reports written to {}", log_dir.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<(FluxOpts, Vec<String>)> {
+        FluxOpts::parse(args.iter().map(|&arg| arg.to_string()).collect())
+    }
+
+    #[test]
+    fn flux_options_are_split_from_cargo_options() {
+        let (opts, cargo_args) = parse(&[
+            "--check-def",
+            "foo::*",
+            "-p",
+            "bar",
+            "--check-file=src/lib.rs",
+            "--check-file",
+            "src/main.rs",
+            "--cache",
+            "--release",
+        ])
+        .unwrap();
+        assert_eq!(opts.check_def.as_deref(), Some("foo::*"));
+        assert_eq!(opts.check_files, ["src/lib.rs", "src/main.rs"]);
+        assert!(opts.cache);
+        assert_eq!(cargo_args, ["-p", "bar", "--release"]);
+    }
+
+    #[test]
+    fn arguments_after_double_dash_go_to_cargo() {
+        let (opts, cargo_args) = parse(&["--explain", "--", "--cache", "--coverage"]).unwrap();
+        assert!(opts.explain);
+        assert!(!opts.cache && !opts.coverage);
+        assert_eq!(cargo_args, ["--", "--cache", "--coverage"]);
+    }
+
+    #[test]
+    fn dump_and_report_kinds() {
+        let (opts, _) = parse(&["--dump", "constraint, mir", "--report=coverage"]).unwrap();
+        assert_eq!(opts.dump, ["constraint", "mir"]);
+        assert!(opts.coverage);
+
+        assert!(parse(&["--dump=smt"]).is_err());
+        assert!(parse(&["--report", "timings"]).is_err());
+    }
+
+    #[test]
+    fn missing_value() {
+        let err = parse(&["--check-def"]).err().unwrap();
+        assert_eq!(err.to_string(), "missing value for `--check-def`");
+    }
+
+    #[test]
+    fn options_are_passed_as_env_vars() {
+        let (opts, _) = parse(&[
+            "--check-def=a,b",
+            "--check-file=x.rs",
+            "--check-file=y.rs",
+            "--dump=timings",
+            "--coverage",
+        ])
+        .unwrap();
+        let vars = opts.env_vars();
+        assert_eq!(
+            vars,
+            [
+                ("FLUX_CHECK_DEF", "a,b".to_string()),
+                ("FLUX_CHECK_FILES", "x.rs,y.rs".to_string()),
+                ("FLUX_DUMP_TIMINGS", "1".to_string()),
+                ("FLUX_COVERAGE", "1".to_string()),
+            ]
+        );
+        assert!(FluxOpts::default().env_vars().is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+use std::process::exit;
+
+use flux_bin::utils::EXIT_ERR;
+
+fn main() {
+    if let Err(e) = flux_bin::lsp::run() {
+        eprintln!("Failed to run flux-lsp, error={e}");
+        exit(EXIT_ERR);
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, exit, Command},
+};
+
+use anyhow::{anyhow, Result};
+use flux_bin::utils::{
+    get_flux_driver_path, get_rust_toolchain, get_rustc_driver_lib_path, prepend_path_to_env_var,
+    sysroot_dir, EXIT_ERR, LIB_PATH,
+};
+
+const USAGE: &str = "usage: flux-meta dump <path/to/libcrate.fluxmeta>";
+
+fn main() {
+    let exit_code = match run() {
+        Ok(code) => code,
+        Err(e) => {
+            println!("Failed to run flux-meta, error={e}");
+            EXIT_ERR
+        }
+    };
+    exit(exit_code)
+}
+
+fn run() -> Result<i32> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match &args[..] {
+        [cmd, path] if cmd == "dump" => dump(Path::new(path)),
+        _ => Err(anyhow!(USAGE)),
+    }
+}
+
+/// Decoding metadata requires a `TyCtxt` with the crate loaded, so we ask `flux-driver` to compile
+/// an empty crate that depends on it and print the metadata instead of checking.
+fn dump(path: &Path) -> Result<i32> {
+    if !path.is_file() {
+        return Err(anyhow!("flux metadata file {:?} does not exist or is not a file", path));
+    }
+    let krate = crate_name(path)?;
+    let artifact = ["rmeta", "rlib"]
+        .into_iter()
+        .map(|ext| path.with_extension(ext))
+        .find(|artifact| artifact.is_file())
+        .ok_or_else(|| anyhow!("no `.rmeta` or `.rlib` file found next to {:?}", path))?;
+    let deps_dir = artifact
+        .parent()
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+
+    let out_dir = env::temp_dir().join(format!("flux-meta-{}", process::id()));
+    fs::create_dir_all(&out_dir)?;
+    let stub = out_dir.join("flux_meta_dump.rs");
+    fs::write(&stub, format!("extern crate {krate};\n"))?;
+
+    let flux_driver_path = get_flux_driver_path()?;
+    let rust_toolchain = get_rust_toolchain()?;
+    let ld_library_path = get_rustc_driver_lib_path(&rust_toolchain)?;
+    let extended_lib_path = prepend_path_to_env_var(LIB_PATH, ld_library_path)?;
+
+    let exit_code = Command::new(flux_driver_path)
+        .arg(&stub)
+        .arg("--crate-type=lib")
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg("--extern")
+        .arg(format!("{krate}={}", artifact.display()))
+        .arg(format!("-Ldependency={}", deps_dir.display()))
+        .arg("-L")
+        .arg(sysroot_dir())
+        .arg("--extern")
+        .arg("flux_rs")
+        .env("FLUX_META_DUMP", &krate)
+        .env(LIB_PATH, extended_lib_path)
+        .status()?
+        .code();
+
+    fs::remove_dir_all(&out_dir)?;
+    Ok(exit_code.unwrap_or(EXIT_ERR))
+}
+
+/// Recovers the name of the crate from the name of the file, which is either `libcrate.fluxmeta`
+/// or `libcrate-<hash>.fluxmeta` when compiled by cargo.
+fn crate_name(path: &Path) -> Result<String> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.strip_prefix("lib"))
+        .and_then(|stem| stem.split('-').next())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("cannot determine the crate name from {:?}", path))
+}
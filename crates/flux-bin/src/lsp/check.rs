@@ -0,0 +1,259 @@
+//! Running `cargo flux` and converting the reported messages into LSP diagnostics.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader},
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::Result;
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString,
+    Position, Range, Url,
+};
+use serde::Deserialize;
+
+use super::{file_uri, utf16_col};
+
+#[derive(Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+enum CargoMessage {
+    CompilerMessage {
+        message: RustcDiagnostic,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    code: Option<RustcCode>,
+    level: String,
+    spans: Vec<RustcSpan>,
+    children: Vec<RustcDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: u32,
+    line_end: u32,
+    column_start: u32,
+    column_end: u32,
+    is_primary: bool,
+    label: Option<String>,
+    /// The source lines covered by the span
+    text: Vec<RustcSpanLine>,
+}
+
+#[derive(Deserialize)]
+struct RustcSpanLine {
+    text: String,
+}
+
+/// Runs `cargo flux` in `root` restricting the check to `file` and returns the diagnostics grouped
+/// by file.
+///
+/// We never modify the file to force a rebuild. Cargo reruns flux when the file or one of the
+/// `FLUX_*` variables it tracks changes (e.g., `FLUX_CHECK_FILES` when a different file is checked),
+/// and otherwise replays the diagnostics of the last run, in which case the trace in the log
+/// directory is still up to date.
+pub(super) fn run_cargo_flux(
+    root: &Path,
+    log_dir: &Path,
+    file: &Path,
+) -> Result<HashMap<Url, Vec<Diagnostic>>> {
+    let rel_file = file.strip_prefix(root).unwrap_or(file);
+    let mut child = Command::new("cargo")
+        .arg("flux")
+        .arg("--message-format=json")
+        .current_dir(root)
+        .env("FLUX_DUMP_CHECKER_TRACE", "1")
+        .env("FLUX_CHECK_FILES", rel_file)
+        .env("FLUX_LOG_DIR", log_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let mut diagnostics: HashMap<Url, Vec<Diagnostic>> = HashMap::new();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(CargoMessage::CompilerMessage { message }) = serde_json::from_str(&line?) else {
+                continue;
+            };
+            if let Some((uri, diagnostic)) = convert_diagnostic(root, message) {
+                diagnostics.entry(uri).or_default().push(diagnostic);
+            }
+        }
+    }
+    child.wait()?;
+    Ok(diagnostics)
+}
+
+fn convert_diagnostic(root: &Path, diag: RustcDiagnostic) -> Option<(Url, Diagnostic)> {
+    let primary = diag.spans.iter().find(|span| span.is_primary)?;
+    let uri = file_uri(root, &primary.file_name)?;
+
+    let mut message = diag.message;
+    if let Some(label) = &primary.label {
+        message = format!("{message}\n{label}");
+    }
+    for child in &diag.children {
+        if child.spans.is_empty() {
+            message = format!("{message}\n{}: {}", child.level, child.message);
+        }
+    }
+
+    let related_information = diag
+        .spans
+        .iter()
+        .filter(|span| !span.is_primary)
+        .chain(diag.children.iter().flat_map(|child| &child.spans))
+        .filter_map(|span| {
+            Some(DiagnosticRelatedInformation {
+                location: Location { uri: file_uri(root, &span.file_name)?, range: range(span) },
+                message: span.label.clone().unwrap_or_default(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let diagnostic = Diagnostic {
+        range: range(primary),
+        severity: Some(severity(&diag.level)),
+        code: diag.code.map(|code| NumberOrString::String(code.code)),
+        source: Some("flux".to_string()),
+        message,
+        related_information: Some(related_information),
+        ..Default::default()
+    };
+    Some((uri, diagnostic))
+}
+
+fn severity(level: &str) -> DiagnosticSeverity {
+    match level {
+        "error" | "error: internal compiler error" => DiagnosticSeverity::ERROR,
+        "warning" => DiagnosticSeverity::WARNING,
+        "note" => DiagnosticSeverity::INFORMATION,
+        _ => DiagnosticSeverity::HINT,
+    }
+}
+
+/// Converts the span into an LSP range. Rustc reports 1-based character columns but LSP counts
+/// UTF-16 code units, so we convert them using the text of the span when it is available.
+fn range(span: &RustcSpan) -> Range {
+    let character = |line: Option<&RustcSpanLine>, col: u32| {
+        match line {
+            Some(line) => utf16_col(&line.text, col as usize - 1),
+            None => col - 1,
+        }
+    };
+    Range {
+        start: Position {
+            line: span.line_start - 1,
+            character: character(span.text.first(), span.column_start),
+        },
+        end: Position {
+            line: span.line_end - 1,
+            character: character(span.text.last(), span.column_end),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = r##"{
+        "reason": "compiler-message",
+        "message": {
+            "message": "refinement type error",
+            "code": { "code": "E0999" },
+            "level": "error",
+            "spans": [{
+                "file_name": "src/lib.rs",
+                "line_start": 3,
+                "line_end": 3,
+                "column_start": 18,
+                "column_end": 25,
+                "is_primary": true,
+                "label": "a precondition cannot be proved",
+                "text": [{ "text": "    let s = \"😀\"; f(s, 0);" }]
+            }],
+            "children": [
+                {
+                    "message": "this is the condition that cannot be proved",
+                    "level": "note",
+                    "spans": [{
+                        "file_name": "src/lib.rs",
+                        "line_start": 1,
+                        "line_end": 1,
+                        "column_start": 31,
+                        "column_end": 36,
+                        "is_primary": true,
+                        "label": null,
+                        "text": [{ "text": "#[flux::sig(fn(s: &str, i: usize{i < 1}))]" }]
+                    }],
+                    "children": []
+                },
+                {
+                    "message": "run with `FLUX_EXPLAIN=1` for details",
+                    "level": "help",
+                    "spans": [],
+                    "children": []
+                }
+            ]
+        }
+    }"##;
+
+    #[test]
+    fn convert_compiler_message() {
+        let CargoMessage::CompilerMessage { message } = serde_json::from_str(MESSAGE).unwrap()
+        else {
+            panic!("expected a compiler message")
+        };
+        let (uri, diagnostic) = convert_diagnostic(Path::new("/ws"), message).unwrap();
+
+        assert_eq!(uri, Url::parse("file:///ws/src/lib.rs").unwrap());
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostic.code, Some(NumberOrString::String("E0999".to_string())));
+        assert_eq!(
+            diagnostic.message,
+            "refinement type error\na precondition cannot be proved\nhelp: run with `FLUX_EXPLAIN=1` for details"
+        );
+        // `😀` takes two UTF-16 code units
+        assert_eq!(
+            diagnostic.range,
+            Range {
+                start: Position { line: 2, character: 18 },
+                end: Position { line: 2, character: 25 }
+            }
+        );
+
+        let related = diagnostic.related_information.unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].location.range.start, Position { line: 0, character: 30 });
+    }
+
+    #[test]
+    fn range_without_text_uses_character_columns() {
+        let span = RustcSpan {
+            file_name: "src/lib.rs".to_string(),
+            line_start: 2,
+            line_end: 2,
+            column_start: 5,
+            column_end: 9,
+            is_primary: true,
+            label: None,
+            text: vec![],
+        };
+        assert_eq!(range(&span).start, Position { line: 1, character: 4 });
+        assert_eq!(range(&span).end, Position { line: 1, character: 8 });
+    }
+}
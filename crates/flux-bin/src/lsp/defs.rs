@@ -0,0 +1,185 @@
+//! Go-to-definition for spec functions and qualifiers.
+//!
+//! When the checker trace is enabled, flux writes the references to spec functions and qualifiers in
+//! the signatures of a crate to `<crate>.refs.json` in the log directory, together with the span of
+//! the definition each name was resolved to. We answer requests by looking up the reference under
+//! the cursor, so shadowed or same-named items are never confused.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use lsp_types::{Location, Position, Range, Url};
+use serde::Deserialize;
+
+use super::{file_uri, trace::TRACE_VERSION, utf16_col};
+
+/// Must be kept in sync with `flux_common::trace::REFS_EXT`
+const REFS_EXT: &str = ".refs.json";
+
+#[derive(Default)]
+pub(super) struct References {
+    files: HashMap<Url, Vec<Reference>>,
+}
+
+struct Reference {
+    /// The (1-based) line and character column where the name starts
+    start: (u32, u32),
+    /// The (1-based) line and character column right after the name
+    end: (u32, u32),
+    def: Location,
+}
+
+#[derive(Deserialize)]
+struct RefsTrace {
+    version: u32,
+    refs: Vec<RefTrace>,
+}
+
+#[derive(Deserialize)]
+struct RefTrace {
+    span: SpanTrace,
+    def: SpanTrace,
+}
+
+#[derive(Deserialize)]
+struct SpanTrace {
+    file: String,
+    start_line: u32,
+    start_col: u32,
+    end_line: u32,
+    end_col: u32,
+}
+
+impl References {
+    /// Loads the references in `log_dir`. Files in the references are relative to `root`. Files
+    /// that cannot be parsed or were written with a different version of the schema are skipped.
+    pub(super) fn load(log_dir: &Path, root: &Path) -> Self {
+        let mut refs = References::default();
+        let Ok(entries) = fs::read_dir(log_dir) else { return refs };
+        // Definitions are usually in a few files so we read each of them once
+        let mut sources = HashMap::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(REFS_EXT))
+            {
+                continue;
+            }
+            let Ok(contents) = fs::read_to_string(&path) else { continue };
+            let Ok(trace) = serde_json::from_str::<RefsTrace>(&contents) else { continue };
+            if trace.version != TRACE_VERSION {
+                continue;
+            }
+            for ref_trace in trace.refs {
+                let (Some(uri), Some(def_uri)) =
+                    (file_uri(root, &ref_trace.span.file), file_uri(root, &ref_trace.def.file))
+                else {
+                    continue;
+                };
+                let source = sources
+                    .entry(def_uri.clone())
+                    .or_insert_with(|| fs::read_to_string(root.join(&ref_trace.def.file)).ok());
+                let def =
+                    Location { uri: def_uri, range: lsp_range(source.as_deref(), &ref_trace.def) };
+                let span = &ref_trace.span;
+                refs.files.entry(uri).or_default().push(Reference {
+                    start: (span.start_line, span.start_col),
+                    end: (span.end_line, span.end_col),
+                    def,
+                });
+            }
+        }
+        refs
+    }
+
+    /// Replaces the references for every file in `other`
+    pub(super) fn extend(&mut self, other: References) {
+        self.files.extend(other.files);
+    }
+
+    /// Returns the definitions of the names at the (0-based) `line` and character column `col` in
+    /// `uri`. The cursor may also be right after the name.
+    pub(super) fn lookup(&self, uri: &Url, line: u32, col: usize) -> Vec<Location> {
+        let pos = (line + 1, col as u32 + 1);
+        self.files
+            .get(uri)
+            .into_iter()
+            .flatten()
+            .filter(|reference| reference.start <= pos && pos <= reference.end)
+            .map(|reference| reference.def.clone())
+            .collect()
+    }
+}
+
+/// Converts a span with character columns into an LSP range, which uses UTF-16 columns, given the
+/// contents of the file if they could be read.
+fn lsp_range(source: Option<&str>, span: &SpanTrace) -> Range {
+    let position = |line: u32, col: u32| {
+        let text = source.and_then(|source| source.lines().nth(line as usize - 1));
+        let character = match text {
+            Some(text) => utf16_col(text, col as usize - 1),
+            None => col - 1,
+        };
+        Position { line: line - 1, character }
+    };
+    Range {
+        start: position(span.start_line, span.start_col),
+        end: position(span.end_line, span.end_col),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(start: (u32, u32), end: (u32, u32), def_line: u32) -> Reference {
+        let uri = Url::parse("file:///defs.rs").unwrap();
+        let pos = Position { line: def_line, character: 0 };
+        Reference { start, end, def: Location { uri, range: Range { start: pos, end: pos } } }
+    }
+
+    #[test]
+    fn lookup_finds_reference_under_cursor() {
+        let uri = Url::parse("file:///main.rs").unwrap();
+        let mut refs = References::default();
+        refs.files.insert(
+            uri.clone(),
+            vec![reference((3, 20), (3, 24), 10), reference((3, 30), (3, 34), 20)],
+        );
+
+        let def_lines = |line, col| {
+            refs.lookup(&uri, line, col)
+                .iter()
+                .map(|loc| loc.range.start.line)
+                .collect::<Vec<_>>()
+        };
+        // Both ends of the name are included
+        assert_eq!(def_lines(2, 19), vec![10]);
+        assert_eq!(def_lines(2, 23), vec![10]);
+        assert_eq!(def_lines(2, 31), vec![20]);
+        assert!(def_lines(2, 25).is_empty());
+        assert!(def_lines(3, 20).is_empty());
+        assert!(refs
+            .lookup(&Url::parse("file:///other.rs").unwrap(), 2, 20)
+            .is_empty());
+    }
+
+    #[test]
+    fn definition_range_uses_utf16_columns() {
+        let source = "fn f() {}\n/* λ😀 */ fn len(x: int) -> int;\n";
+        let span = SpanTrace {
+            file: "lib.rs".to_string(),
+            start_line: 2,
+            start_col: 13,
+            end_line: 2,
+            end_col: 16,
+        };
+        let range = lsp_range(Some(source), &span);
+        // `😀` takes two UTF-16 code units
+        assert_eq!(range.start, Position { line: 1, character: 13 });
+        assert_eq!(range.end, Position { line: 1, character: 16 });
+        // Without the source we fall back to character columns
+        assert_eq!(lsp_range(None, &span).start, Position { line: 1, character: 12 });
+    }
+}
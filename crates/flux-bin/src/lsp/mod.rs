@@ -0,0 +1,303 @@
+//! A language server for Flux.
+//!
+//! The server runs `cargo flux` on the saved file every time a document is saved, publishing the
+//! resulting diagnostics. Flux is run with the checker trace enabled so we can answer hover requests
+//! with the refinement context and type environment at the statement under the cursor. The server
+//! also offers go-to-definition for spec functions and qualifiers, using the references resolved by
+//! Flux and dumped together with the trace.
+//!
+//! Positions sent by the client count UTF-16 code units while rustc reports character columns, so
+//! we convert columns when going from one to the other (see [`utf16_col`] and [`char_col`]).
+//!
+//! Requests are processed sequentially, thus, requests arriving while Flux is running are answered
+//! after the check finishes.
+
+mod check;
+mod defs;
+mod trace;
+
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{GotoDefinition, HoverRequest, Request as _},
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, InitializeParams, MarkupContent, MarkupKind, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TextDocumentSyncSaveOptions, Url,
+};
+
+use self::{defs::References, trace::Trace};
+
+pub fn run() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::FULL),
+            save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+            ..Default::default()
+        })),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    let params = connection.initialize(serde_json::to_value(capabilities)?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+
+    let mut server = Server::new(workspace_root(&params)?);
+    server.main_loop(&connection)?;
+
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+// `root_uri` is deprecated but we still support clients that only send it
+#[allow(deprecated)]
+fn workspace_root(params: &InitializeParams) -> Result<PathBuf> {
+    let uri = params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .map(|folder| &folder.uri)
+        .or(params.root_uri.as_ref());
+    match uri {
+        Some(uri) => {
+            uri.to_file_path()
+                .map_err(|_| anyhow!("invalid workspace uri `{uri}`"))
+        }
+        None => Ok(env::current_dir()?),
+    }
+}
+
+struct Server {
+    root: PathBuf,
+    log_dir: PathBuf,
+    /// Contents of the documents currently open in the client
+    docs: HashMap<Url, String>,
+    trace: Trace,
+    refs: References,
+}
+
+impl Server {
+    fn new(root: PathBuf) -> Self {
+        let log_dir = env::var("FLUX_LOG_DIR").map_or_else(|_| root.join("log"), PathBuf::from);
+        let trace = Trace::load(&log_dir, &root);
+        let refs = References::load(&log_dir, &root);
+        Self { root, log_dir, docs: HashMap::new(), trace, refs }
+    }
+
+    fn main_loop(&mut self, connection: &Connection) -> Result<()> {
+        for msg in &connection.receiver {
+            match msg {
+                Message::Request(req) => {
+                    if connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    let resp = self.handle_request(req);
+                    connection.sender.send(Message::Response(resp))?;
+                }
+                Message::Notification(not) => self.handle_notification(connection, not)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, req: Request) -> Response {
+        match req.method.as_str() {
+            HoverRequest::METHOD => {
+                respond::<HoverParams, _>(req, |params| {
+                    let pos = params.text_document_position_params;
+                    self.hover(&pos.text_document.uri, pos.position)
+                })
+            }
+            GotoDefinition::METHOD => {
+                respond::<GotoDefinitionParams, _>(req, |params| {
+                    let pos = params.text_document_position_params;
+                    self.goto_definition(&pos.text_document.uri, pos.position)
+                })
+            }
+            _ => {
+                Response::new_err(
+                    req.id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request `{}`", req.method),
+                )
+            }
+        }
+    }
+
+    fn handle_notification(&mut self, connection: &Connection, not: Notification) -> Result<()> {
+        match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(not.params)?;
+                let uri = params.text_document.uri;
+                self.docs.insert(uri.clone(), params.text_document.text);
+                if !self.trace.has_file(&uri) {
+                    self.check(connection, &uri)?;
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = serde_json::from_value(not.params)?;
+                // We only ask for full document sync so the last change has the entire text
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.docs.insert(params.text_document.uri, change.text);
+                }
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams = serde_json::from_value(not.params)?;
+                self.check(connection, &params.text_document.uri)?;
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(not.params)?;
+                self.docs.remove(&params.text_document.uri);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Runs flux on `uri` publishing diagnostics and reloading the checker trace and references
+    fn check(&mut self, connection: &Connection, uri: &Url) -> Result<()> {
+        let Ok(path) = uri.to_file_path() else { return Ok(()) };
+        if path.extension().is_none_or(|ext| ext != "rs") {
+            return Ok(());
+        }
+        let mut diagnostics = check::run_cargo_flux(&self.root, &self.log_dir, &path)?;
+
+        // Always publish diagnostics for the checked file to clear errors that were fixed. Other
+        // files are not checked by flux so we only update them if rustc reported something.
+        diagnostics.entry(uri.clone()).or_default();
+        for (uri, diagnostics) in diagnostics {
+            publish_diagnostics(connection, uri, diagnostics)?;
+        }
+
+        // The trace only contains information for the checked file
+        self.trace.extend(Trace::load(&self.log_dir, &self.root));
+        self.refs
+            .extend(References::load(&self.log_dir, &self.root));
+        Ok(())
+    }
+
+    fn hover(&self, uri: &Url, pos: lsp_types::Position) -> Option<Hover> {
+        let line = self.docs.get(uri)?.lines().nth(pos.line as usize)?;
+        let info = self.trace.lookup(
+            uri,
+            pos.line + 1,
+            starts_line(line, char_col(line, pos.character)),
+        )?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: info.to_markdown(),
+            }),
+            range: None,
+        })
+    }
+
+    fn goto_definition(
+        &self,
+        uri: &Url,
+        pos: lsp_types::Position,
+    ) -> Option<GotoDefinitionResponse> {
+        let line = self.docs.get(uri)?.lines().nth(pos.line as usize)?;
+        let locations = self
+            .refs
+            .lookup(uri, pos.line, char_col(line, pos.character));
+        if locations.is_empty() {
+            None
+        } else {
+            Some(GotoDefinitionResponse::Array(locations))
+        }
+    }
+}
+
+/// Whether the cursor is before the first non-whitespace character in the line. We show the
+/// information *before* the first statement in the line in that case and the information *after*
+/// the last statement otherwise.
+fn starts_line(line: &str, col: usize) -> bool {
+    line.chars().take(col).all(char::is_whitespace)
+}
+
+/// Converts the character column `col` in `line` into a column counting UTF-16 code units.
+fn utf16_col(line: &str, col: usize) -> u32 {
+    line.chars().take(col).map(|c| c.len_utf16() as u32).sum()
+}
+
+/// Converts the column `utf16` counting UTF-16 code units in `line` into a character column. A
+/// column in the middle of a character is rounded down.
+fn char_col(line: &str, utf16: u32) -> usize {
+    let mut units = 0;
+    line.chars()
+        .take_while(|c| {
+            units += c.len_utf16() as u32;
+            units <= utf16
+        })
+        .count()
+}
+
+fn respond<P, R>(req: Request, f: impl FnOnce(P) -> R) -> Response
+where
+    P: serde::de::DeserializeOwned,
+    R: serde::Serialize,
+{
+    let id: RequestId = req.id;
+    match serde_json::from_value(req.params) {
+        Ok(params) => Response::new_ok(id, f(params)),
+        Err(err) => {
+            Response::new_err(id, lsp_server::ErrorCode::InvalidParams as i32, err.to_string())
+        }
+    }
+}
+
+fn publish_diagnostics(
+    connection: &Connection,
+    uri: Url,
+    diagnostics: Vec<lsp_types::Diagnostic>,
+) -> Result<()> {
+    let params = PublishDiagnosticsParams { uri, diagnostics, version: None };
+    let not = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+    connection.sender.send(Message::Notification(not))?;
+    Ok(())
+}
+
+fn file_uri(root: &Path, file: impl AsRef<Path>) -> Option<Url> {
+    Url::from_file_path(root.join(file)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_round_trip_through_utf16() {
+        let line = "let λ = \"😀\"; x";
+        // `λ` is one code unit and `😀` two
+        assert_eq!(utf16_col(line, 5), 5);
+        assert_eq!(utf16_col(line, 11), 12);
+        assert_eq!(char_col(line, 12), 11);
+        assert_eq!(char_col(line, utf16_col(line, 14)), 14);
+        // A column in the middle of `😀` and one past the end of the line
+        assert_eq!(char_col(line, 10), 9);
+        assert_eq!(char_col(line, 100), line.chars().count());
+    }
+
+    #[test]
+    fn cursor_before_first_statement() {
+        assert!(starts_line("    x += 1;", 4));
+        assert!(!starts_line("    x += 1;", 5));
+    }
+}
@@ -0,0 +1,187 @@
+//! Reading the checker trace dumped by flux when `FLUX_DUMP_CHECKER_TRACE=1`.
+//!
+//! Flux writes the trace of each checked item to `<crate>.<item>.trace.json` in the log directory.
+//! The trace records the refinement context and the type environment before and after checking each
+//! statement. We only deserialize the parts of the schema (defined in `flux_common::trace`) that we
+//! need.
+
+use std::{collections::HashMap, fmt::Write, fs, path::Path};
+
+use itertools::Itertools;
+use lsp_types::Url;
+use serde::Deserialize;
+
+use super::file_uri;
+
+/// Must be kept in sync with `flux_common::trace::TRACE_VERSION`
+pub(super) const TRACE_VERSION: u32 = 1;
+
+/// Must be kept in sync with `flux_common::trace::TRACE_EXT`
+const TRACE_EXT: &str = ".trace.json";
+
+#[derive(Default)]
+pub(super) struct Trace {
+    files: HashMap<Url, FileTrace>,
+}
+
+#[derive(Default)]
+struct FileTrace {
+    /// Information *before* the first statement in a line
+    start: HashMap<u32, StatementInfo>,
+    /// Information *after* the last statement in a line
+    end: HashMap<u32, StatementInfo>,
+}
+
+#[derive(Deserialize)]
+struct FnTrace {
+    version: u32,
+    basic_blocks: Vec<BasicBlockTrace>,
+}
+
+#[derive(Deserialize)]
+struct BasicBlockTrace {
+    statements: Vec<StatementTrace>,
+}
+
+#[derive(Deserialize)]
+struct StatementTrace {
+    span: Option<SpanTrace>,
+    start: StatementInfo,
+    end: Option<StatementInfo>,
+}
+
+#[derive(Deserialize)]
+struct SpanTrace {
+    file: String,
+    end_line: u32,
+}
+
+/// The refinement context and the type environment at a program point
+#[derive(Deserialize)]
+pub(super) struct StatementInfo {
+    rcx: RcxTrace,
+    env: Vec<TypeEnvBind>,
+}
+
+#[derive(Deserialize)]
+struct RcxTrace {
+    bindings: Vec<RcxBind>,
+    exprs: Vec<NestedString>,
+    kvars: Vec<NestedString>,
+}
+
+#[derive(Deserialize)]
+struct RcxBind {
+    name: String,
+    sort: String,
+}
+
+#[derive(Deserialize)]
+struct TypeEnvBind {
+    name: Option<String>,
+    ty: NestedString,
+}
+
+#[derive(Deserialize)]
+struct NestedString {
+    text: String,
+}
+
+impl Trace {
+    /// Loads the traces in `log_dir`. Files in the trace are relative to `root`. Traces that cannot
+    /// be parsed or were written with a different version of the schema are skipped.
+    pub(super) fn load(log_dir: &Path, root: &Path) -> Self {
+        let mut trace = Trace::default();
+        let Ok(entries) = fs::read_dir(log_dir) else { return trace };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(TRACE_EXT))
+            {
+                continue;
+            }
+            let Ok(contents) = fs::read_to_string(&path) else { continue };
+            let Ok(fn_trace) = serde_json::from_str::<FnTrace>(&contents) else { continue };
+            if fn_trace.version != TRACE_VERSION {
+                continue;
+            }
+            trace.add_fn_trace(root, fn_trace);
+        }
+        trace
+    }
+
+    fn add_fn_trace(&mut self, root: &Path, fn_trace: FnTrace) {
+        for stmt in fn_trace
+            .basic_blocks
+            .into_iter()
+            .flat_map(|bb| bb.statements)
+        {
+            let Some(span) = stmt.span else { continue };
+            let Some(uri) = file_uri(root, &span.file) else { continue };
+            let file_trace = self.files.entry(uri).or_default();
+            file_trace.start.entry(span.end_line).or_insert(stmt.start);
+            if let Some(end) = stmt.end {
+                file_trace.end.insert(span.end_line, end);
+            }
+        }
+    }
+
+    /// Replaces the information for every file in `other`
+    pub(super) fn extend(&mut self, other: Trace) {
+        self.files.extend(other.files);
+    }
+
+    pub(super) fn has_file(&self, uri: &Url) -> bool {
+        self.files.contains_key(uri)
+    }
+
+    /// Returns the information at a (1-based) `line` in `uri`, either before the first statement in
+    /// the line if `at_start` is true or after the last one otherwise.
+    pub(super) fn lookup(&self, uri: &Url, line: u32, at_start: bool) -> Option<&StatementInfo> {
+        let file_trace = self.files.get(uri)?;
+        if at_start {
+            file_trace.start.get(&line)
+        } else {
+            file_trace.end.get(&line)
+        }
+    }
+}
+
+impl StatementInfo {
+    pub(super) fn to_markdown(&self) -> String {
+        let mut s = String::new();
+
+        s.push_str("**Values**\n```\n");
+        for (sort, binds) in &self.rcx.bindings.iter().chunk_by(|bind| &bind.sort) {
+            let _ = writeln!(s, "{}: {sort}", binds.map(|bind| &bind.name).format(", "));
+        }
+        s.push_str("```\n");
+
+        if !self.rcx.exprs.is_empty() {
+            s.push_str("**Constraints**\n```\n");
+            for expr in &self.rcx.exprs {
+                let _ = writeln!(s, "{}", expr.text);
+            }
+            s.push_str("```\n");
+        }
+
+        if !self.rcx.kvars.is_empty() {
+            s.push_str("**KVars**\n```\n");
+            for kvar in &self.rcx.kvars {
+                let _ = writeln!(s, "{}", kvar.text);
+            }
+            s.push_str("```\n");
+        }
+
+        s.push_str("**Types**\n```\n");
+        for bind in &self.env {
+            if let Some(name) = &bind.name {
+                let _ = writeln!(s, "{name}: {}", bind.ty.text);
+            }
+        }
+        s.push_str("```");
+        s
+    }
+}
@@ -72,12 +72,19 @@ pub use crate::_basic_block_start as basic_block_start;
 macro_rules! _statement{
     ($pos:literal, $stmt:expr, $rcx:expr, $env:expr, $span:expr, $checker:expr) => {{
         if config::dump_checker_trace() {
-            let ck = $checker;
-            let genv = ck.genv;
-            let local_names = &ck.body.local_names;
-            let rcx_json = RefineCtxtTrace::new(genv, $rcx);
-            let env_json = TypeEnvTrace::new(genv, local_names, $env);
-            tracing::info!(event = concat!("statement_", $pos), stmt = ?$stmt, stmt_span = ?$span, rcx = ?$rcx, env = ?$env, rcx_json = ?rcx_json, env_json = ?env_json)
+            let genv = $checker.genv;
+            let rcx_trace = $rcx.trace(genv);
+            let env_trace = $env.trace(genv, &$checker.body.local_names);
+            tracing::info!(event = concat!("statement_", $pos), stmt = ?$stmt, stmt_span = ?$span, rcx = ?$rcx, env = ?$env, rcx_json = %$crate::trace::to_json(&rcx_trace), env_json = %$crate::trace::to_json(&env_trace));
+            if let Some(trace) = &mut $checker.trace {
+                let point = $crate::trace::ProgramPointTrace { rcx: rcx_trace, env: env_trace };
+                if $pos == "start" {
+                    let span = $crate::trace::SpanTrace::new(genv.tcx(), $span);
+                    trace.statement_start(format!("{:?}", $stmt), span, point);
+                } else {
+                    trace.statement_end(point);
+                }
+            }
         }
     }};
 }
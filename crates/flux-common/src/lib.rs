@@ -1,6 +1,9 @@
 #![feature(closure_track_caller, never_type, rustc_private, try_blocks, try_trait_v2)]
 
 extern crate rustc_borrowck;
+// Tests are linked into an executable, which needs the compiler crates from `rustc_driver`
+#[cfg(test)]
+extern crate rustc_driver as _;
 extern crate rustc_errors;
 
 extern crate rustc_hir;
@@ -13,6 +16,7 @@ pub mod dbg;
 pub mod index;
 pub mod iter;
 pub mod mir_storage;
+pub mod trace;
 
 pub mod bug;
 pub mod result;
@@ -9,19 +9,62 @@
 //! require the client to provide a witness: an instance of type `TyCtxt<'tcx>`
 //! that is used to show that the lifetime that the client provided is indeed
 //! `'tcx`.
+//!
+//! Bodies are stored when rustc runs the borrow checker on them. With incremental compilation,
+//! rustc doesn't run the borrow checker on bodies whose result is still valid, so if a body wasn't
+//! stored we compute it when it's retrieved. Computing a body requires the result of
+//! `mir_promoted`, which rustc steals in later queries, so the driver overrides these queries to
+//! compute and store the body before it is stolen (see [`is_mir_body_computed`]).
 
-use std::{cell::RefCell, collections::HashMap, thread_local};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    thread_local,
+};
 
-use rustc_borrowck::consumers::BodyWithBorrowckFacts;
+use flux_config as config;
+use rustc_borrowck::consumers::{BodyWithBorrowckFacts, ConsumerOptions};
 use rustc_hir::def_id::LocalDefId;
 use rustc_middle::ty::TyCtxt;
 
-use crate::bug;
+use crate::dbg;
 
 thread_local! {
     pub static SHARED_STATE:
         RefCell<HashMap<LocalDefId, BodyWithBorrowckFacts<'static>>> =
         RefCell::new(HashMap::new());
+
+    /// Bodies that were computed, including the ones already retrieved
+    static COMPUTED: RefCell<HashSet<LocalDefId>> = RefCell::new(HashSet::new());
+}
+
+/// Whether the body of `def_id` was already computed, either when rustc ran the borrow checker on it
+/// or when it was retrieved. If it wasn't, it must be computed and stored before rustc steals the
+/// result of `mir_promoted`.
+pub fn is_mir_body_computed(def_id: LocalDefId) -> bool {
+    COMPUTED.with(|computed| computed.borrow().contains(&def_id))
+}
+
+/// Runs the borrow checker on the body of `def_id` returning the body along with the facts we need.
+pub fn compute_mir_body(tcx: TyCtxt, def_id: LocalDefId) -> BodyWithBorrowckFacts {
+    COMPUTED.with(|computed| computed.borrow_mut().insert(def_id));
+    let body_with_facts = rustc_borrowck::consumers::get_body_with_borrowck_facts(
+        tcx,
+        def_id,
+        ConsumerOptions::RegionInferenceContext,
+    );
+
+    if config::dump_mir() {
+        rustc_middle::mir::pretty::write_mir_fn(
+            tcx,
+            &body_with_facts.body,
+            &mut |_, _| Ok(()),
+            &mut dbg::writer_for_item(tcx, def_id.to_def_id(), "mir").unwrap(),
+            rustc_middle::mir::pretty::PrettyPrintMirOptions::from_cli(tcx),
+        )
+        .unwrap();
+    }
+    body_with_facts
 }
 
 /// # Safety
@@ -43,17 +86,19 @@ pub unsafe fn store_mir_body<'tcx>(
 /// # Safety
 ///
 /// See the module level comment.
-#[expect(clippy::needless_lifetimes, reason = "we want to be very explicit about lifetimes here")]
 pub unsafe fn retrieve_mir_body<'tcx>(
-    _tcx: TyCtxt<'tcx>,
+    tcx: TyCtxt<'tcx>,
     def_id: LocalDefId,
 ) -> BodyWithBorrowckFacts<'tcx> {
-    let body_with_facts: BodyWithBorrowckFacts<'static> = SHARED_STATE.with(|state| {
-        match state.borrow_mut().remove(&def_id) {
-            Some(body) => body,
-            None => bug!("retrieve_mir_body: panic on {def_id:?}"),
+    let body_with_facts: Option<BodyWithBorrowckFacts<'static>> =
+        SHARED_STATE.with(|state| state.borrow_mut().remove(&def_id));
+    match body_with_facts {
+        // SAFETY: See the module level comment.
+        Some(body_with_facts) => {
+            std::mem::transmute::<BodyWithBorrowckFacts<'static>, BodyWithBorrowckFacts<'tcx>>(
+                body_with_facts,
+            )
         }
-    });
-    // SAFETY: See the module level comment.
-    std::mem::transmute(body_with_facts)
+        None => compute_mir_body(tcx, def_id),
+    }
 }
@@ -0,0 +1,336 @@
+//! A stable format for the checker trace.
+//!
+//! When `dump_checker_trace` is enabled, the checker records, for every function it checks, the
+//! refinement context and the type environment before and after each statement. The trace for a
+//! function is written as json to a dedicated file in the log directory named
+//! `<crate>.<item>.trace.json` (see [`dump_fn_trace`]). The types in this module define the schema
+//! of that file. Any change to the schema must bump [`TRACE_VERSION`] so external tools can detect
+//! when they are reading a format they don't understand.
+use std::io::{self, Write};
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+use serde::{Deserialize, Serialize};
+
+use crate::dbg;
+
+/// The version of the trace schema.
+pub const TRACE_VERSION: u32 = 1;
+
+/// Extension of the file containing the trace for an item.
+pub const TRACE_EXT: &str = "trace.json";
+
+/// Extension of the file containing the references to flux items of a crate.
+pub const REFS_EXT: &str = "refs.json";
+
+/// The references to spec functions and qualifiers in the signatures of a crate, written to
+/// `<crate>.refs.json` in the log directory together with the trace. Editors use it to go to the
+/// definition of the item a name was resolved to.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefsTrace {
+    pub version: u32,
+    pub refs: Vec<RefTrace>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefTrace {
+    /// The span of the name at the reference
+    pub span: SpanTrace,
+    /// The span of the name in the definition of the item it refers to
+    pub def: SpanTrace,
+}
+
+/// The trace of a function-like item, i.e., a function, a closure or a coroutine.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FnTrace {
+    pub version: u32,
+    /// The path of the item as printed by `def_path_str`
+    pub def_path: String,
+    pub span: Option<SpanTrace>,
+    /// The basic blocks in the order they were checked.
+    pub basic_blocks: Vec<BasicBlockTrace>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BasicBlockTrace {
+    /// The index of the basic block in the MIR body or `None` for ghost statements executed at the
+    /// entry of the function before the first basic block.
+    pub bb: Option<u32>,
+    /// Statements in the order they were checked. This includes ghost statements, which are
+    /// attributed to the basic block being checked when they are executed, e.g., ghost statements
+    /// on the edge between two basic blocks are attributed to the source block.
+    pub statements: Vec<StatementTrace>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatementTrace {
+    /// The statement as printed with its debug format
+    pub stmt: String,
+    pub span: Option<SpanTrace>,
+    /// The state right before checking the statement
+    pub start: ProgramPointTrace,
+    /// The state right after checking the statement or `None` if checking the statement failed.
+    pub end: Option<ProgramPointTrace>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProgramPointTrace {
+    pub rcx: RefineCtxtTrace,
+    pub env: TypeEnvTrace,
+}
+
+/// A very explicit representation of a refinement context.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefineCtxtTrace {
+    /// Refinement variables in scope
+    pub bindings: Vec<RcxBind>,
+    /// Assumptions in scope that are not kvars
+    pub exprs: Vec<NestedString>,
+    /// Assumptions in scope that are kvars
+    pub kvars: Vec<NestedString>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RcxBind {
+    pub name: String,
+    pub sort: String,
+}
+
+/// A very explicit representation of a type environment.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TypeEnvTrace(pub Vec<TypeEnvBind>);
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TypeEnvBind {
+    pub local: LocInfo,
+    /// The name of the local in the source code if it has one
+    pub name: Option<String>,
+    pub kind: String,
+    pub ty: NestedString,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum LocInfo {
+    Local(String),
+    Var(String),
+}
+
+/// A pretty printed value that can be expanded into its components, e.g., the fields of a struct.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NestedString {
+    pub text: String,
+    pub key: Option<String>,
+    pub children: Option<Vec<NestedString>>,
+}
+
+/// A source span with 1-based lines and columns.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SpanTrace {
+    /// The file as passed to the compiler
+    pub file: String,
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+impl FnTrace {
+    pub fn new(tcx: TyCtxt, def_id: DefId) -> Self {
+        FnTrace {
+            version: TRACE_VERSION,
+            def_path: tcx.def_path_str(def_id),
+            span: SpanTrace::new(tcx, tcx.def_span(def_id)),
+            basic_blocks: vec![],
+        }
+    }
+
+    /// Starts recording statements for the basic block `bb`
+    pub fn enter_basic_block(&mut self, bb: u32) {
+        self.basic_blocks
+            .push(BasicBlockTrace { bb: Some(bb), statements: vec![] });
+    }
+
+    pub fn statement_start(
+        &mut self,
+        stmt: String,
+        span: Option<SpanTrace>,
+        start: ProgramPointTrace,
+    ) {
+        if self.basic_blocks.is_empty() {
+            self.basic_blocks
+                .push(BasicBlockTrace { bb: None, statements: vec![] });
+        }
+        let bb = self.basic_blocks.last_mut().unwrap();
+        bb.statements
+            .push(StatementTrace { stmt, span, start, end: None });
+    }
+
+    pub fn statement_end(&mut self, end: ProgramPointTrace) {
+        if let Some(stmt) = self
+            .basic_blocks
+            .last_mut()
+            .and_then(|bb| bb.statements.last_mut())
+        {
+            stmt.end = Some(end);
+        }
+    }
+}
+
+impl SpanTrace {
+    pub fn new(tcx: TyCtxt, span: Span) -> Option<Self> {
+        if span.is_dummy() {
+            return None;
+        }
+        let sm = tcx.sess.source_map();
+        let lo = sm.lookup_char_pos(span.lo());
+        let hi = sm.lookup_char_pos(span.hi());
+        Some(SpanTrace {
+            file: sm.filename_for_diagnostics(&lo.file.name).to_string(),
+            start_line: lo.line,
+            start_col: lo.col.0 + 1,
+            end_line: hi.line,
+            end_col: hi.col.0 + 1,
+        })
+    }
+}
+
+/// Serializes `val` as json. Used to emit trace values as fields of tracing events.
+pub fn to_json<T: Serialize>(val: &T) -> String {
+    serde_json::to_string(val).unwrap_or_default()
+}
+
+/// Writes the trace for `def_id` to `<crate>.<item>.trace.json` in the log directory.
+pub fn dump_fn_trace(tcx: TyCtxt, def_id: DefId, trace: &FnTrace) -> io::Result<()> {
+    let mut writer = dbg::writer_for_item(tcx, def_id, TRACE_EXT)?;
+    serde_json::to_writer_pretty(&mut writer, trace)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn span(line: usize) -> SpanTrace {
+        SpanTrace {
+            file: "src/lib.rs".to_string(),
+            start_line: line,
+            start_col: 5,
+            end_line: line,
+            end_col: 11,
+        }
+    }
+
+    fn nested(text: &str) -> NestedString {
+        NestedString { text: text.to_string(), key: None, children: None }
+    }
+
+    fn point(env: Vec<TypeEnvBind>) -> ProgramPointTrace {
+        ProgramPointTrace {
+            rcx: RefineCtxtTrace {
+                bindings: vec![RcxBind { name: "a0".to_string(), sort: "int".to_string() }],
+                exprs: vec![nested("a0 > 0")],
+                kvars: vec![],
+            },
+            env: TypeEnvTrace(env),
+        }
+    }
+
+    /// Tools read the trace with their own definition of the schema, so any change to the json
+    /// below must bump [`TRACE_VERSION`].
+    #[test]
+    fn fn_trace_schema() {
+        let bind = TypeEnvBind {
+            local: LocInfo::Local("_1".to_string()),
+            name: Some("x".to_string()),
+            kind: "Local".to_string(),
+            ty: NestedString {
+                text: "i32[a0]".to_string(),
+                key: Some("x".to_string()),
+                children: Some(vec![nested("a0")]),
+            },
+        };
+        let trace = FnTrace {
+            version: TRACE_VERSION,
+            def_path: "foo".to_string(),
+            span: Some(span(1)),
+            basic_blocks: vec![
+                BasicBlockTrace { bb: None, statements: vec![] },
+                BasicBlockTrace {
+                    bb: Some(0),
+                    statements: vec![StatementTrace {
+                        stmt: "_0 = _1".to_string(),
+                        span: Some(span(2)),
+                        start: point(vec![bind]),
+                        end: None,
+                    }],
+                },
+            ],
+        };
+        let span_json = |line: usize| {
+            json!({
+                "file": "src/lib.rs",
+                "start_line": line,
+                "start_col": 5,
+                "end_line": line,
+                "end_col": 11,
+            })
+        };
+        let expected = json!({
+            "version": 1,
+            "def_path": "foo",
+            "span": span_json(1),
+            "basic_blocks": [
+                { "bb": null, "statements": [] },
+                {
+                    "bb": 0,
+                    "statements": [{
+                        "stmt": "_0 = _1",
+                        "span": span_json(2),
+                        "start": {
+                            "rcx": {
+                                "bindings": [{ "name": "a0", "sort": "int" }],
+                                "exprs": [{ "text": "a0 > 0", "key": null, "children": null }],
+                                "kvars": [],
+                            },
+                            "env": [{
+                                "local": { "Local": "_1" },
+                                "name": "x",
+                                "kind": "Local",
+                                "ty": {
+                                    "text": "i32[a0]",
+                                    "key": "x",
+                                    "children": [{ "text": "a0", "key": null, "children": null }],
+                                },
+                            }],
+                        },
+                        "end": null,
+                    }],
+                },
+            ],
+        });
+
+        let value = serde_json::to_value(&trace).unwrap();
+        assert_eq!(value, expected);
+
+        let round_trip: FnTrace = serde_json::from_value(value).unwrap();
+        assert_eq!(serde_json::to_value(&round_trip).unwrap(), expected);
+    }
+
+    #[test]
+    fn refs_trace_schema() {
+        let refs = RefsTrace {
+            version: TRACE_VERSION,
+            refs: vec![RefTrace { span: span(3), def: span(1) }],
+        };
+        let value = serde_json::to_value(&refs).unwrap();
+        assert_eq!(value["version"], json!(1));
+        assert_eq!(value["refs"][0]["span"]["start_line"], json!(3));
+        assert_eq!(value["refs"][0]["def"]["start_line"], json!(1));
+
+        let round_trip: RefsTrace = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&round_trip).unwrap(), value);
+    }
+}
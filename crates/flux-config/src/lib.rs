@@ -1,14 +1,23 @@
-use std::{io::Read, path::PathBuf, sync::LazyLock};
+mod lints;
 
-use config::{Environment, File};
+use std::{
+    env,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{LazyLock, OnceLock},
+};
+
+use config::{Environment, File, FileFormat};
+pub use lints::{Lint, LintLevel, LintLevels, ALL_LINTS};
 use serde::Deserialize;
 pub use toml::Value;
 
 const FLUX_ENV_VAR_PREFIX: &str = "FLUX";
 const FLUX_CONFIG_ENV_VAR: &str = "FLUX_CONFIG";
 
-pub fn check_def() -> &'static str {
-    &CONFIG.check_def
+/// Selectors for the definitions to check. Every definition is checked if empty.
+pub fn check_def() -> &'static [DefSelector] {
+    &CONFIG.check_def.selectors
 }
 
 pub fn dump_timings() -> bool {
@@ -67,10 +76,57 @@ pub fn catch_bugs() -> bool {
     CONFIG.catch_bugs
 }
 
+/// Whether to write a report of the verification coverage of each checked crate to the log directory
+pub fn coverage() -> bool {
+    CONFIG.coverage
+}
+
+/// Whether to explain every refinement type error by listing the assumptions leading to it. It
+/// can be enabled for a single function with `#[flux::explain]`.
+pub fn explain() -> bool {
+    CONFIG.explain
+}
+
+/// Standalone files with extern specs to include in every checked crate. Relative paths are
+/// resolved against the directory of the file setting them, i.e., the config file or the manifest of
+/// the package, or the current directory when set with an environment variable.
+pub fn extern_specs() -> &'static [PathBuf] {
+    &EXTERN_SPECS
+}
+
+static EXTERN_SPECS: LazyLock<Vec<PathBuf>> = LazyLock::new(|| {
+    let paths = CONFIG.extern_specs.paths.as_deref().unwrap_or_default();
+    let set_by_env = env::var(format!("{FLUX_ENV_VAR_PREFIX}_EXTERN_SPECS"))
+        .is_ok_and(|value| !value.is_empty());
+    let set_by_package = PACKAGE_CONFIG
+        .get()
+        .is_some_and(|config| config.contains_key("extern_specs"));
+    let base = if set_by_env {
+        None
+    } else if set_by_package {
+        env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from)
+    } else {
+        CONFIG_PATH
+            .as_ref()
+            .and_then(|path| path.parent())
+            .map(Path::to_path_buf)
+    };
+    match base {
+        Some(base) => paths.iter().map(|path| base.join(path)).collect(),
+        None => paths.to_vec(),
+    }
+});
+
+/// Lint levels set in the `[lints]` table of the config file
+pub fn lint_levels() -> &'static LintLevels {
+    &CONFIG.lints
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct CrateConfig {
     pub check_overflow: bool,
     pub scrape_quals: bool,
+    pub prelude: bool,
 }
 
 #[derive(Deserialize)]
@@ -84,12 +140,17 @@ struct Config {
     dump_mir: bool,
     catch_bugs: bool,
     pointer_width: PointerWidth,
-    check_def: String,
+    check_def: DefSelectors,
     check_files: Paths,
+    extern_specs: Paths,
+    coverage: bool,
+    explain: bool,
     cache: bool,
     cache_file: String,
     check_overflow: bool,
     scrape_quals: bool,
+    #[serde(default)]
+    lints: LintLevels,
 }
 
 #[derive(Default)]
@@ -123,6 +184,67 @@ impl<'de> Deserialize<'de> for Paths {
     }
 }
 
+/// Selects definitions to check with `check_def`
+#[derive(Debug, PartialEq, Eq)]
+pub enum DefSelector {
+    /// Definitions whose path matches a pattern where `*` matches any sequence of characters, e.g.,
+    /// `RVec::<T>::push`, `<* as Iterator>::next` or `*push*`.
+    Path(String),
+    /// The innermost definition containing a line, written `file:line`. The file is matched against
+    /// the end of the path of the file of the definition, so `lib.rs:10` matches `src/lib.rs`.
+    Location { file: PathBuf, line: usize },
+}
+
+impl DefSelector {
+    fn parse(selector: &str) -> DefSelector {
+        let location = selector
+            .rsplit_once(':')
+            .filter(|(file, _)| file.ends_with(".rs"))
+            .and_then(|(file, line)| Some((file, line.parse().ok()?)));
+        match location {
+            Some((file, line)) => DefSelector::Location { file: PathBuf::from(file), line },
+            None => DefSelector::Path(selector.to_string()),
+        }
+    }
+
+    /// Whether `path` matches the selector. Always false for a location.
+    pub fn matches_path(&self, path: &str) -> bool {
+        match self {
+            DefSelector::Path(pattern) => glob_match(pattern.as_bytes(), path.as_bytes()),
+            DefSelector::Location { .. } => false,
+        }
+    }
+}
+
+/// Matches `text` against `pattern` where `*` matches any sequence of characters
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+        Some((c, rest)) => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+#[derive(Default)]
+struct DefSelectors {
+    selectors: Vec<DefSelector>,
+}
+
+impl<'de> Deserialize<'de> for DefSelectors {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let selectors = String::deserialize(deserializer)?
+            .split(",")
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(DefSelector::parse)
+            .collect();
+        Ok(DefSelectors { selectors })
+    }
+}
+
 #[derive(Copy, Clone, Deserialize)]
 #[serde(try_from = "u8")]
 pub enum PointerWidth {
@@ -152,37 +274,88 @@ impl TryFrom<u8> for PointerWidth {
 }
 
 static CONFIG: LazyLock<Config> = LazyLock::new(|| {
-    fn build() -> Result<Config, config::ConfigError> {
-        let mut config_builder = config::Config::builder()
-            .set_default("driver_path", None::<String>)?
-            .set_default("log_dir", "./log/")?
-            .set_default("dump_constraint", false)?
-            .set_default("dump_checker_trace", false)?
-            .set_default("dump_timings", false)?
-            .set_default("dump_mir", false)?
-            .set_default("dump_fhir", false)?
-            .set_default("dump_rty", false)?
-            .set_default("catch_bugs", false)?
-            .set_default("check_asserts", "assume")?
-            .set_default("pointer_width", "64")?
-            .set_default("check_def", "")?
-            .set_default("check_files", "")?
-            .set_default("cache", false)?
-            .set_default("cache_file", "cache.json")?
-            .set_default("check_overflow", false)?
-            .set_default("scrape_quals", false)?;
-        // Config comes first, environment settings override it.
-        if let Some(config_path) = CONFIG_PATH.as_ref() {
-            config_builder = config_builder.add_source(File::from(config_path.clone()));
-        };
-        config_builder
-            .add_source(Environment::with_prefix(FLUX_ENV_VAR_PREFIX).ignore_empty(true))
-            .build()?
-            .try_deserialize()
-    }
-    build().unwrap()
+    // Fixes the config of the package, so setting it after this fails if it's not empty
+    let package_config = PACKAGE_CONFIG.get_or_init(toml::Table::new);
+    build_config(
+        CONFIG_PATH.as_deref(),
+        Some(package_config),
+        Environment::with_prefix(FLUX_ENV_VAR_PREFIX),
+    )
+    .unwrap()
 });
 
+/// Builds the config from the defaults, the config file, the config of the package and the
+/// environment, each overriding the previous ones.
+fn build_config(
+    config_path: Option<&Path>,
+    package_config: Option<&toml::Table>,
+    env: Environment,
+) -> Result<Config, config::ConfigError> {
+    let mut config_builder = config::Config::builder()
+        .set_default("driver_path", None::<String>)?
+        .set_default("log_dir", "./log/")?
+        .set_default("dump_constraint", false)?
+        .set_default("dump_checker_trace", false)?
+        .set_default("dump_timings", false)?
+        .set_default("dump_mir", false)?
+        .set_default("dump_fhir", false)?
+        .set_default("dump_rty", false)?
+        .set_default("catch_bugs", false)?
+        .set_default("check_asserts", "assume")?
+        .set_default("pointer_width", "64")?
+        .set_default("check_def", "")?
+        .set_default("check_files", "")?
+        .set_default("extern_specs", "")?
+        .set_default("coverage", false)?
+        .set_default("explain", false)?
+        .set_default("cache", false)?
+        .set_default("cache_file", "cache.json")?
+        .set_default("check_overflow", false)?
+        .set_default("scrape_quals", false)?;
+    if let Some(config_path) = config_path {
+        config_builder = config_builder.add_source(File::from(config_path));
+    };
+    if let Some(package_config) = package_config {
+        config_builder = config_builder
+            .add_source(File::from_str(&package_config.to_string(), FileFormat::Toml));
+    }
+    config_builder
+        .add_source(env.ignore_empty(true))
+        .build()?
+        .try_deserialize()
+}
+
+static PACKAGE_CONFIG: OnceLock<toml::Table> = OnceLock::new();
+
+/// Sets the config of the package being checked, i.e., the `[package.metadata.flux]` table in its
+/// `Cargo.toml`. It takes precedence over the config file and is overridden by environment
+/// variables. Settings are read once per process, so the config can only be set before reading any
+/// setting and it cannot change afterwards.
+///
+/// # Errors
+///
+/// If a different config was already set, or `config` is not empty and a setting was already read.
+pub fn set_package_config(config: toml::Table) -> Result<(), PackageConfigError> {
+    let current = PACKAGE_CONFIG.get_or_init(|| config.clone());
+    if *current == config {
+        Ok(())
+    } else {
+        Err(PackageConfigError)
+    }
+}
+
+/// The config of the package could not be set because the config in use differs
+#[derive(Debug)]
+pub struct PackageConfigError;
+
+impl std::fmt::Display for PackageConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the config of flux was already set to a different value")
+    }
+}
+
+impl std::error::Error for PackageConfigError {}
+
 pub static CONFIG_PATH: LazyLock<Option<PathBuf>> = LazyLock::new(|| {
     if let Ok(file) = std::env::var(FLUX_CONFIG_ENV_VAR) {
         return Some(PathBuf::from(file));
@@ -216,6 +389,101 @@ pub static CONFIG_FILE: LazyLock<Value> = LazyLock::new(|| {
 
 impl Default for CrateConfig {
     fn default() -> Self {
-        Self { check_overflow: check_overflow(), scrape_quals: scrape_quals() }
+        Self { check_overflow: check_overflow(), scrape_quals: scrape_quals(), prelude: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use super::*;
+
+    fn env_vars(vars: &[(&str, &str)]) -> Environment {
+        let vars: HashMap<_, _> = vars
+            .iter()
+            .map(|&(var, value)| (var.to_string(), value.to_string()))
+            .collect();
+        Environment::with_prefix(FLUX_ENV_VAR_PREFIX).source(Some(vars))
+    }
+
+    fn package_config(toml: &str) -> toml::Table {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn defaults_without_sources() {
+        let config = build_config(None, None, env_vars(&[])).unwrap();
+        assert_eq!(config.log_dir, PathBuf::from("./log/"));
+        assert!(!config.cache);
+        assert!(config.extern_specs.paths.is_none());
+    }
+
+    #[test]
+    fn package_config_overrides_config_file() {
+        let dir = env::temp_dir().join(format!("flux-config-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("flux.toml");
+        fs::write(&config_path, "cache = true\ndump_mir = true\nlog_dir = \"file-log\"\n").unwrap();
+
+        let package = package_config("cache = false\nlog_dir = \"package-log\"");
+        let config = build_config(Some(&config_path), Some(&package), env_vars(&[])).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!config.cache);
+        assert!(config.dump_mir);
+        assert_eq!(config.log_dir, PathBuf::from("package-log"));
+    }
+
+    #[test]
+    fn env_overrides_package_config() {
+        let package = package_config("cache = true\nexplain = true\ncheck_def = \"foo\"");
+        let env = env_vars(&[("FLUX_CACHE", "false"), ("FLUX_CHECK_DEF", "bar, baz")]);
+        let config = build_config(None, Some(&package), env).unwrap();
+
+        assert!(!config.cache);
+        assert!(config.explain);
+        let selectors = &config.check_def.selectors;
+        assert_eq!(selectors.len(), 2);
+        assert!(selectors[0].matches_path("bar"));
+        assert!(selectors[1].matches_path("baz"));
+    }
+
+    #[test]
+    fn empty_env_var_keeps_package_config() {
+        let package = package_config("extern_specs = \"specs/a.flux\"");
+        let config =
+            build_config(None, Some(&package), env_vars(&[("FLUX_EXTERN_SPECS", "")])).unwrap();
+        assert_eq!(config.extern_specs.paths, Some(vec![PathBuf::from("specs/a.flux")]));
+    }
+
+    #[test]
+    fn parse_def_selectors() {
+        assert_eq!(
+            DefSelector::parse("src/lib.rs:10"),
+            DefSelector::Location { file: PathBuf::from("src/lib.rs"), line: 10 }
+        );
+        assert_eq!(DefSelector::parse("foo::bar"), DefSelector::Path("foo::bar".to_string()));
+        assert_eq!(
+            DefSelector::parse("<S as Tr>::f"),
+            DefSelector::Path("<S as Tr>::f".to_string())
+        );
+        assert_eq!(DefSelector::parse("a.rs:x"), DefSelector::Path("a.rs:x".to_string()));
+        assert_eq!(DefSelector::parse("lib:10"), DefSelector::Path("lib:10".to_string()));
+    }
+
+    #[test]
+    fn glob_match_paths() {
+        let matches = |pattern: &str, text: &str| glob_match(pattern.as_bytes(), text.as_bytes());
+        assert!(matches("RVec::<T>::push", "RVec::<T>::push"));
+        assert!(!matches("RVec::<T>::push", "RVec::<T>::pop"));
+        assert!(matches("*push*", "RVec::<T>::push_all"));
+        assert!(matches("<* as Iterator>::next", "<Counter as Iterator>::next"));
+        assert!(!matches("<* as Iterator>::next", "<Counter as Iterator>::size_hint"));
+        assert!(matches("a*b*c", "abbc"));
+        assert!(!matches("a*b*c", "acb"));
+        assert!(matches("*", ""));
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
     }
 }
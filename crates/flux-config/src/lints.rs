@@ -0,0 +1,143 @@
+use std::{collections::HashMap, fmt};
+
+use serde::Deserialize;
+
+/// Refinement type errors whose level can be configured, either in the config file or with the
+/// `#[flux::allow]`, `#[flux::warn]` and `#[flux::deny]` attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lint {
+    Precondition,
+    Postcondition,
+    Assignment,
+    DivisionByZero,
+    Assertion,
+    Fold,
+    JoinPoint,
+    Overflow,
+    Invariant,
+    Unknown,
+}
+
+/// The name of the group containing all lints
+pub const ALL_LINTS: &str = "all";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// The error is not reported
+    Allow,
+    /// The error is reported as a warning
+    Warn,
+    /// The error is reported as an error. This is the default.
+    Deny,
+}
+
+/// A set of lint levels. A level for a specific lint takes precedence over the level for
+/// [`ALL_LINTS`].
+#[derive(Clone, Debug, Default)]
+pub struct LintLevels {
+    all: Option<LintLevel>,
+    lints: HashMap<Lint, LintLevel>,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 10] = [
+        Lint::Precondition,
+        Lint::Postcondition,
+        Lint::Assignment,
+        Lint::DivisionByZero,
+        Lint::Assertion,
+        Lint::Fold,
+        Lint::JoinPoint,
+        Lint::Overflow,
+        Lint::Invariant,
+        Lint::Unknown,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::Precondition => "precondition",
+            Lint::Postcondition => "postcondition",
+            Lint::Assignment => "assignment",
+            Lint::DivisionByZero => "division_by_zero",
+            Lint::Assertion => "assertion",
+            Lint::Fold => "fold",
+            Lint::JoinPoint => "join_point",
+            Lint::Overflow => "overflow",
+            Lint::Invariant => "invariant",
+            Lint::Unknown => "unknown",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl LintLevel {
+    pub fn name(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+impl LintLevels {
+    /// Sets the level for the lint or group with the given `name`. Returns `false` if there's no
+    /// lint or group with that name.
+    pub fn set(&mut self, name: &str, level: LintLevel) -> bool {
+        if name == ALL_LINTS {
+            self.all = Some(level);
+        } else if let Some(lint) = Lint::from_name(name) {
+            self.lints.insert(lint, level);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Returns the level for `lint` if it's set either explicitly or through [`ALL_LINTS`]
+    pub fn get(&self, lint: Lint) -> Option<LintLevel> {
+        self.lints.get(&lint).copied().or(self.all)
+    }
+
+    /// Adds all the levels in `other` overriding the ones already set
+    pub fn extend(&mut self, other: LintLevels) {
+        if other.all.is_some() {
+            self.all = other.all;
+        }
+        self.lints.extend(other.lints);
+    }
+}
+
+impl<'de> Deserialize<'de> for LintLevels {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut levels = LintLevels::default();
+        for (name, level) in HashMap::<String, LintLevel>::deserialize(deserializer)? {
+            if !levels.set(&name, level) {
+                return Err(serde::de::Error::custom(format!("unknown lint `{name}`")));
+            }
+        }
+        Ok(levels)
+    }
+}
@@ -7,9 +7,16 @@ desugar_invalid_alias_reft =
 desugar_int_too_large =
     integer literal is too large
 
+desugar_real_too_large =
+    real literal is too large
+
 desugar_unexpected_literal =
     unexpected literal
 
+desugar_invalid_const_arg =
+    invalid array length in refinement type
+    .label = expected an expression over literals, const items and const generics
+
 desugar_invalid_dot_var =
     unsupported field access in refinement
 
@@ -57,6 +64,10 @@ desugar_unresolved_path =
     cannot resolve `{$path}`
     .help = flux can only resolve a path if it is present in the definition being refined
 
+desugar_unknown_qualifier =
+    unknown qualifier `{$path}`
+    .label = not found in this crate or its dependencies
+
 desugar_unresolved_var =
     cannot find value `{$var}` in this scope
     .label = not found in this scope
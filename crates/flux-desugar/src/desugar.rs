@@ -6,6 +6,7 @@ use flux_errors::FluxSession;
 use flux_middle::{
     fhir::{self, lift::LiftCtxt, ExprRes, FhirId, FluxOwnerId, Res},
     global_env::GlobalEnv,
+    rty::Real,
     try_alloc_slice, MaybeExternId, ResolverOutput,
 };
 use flux_syntax::{
@@ -246,22 +247,6 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
         })
     }
 
-    fn desugar_trait_ref(
-        &mut self,
-        trait_ref: &surface::TraitRef,
-    ) -> Result<fhir::PolyTraitRef<'genv>> {
-        let fhir::QPath::Resolved(None, path) = self.desugar_qpath(None, &trait_ref.path)? else {
-            span_bug!(trait_ref.path.span, "desugar_alias_reft: unexpected qpath")
-        };
-        let span = path.span;
-        Ok(fhir::PolyTraitRef {
-            bound_generic_params: &[],
-            modifiers: fhir::TraitBoundModifier::None,
-            trait_ref: path,
-            span,
-        })
-    }
-
     fn desugar_refined_by(
         &mut self,
         refined_by: &surface::RefineParams,
@@ -516,14 +501,15 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
         }
 
         let qual_names = fn_spec.qual_names.as_ref().map_or(&[][..], |it| &it.names);
-        Ok((
-            generics,
-            fhir::FnSig {
-                header,
-                qualifiers: self.genv.alloc_slice(qual_names),
-                decl: self.genv.alloc(decl),
-            },
-        ))
+        let qualifiers = self
+            .genv
+            .alloc_slice_fill_iter(qual_names.iter().map(|path| {
+                fhir::QualifierName {
+                    def_id: self.resolver_output.qualifier_res_map[&path.node_id],
+                    span: path.span,
+                }
+            }));
+        Ok((generics, fhir::FnSig { header, qualifiers, decl: self.genv.alloc(decl) }))
     }
 
     fn desugar_fn_sig_refine_params(
@@ -739,7 +725,12 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
         }
 
         let generics = self.genv.tcx().generics_of(resolved_id);
-        let args = &path.last().args;
+        let args = path
+            .last()
+            .args
+            .iter()
+            .filter(|arg| !matches!(arg.kind, surface::GenericArgKind::Lifetime(_)))
+            .collect_vec();
         if generics.own_counts().types != args.len() {
             return None;
         }
@@ -844,10 +835,12 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
     }
 
     #[track_caller]
-    fn desugar_func(&self, func: surface::Ident, node_id: NodeId) -> Result<fhir::PathExpr<'genv>> {
-        let res = self.resolver_output().expr_path_res_map[&node_id];
+    fn desugar_func(&self, func: &surface::ExprPath) -> Result<fhir::PathExpr<'genv>> {
+        let res = self.resolver_output().expr_path_res_map[&func.node_id];
         if let ExprRes::Param(..) | ExprRes::GlobalFunc(..) = res {
-            let segments = self.genv().alloc_slice(&[func]);
+            let segments = self
+                .genv()
+                .alloc_slice_fill_iter(func.segments.iter().map(|s| s.ident));
             Ok(fhir::PathExpr { segments, res, fhir_id: self.next_fhir_id(), span: func.span })
         } else {
             let span = func.span;
@@ -969,6 +962,8 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                         },
                     });
                 }
+                // Lifetime arguments are holes, which we already added above
+                surface::GenericArgKind::Lifetime(_) => {}
             }
         }
         Ok((self.genv().alloc_slice(&fhir_args), self.genv().alloc_slice(&constraints)))
@@ -1040,7 +1035,7 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                 let ty = self.desugar_ty(ty)?;
                 fhir::TyKind::Constr(pred, self.genv().alloc(ty))
             }
-            surface::TyKind::Ref(mutbl, ty) => {
+            surface::TyKind::Ref(_, mutbl, ty) => {
                 let ty = self.desugar_ty(ty)?;
                 let mut_ty = fhir::MutTy { ty: self.genv().alloc(ty), mutbl: *mutbl };
                 fhir::TyKind::Ref(self.mk_lft_hole(), mut_ty)
@@ -1051,7 +1046,7 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
             }
             surface::TyKind::Array(ty, len) => {
                 let ty = self.desugar_ty(ty)?;
-                let len = Self::desugar_const_arg(len)?;
+                let len = self.desugar_const_arg(len)?;
                 fhir::TyKind::Array(self.genv().alloc(ty), len)
             }
             surface::TyKind::ImplTrait(node_id, bounds) => {
@@ -1062,12 +1057,93 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
         Ok(fhir::Ty { kind, span })
     }
 
-    fn desugar_const_arg(const_arg: &surface::ConstArg) -> Result<fhir::ConstArg> {
-        let kind = match const_arg.kind {
-            surface::ConstArgKind::Lit(val) => fhir::ConstArgKind::Lit(val),
-            surface::ConstArgKind::Infer => fhir::ConstArgKind::Infer,
+    /// Const items and literals in an array length are evaluated to a literal and a lone const
+    /// generic is kept as a parameter. Arithmetic over const generics (e.g., `N + 1`) cannot be
+    /// represented in an unrefined type, so we infer it from the length in the rust signature.
+    fn desugar_const_arg(
+        &mut self,
+        const_arg: &surface::ConstArg,
+    ) -> Result<fhir::ConstArg<'genv>> {
+        match &const_arg.kind {
+            surface::ConstArgKind::Lit(val) => {
+                Ok(fhir::ConstArg { kind: fhir::ConstArgKind::Lit(*val), span: const_arg.span })
+            }
+            surface::ConstArgKind::Infer => {
+                Ok(fhir::ConstArg { kind: fhir::ConstArgKind::Infer, span: const_arg.span })
+            }
+            surface::ConstArgKind::Expr(expr) => self.desugar_const_expr(expr),
+        }
+    }
+
+    /// Desugars an array length given as an expression. Subexpressions that don't depend on const
+    /// generics are evaluated.
+    fn desugar_const_expr(&mut self, expr: &surface::Expr) -> Result<fhir::ConstArg<'genv>> {
+        let kind = if let surface::ExprKind::Path(path) = &expr.kind
+            && let ExprRes::ConstGeneric(def_id) =
+                self.resolver_output().expr_path_res_map[&path.node_id]
+        {
+            fhir::ConstArgKind::Param(def_id)
+        } else if let Some(val) = self.eval_const_arg(expr)? {
+            let val = usize::try_from(val)
+                .map_err(|_| self.emit_err(errors::InvalidConstArg { span: expr.span }))?;
+            fhir::ConstArgKind::Lit(val)
+        } else if let surface::ExprKind::BinaryOp(op, box [e1, e2]) = &expr.kind
+            && matches!(
+                op,
+                surface::BinOp::Add
+                    | surface::BinOp::Sub
+                    | surface::BinOp::Mul
+                    | surface::BinOp::Div
+                    | surface::BinOp::Mod
+            )
+        {
+            let args = [self.desugar_const_expr(e1)?, self.desugar_const_expr(e2)?];
+            fhir::ConstArgKind::BinaryOp(*op, self.genv().alloc(args))
+        } else {
+            return Err(self.emit_err(errors::InvalidConstArg { span: expr.span }));
         };
-        Ok(fhir::ConstArg { kind, span: const_arg.span })
+        Ok(fhir::ConstArg { kind, span: expr.span })
+    }
+
+    /// Evaluates an array length returning `None` if it depends on a const generic.
+    fn eval_const_arg(&self, expr: &surface::Expr) -> Result<Option<i128>> {
+        let invalid = || self.emit_err(errors::InvalidConstArg { span: expr.span });
+        match &expr.kind {
+            surface::ExprKind::Literal(
+                lit @ surface::Lit { kind: surface::LitKind::Integer, .. },
+            ) => Ok(Some(self.try_parse_int_lit(expr.span, lit.symbol.as_str())?)),
+            surface::ExprKind::Path(path) => {
+                match self.resolver_output().expr_path_res_map[&path.node_id] {
+                    ExprRes::ConstGeneric(_) => Ok(None),
+                    ExprRes::Const(def_id) => {
+                        let tcx = self.genv().tcx();
+                        let val = tcx
+                            .const_eval_poly(def_id)
+                            .ok()
+                            .and_then(|val| val.try_to_target_usize(tcx))
+                            .ok_or_else(invalid)?;
+                        Ok(Some(val.into()))
+                    }
+                    _ => Err(invalid()),
+                }
+            }
+            surface::ExprKind::BinaryOp(op, box [e1, e2]) => {
+                let (Some(v1), Some(v2)) = (self.eval_const_arg(e1)?, self.eval_const_arg(e2)?)
+                else {
+                    return Ok(None);
+                };
+                let val = match op {
+                    surface::BinOp::Add => v1.checked_add(v2),
+                    surface::BinOp::Sub => v1.checked_sub(v2),
+                    surface::BinOp::Mul => v1.checked_mul(v2),
+                    surface::BinOp::Div => v1.checked_div(v2),
+                    surface::BinOp::Mod => v1.checked_rem(v2),
+                    _ => None,
+                };
+                val.map(Some).ok_or_else(invalid)
+            }
+            _ => Err(invalid()),
+        }
     }
 
     fn desugar_bty(&mut self, bty: &surface::BaseTy) -> Result<fhir::BaseTy<'genv>> {
@@ -1081,9 +1157,35 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                 let kind = fhir::BaseTyKind::Slice(self.genv().alloc(ty));
                 Ok(fhir::BaseTy { kind, fhir_id: self.next_fhir_id(), span: bty.span })
             }
+            surface::BaseTyKind::TraitObject(bounds) => {
+                let poly_traits =
+                    try_alloc_slice!(self.genv(), bounds, |bound| self.desugar_trait_ref(bound))?;
+                let kind = fhir::BaseTyKind::TraitObject(
+                    poly_traits,
+                    self.mk_lft_hole(),
+                    rustc_ast::TraitObjectSyntax::Dyn,
+                );
+                Ok(fhir::BaseTy { kind, fhir_id: self.next_fhir_id(), span: bty.span })
+            }
         }
     }
 
+    fn desugar_trait_ref(
+        &mut self,
+        trait_ref: &surface::TraitRef,
+    ) -> Result<fhir::PolyTraitRef<'genv>> {
+        let fhir::QPath::Resolved(None, path) = self.desugar_qpath(None, &trait_ref.path)? else {
+            span_bug!(trait_ref.path.span, "desugar_alias_reft: unexpected qpath")
+        };
+        let span = path.span;
+        Ok(fhir::PolyTraitRef {
+            bound_generic_params: &[],
+            modifiers: fhir::TraitBoundModifier::None,
+            trait_ref: path,
+            span,
+        })
+    }
+
     fn desugar_path_to_bty(
         &mut self,
         qself: Option<&surface::Ty>,
@@ -1257,7 +1359,6 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
     }
 
     fn desugar_expr(&mut self, expr: &surface::Expr) -> Result<fhir::Expr<'genv>> {
-        let node_id = expr.node_id;
         let kind = match &expr.kind {
             surface::ExprKind::Path(path) => self.desugar_var(path)?,
             surface::ExprKind::Literal(lit) => {
@@ -1290,7 +1391,7 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
             }
             surface::ExprKind::App(func, args) => {
                 let args = self.desugar_exprs(args)?;
-                let func = self.desugar_func(*func, node_id)?;
+                let func = self.desugar_func(func)?;
                 fhir::ExprKind::App(func, args)
             }
             surface::ExprKind::Alias(alias_reft, func_args) => {
@@ -1397,15 +1498,28 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                 if suffix == SORTS.int {
                     Ok(fhir::Lit::Int(n))
                 } else if suffix == SORTS.real {
-                    Ok(fhir::Lit::Real(n))
+                    Ok(fhir::Lit::Real(Real::from(n)))
                 } else {
                     Err(self.emit_err(errors::InvalidNumericSuffix::new(span, suffix)))
                 }
             }
+            surface::LitKind::Float => {
+                let suffix = lit.suffix.unwrap_or(SORTS.real);
+                if suffix != SORTS.real {
+                    return Err(self.emit_err(errors::InvalidNumericSuffix::new(span, suffix)));
+                }
+                Real::from_decimal_str(lit.symbol.as_str())
+                    .map(fhir::Lit::Real)
+                    .ok_or_else(|| self.emit_err(errors::RealTooLarge { span }))
+            }
             surface::LitKind::Bool => Ok(fhir::Lit::Bool(lit.symbol == kw::True)),
-            surface::LitKind::Str => Ok(fhir::Lit::Str(lit.symbol)),
-            surface::LitKind::Char => {
-                Ok(fhir::Lit::Char(lit.symbol.as_str().parse::<char>().unwrap()))
+            // String and char literals may contain escapes so we let rustc unescape them
+            surface::LitKind::Str | surface::LitKind::Char => {
+                match rustc_ast::LitKind::from_token_lit(lit) {
+                    Ok(rustc_ast::LitKind::Str(symbol, _)) => Ok(fhir::Lit::Str(symbol)),
+                    Ok(rustc_ast::LitKind::Char(c)) => Ok(fhir::Lit::Char(c)),
+                    _ => Err(self.emit_err(errors::UnexpectedLiteral { span })),
+                }
             }
             _ => Err(self.emit_err(errors::UnexpectedLiteral { span })),
         }
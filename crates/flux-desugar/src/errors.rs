@@ -1,38 +1,53 @@
-use flux_errors::E0999;
+use flux_errors::E9031;
 use flux_macros::Diagnostic;
 use flux_syntax::surface;
 use rustc_span::{Span, Symbol};
 
 #[derive(Diagnostic)]
-#[diag(desugar_int_too_large, code = E0999)]
+#[diag(desugar_int_too_large, code = E9031)]
 pub(super) struct IntTooLarge {
     #[primary_span]
     pub(super) span: Span,
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_unexpected_literal, code = E0999)]
+#[diag(desugar_real_too_large, code = E9031)]
+pub(super) struct RealTooLarge {
+    #[primary_span]
+    pub(super) span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(desugar_unexpected_literal, code = E9031)]
 pub(super) struct UnexpectedLiteral {
     #[primary_span]
     pub(super) span: Span,
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_invalid_constructor_path, code = E0999)]
+#[diag(desugar_invalid_constructor_path, code = E9031)]
 pub(super) struct InvalidConstructorPath {
     #[primary_span]
     pub(super) span: Span,
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_invalid_dot_var, code = E0999)]
+#[diag(desugar_invalid_const_arg, code = E9031)]
+pub(super) struct InvalidConstArg {
+    #[primary_span]
+    #[label]
+    pub(super) span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(desugar_invalid_dot_var, code = E9031)]
 pub(super) struct InvalidDotVar {
     #[primary_span]
     pub(super) span: Span,
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_invalid_func_as_var, code = E0999)]
+#[diag(desugar_invalid_func_as_var, code = E9031)]
 pub(super) struct InvalidFuncAsVar {
     #[primary_span]
     #[label]
@@ -40,7 +55,7 @@ pub(super) struct InvalidFuncAsVar {
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_invalid_func, code = E0999)]
+#[diag(desugar_invalid_func, code = E9031)]
 pub(super) struct InvalidFunc {
     #[primary_span]
     #[label]
@@ -48,14 +63,14 @@ pub(super) struct InvalidFunc {
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_invalid_loc, code = E0999)]
+#[diag(desugar_invalid_loc, code = E9031)]
 pub(super) struct InvalidLoc {
     #[primary_span]
     pub(super) span: Span,
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_invalid_numeric_suffix, code = E0999)]
+#[diag(desugar_invalid_numeric_suffix, code = E9031)]
 pub(super) struct InvalidNumericSuffix {
     #[primary_span]
     #[label]
@@ -70,7 +85,7 @@ impl InvalidNumericSuffix {
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_invalid_alias_reft, code = E0999)]
+#[diag(desugar_invalid_alias_reft, code = E9031)]
 pub(super) struct InvalidAliasReft {
     #[primary_span]
     #[label]
@@ -84,7 +99,7 @@ impl InvalidAliasReft {
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_invalid_variant_ret, code = E0999)]
+#[diag(desugar_invalid_variant_ret, code = E9031)]
 pub(super) struct InvalidVariantRet {
     #[primary_span]
     pub(super) span: Span,
@@ -97,7 +112,7 @@ impl InvalidVariantRet {
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_multiple_spreads_in_constructor, code = E0999)]
+#[diag(desugar_multiple_spreads_in_constructor, code = E9031)]
 pub(super) struct MultipleSpreadsInConstructor {
     #[primary_span]
     pub(super) span: Span,
@@ -2,6 +2,7 @@
 
 #![feature(rustc_private, min_specialization, box_patterns, let_chains, never_type)]
 
+extern crate rustc_ast;
 extern crate rustc_data_structures;
 extern crate rustc_errors;
 
@@ -266,7 +267,12 @@ impl CrateDesugar<'_, '_> {
                 surface::Item::FuncDef(defn) => {
                     self.desugar_func_defn(defn).collect_err(&mut self.err);
                 }
-                surface::Item::SortDecl(_) => {}
+                surface::Item::SortDecl(sort_decl) => {
+                    self.fhir.sort_decls.insert(
+                        sort_decl.name.name,
+                        fhir::SortDecl { name: sort_decl.name.name, span: sort_decl.name.span },
+                    );
+                }
             }
         }
     }
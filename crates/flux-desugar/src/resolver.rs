@@ -6,13 +6,14 @@ use flux_common::{
 };
 use flux_errors::{Errors, FluxSession};
 use flux_middle::{
+    cstore::CrateFluxItems,
     fhir::{self, Res},
     global_env::GlobalEnv,
-    MaybeExternId, ResolverOutput, Specs,
+    FluxDefId, MaybeExternId, ResolverOutput, Specs,
 };
 use flux_syntax::surface::{self, visit::Visitor as _, Ident};
 use hir::{def::DefKind, ItemId, ItemKind, OwnerId};
-use rustc_data_structures::unord::{ExtendUnord, UnordMap};
+use rustc_data_structures::unord::{ExtendUnord, UnordMap, UnordSet};
 use rustc_errors::ErrorGuaranteed;
 use rustc_hash::FxHashMap;
 use rustc_hir::{
@@ -21,7 +22,7 @@ use rustc_hir::{
         Namespace::{self, *},
         PerNS,
     },
-    def_id::{LocalDefId, CRATE_DEF_ID},
+    def_id::{CrateNum, LocalDefId, CRATE_DEF_ID, LOCAL_CRATE},
     ParamName, PrimTy, CRATE_HIR_ID, CRATE_OWNER_ID,
 };
 use rustc_middle::{metadata::ModChild, ty::TyCtxt};
@@ -58,6 +59,8 @@ pub(crate) struct CrateResolver<'genv, 'tcx> {
     prelude: PerNS<Rib>,
     func_decls: UnordMap<Symbol, fhir::SpecFuncKind>,
     sort_decls: UnordMap<Symbol, fhir::SortDecl>,
+    /// The names of the qualifiers defined in the local crate
+    qualifiers: UnordSet<Symbol>,
     err: Option<ErrorGuaranteed>,
     /// The most recent module we have visited. Used to check for visibility of other items from
     /// this module.
@@ -80,6 +83,7 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
             err: None,
             func_decls: Default::default(),
             sort_decls: Default::default(),
+            qualifiers: Default::default(),
             current_module: CRATE_OWNER_ID,
         }
     }
@@ -87,12 +91,15 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
     fn define_flux_global_items(&mut self) {
         for item in self.specs.flux_items_by_parent.values().flatten() {
             match item {
-                surface::Item::Qualifier(_) => {}
+                surface::Item::Qualifier(qual) => {
+                    self.qualifiers.insert(qual.name.name);
+                }
                 surface::Item::FuncDef(defn) => {
+                    let def_id = FluxDefId::local(defn.name.name);
                     let kind = if defn.body.is_some() {
-                        fhir::SpecFuncKind::Def
+                        fhir::SpecFuncKind::Def(def_id)
                     } else {
-                        fhir::SpecFuncKind::Uif
+                        fhir::SpecFuncKind::Uif(def_id)
                     };
                     self.func_decls.insert(defn.name.name, kind);
                 }
@@ -112,6 +119,18 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
         );
     }
 
+    /// Returns the crate named `name` if it is a dependency, i.e., if the name is not `crate` or
+    /// the name of the local crate.
+    fn extern_crate(&self, name: Symbol) -> Option<CrateNum> {
+        let krate = self.crates.get(&name)?.krate;
+        (krate != LOCAL_CRATE).then_some(krate)
+    }
+
+    /// Returns the flux items exported by the crate named `name` if it is a direct dependency
+    fn extern_flux_items(&self, name: Symbol) -> Option<&'genv CrateFluxItems> {
+        self.genv.extern_flux_items(self.extern_crate(name)?)
+    }
+
     fn define_items(&mut self, item_ids: impl IntoIterator<Item = &'tcx ItemId>) {
         for item_id in item_ids {
             let item = self.genv.hir().item(*item_id);
@@ -128,7 +147,14 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
                         }
                         hir::UseKind::Glob => {
                             let is_prelude = is_prelude_import(self.genv.tcx(), item);
+                            let curr_mod = self.current_module.def_id;
                             for mod_child in glob_imports(self.genv.tcx(), path) {
+                                // Like rustc, a glob import only brings accessible items into scope.
+                                // Otherwise, private imports in the module would shadow our names,
+                                // e.g., `alloc::string` imports the module `str` privately.
+                                if !mod_child.vis.is_accessible_from(curr_mod, self.genv.tcx()) {
+                                    continue;
+                                }
                                 if let Some(ns @ (TypeNS | ValueNS)) = mod_child.res.ns() {
                                     let name = mod_child.ident.name;
                                     let res = map_res(mod_child.res);
@@ -251,7 +277,11 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
     }
 
     fn resolve_fn_sig(&mut self, owner_id: MaybeExternId<OwnerId>) -> Result {
-        if let Some(fn_sig) = &self.specs.fn_sigs[&owner_id.local_id()].fn_sig {
+        let fn_spec = &self.specs.fn_sigs[&owner_id.local_id()];
+        if let Some(qual_names) = &fn_spec.qual_names {
+            self.resolve_qual_names(qual_names)?;
+        }
+        if let Some(fn_sig) = &fn_spec.fn_sig {
             ItemResolver::run(self, owner_id, |item_resolver| {
                 item_resolver.visit_fn_sig(fn_sig);
             })?;
@@ -260,6 +290,42 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
         Ok(())
     }
 
+    /// Resolves the qualifiers named in a `flux::qualifiers` attribute. A qualifier is named by its
+    /// name if it is defined in the local crate or by a path qualified with the name of the crate
+    /// that exports it.
+    fn resolve_qual_names(&mut self, qual_names: &surface::QualNames) -> Result {
+        for path in &qual_names.names {
+            let def_id = match &path.segments[..] {
+                [name] => {
+                    let name = name.ident.name;
+                    self.qualifiers
+                        .contains(&name)
+                        .then(|| FluxDefId::local(name))
+                }
+                [krate, name] => {
+                    let name = name.ident.name;
+                    self.extern_crate(krate.ident.name).and_then(|krate| {
+                        self.genv
+                            .extern_flux_items(krate)?
+                            .qualifiers
+                            .iter()
+                            .any(|qualifier| qualifier.def_id.name == name)
+                            .then_some(FluxDefId { krate, name })
+                    })
+                }
+                _ => None,
+            };
+            let Some(def_id) = def_id else {
+                return Err(self
+                    .genv
+                    .sess()
+                    .emit_err(errors::UnknownQualifier::new(path)));
+            };
+            self.output.qualifier_res_map.insert(path.node_id, def_id);
+        }
+        Ok(())
+    }
+
     fn resolve_path_with_ribs<S: Segment>(
         &mut self,
         segments: &[S],
@@ -753,14 +819,14 @@ fn mk_crate_mapping(tcx: TyCtxt) -> UnordMap<Symbol, DefId> {
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::{E9030, E9033, E9044};
     use flux_macros::Diagnostic;
     use flux_syntax::surface;
     use itertools::Itertools;
     use rustc_span::Span;
 
     #[derive(Diagnostic)]
-    #[diag(desugar_unsupported_signature, code = E0999)]
+    #[diag(desugar_unsupported_signature, code = E9033)]
     #[note]
     pub(super) struct UnsupportedSignature<'a> {
         #[primary_span]
@@ -775,7 +841,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(desugar_unresolved_path, code = E0999)]
+    #[diag(desugar_unresolved_path, code = E9030)]
     #[help]
     pub struct UnresolvedPath {
         #[primary_span]
@@ -791,4 +857,22 @@ mod errors {
             }
         }
     }
+
+    #[derive(Diagnostic)]
+    #[diag(desugar_unknown_qualifier, code = E9044)]
+    pub(super) struct UnknownQualifier {
+        #[primary_span]
+        #[label]
+        span: Span,
+        path: String,
+    }
+
+    impl UnknownQualifier {
+        pub(super) fn new(path: &surface::ExprPath) -> Self {
+            Self {
+                span: path.span,
+                path: path.segments.iter().map(|segment| segment.ident).join("::"),
+            }
+        }
+    }
 }
@@ -4,7 +4,7 @@ use flux_common::index::IndexGen;
 use flux_errors::Errors;
 use flux_middle::{
     fhir::{self, ExprRes},
-    ResolverOutput,
+    FluxDefId, ResolverOutput,
 };
 use flux_syntax::{
     surface::{self, visit::Visitor as _, Ident, NodeId},
@@ -70,7 +70,6 @@ pub(crate) trait ScopedVisitor: Sized {
     fn on_fn_sig(&mut self, _fn_sig: &surface::FnSig) {}
     fn on_fn_output(&mut self, _output: &surface::FnOutput) {}
     fn on_loc(&mut self, _loc: Ident, _node_id: NodeId) {}
-    fn on_func(&mut self, _func: Ident, _node_id: NodeId) {}
     fn on_path(&mut self, _path: &surface::ExprPath) {}
     fn on_base_sort(&mut self, _sort: &surface::BaseSort) {}
 }
@@ -281,19 +280,12 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
                     surface::visit::walk_bty(this, bty);
                 });
             }
-            surface::BaseTyKind::Path(..) => {
+            surface::BaseTyKind::Path(..) | surface::BaseTyKind::TraitObject(_) => {
                 surface::visit::walk_bty(self, bty);
             }
         }
     }
 
-    fn visit_expr(&mut self, expr: &surface::Expr) {
-        if let surface::ExprKind::App(func, _) = &expr.kind {
-            self.on_func(*func, expr.node_id);
-        }
-        surface::visit::walk_expr(self, expr);
-    }
-
     fn visit_path_expr(&mut self, path: &surface::ExprPath) {
         self.on_path(path);
     }
@@ -530,6 +522,10 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
             self.path_res_map.insert(path.node_id, res);
             return;
         }
+        if let Some(res) = self.try_resolve_extern_func(path) {
+            self.path_res_map.insert(path.node_id, res);
+            return;
+        }
         self.errors.emit(errors::UnresolvedVar::from_path(path));
     }
 
@@ -582,6 +578,17 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
         Some(ExprRes::GlobalFunc(*kind, ident.name))
     }
 
+    /// Resolves a path `krate::f` to the spec function `f` exported by `krate`
+    fn try_resolve_extern_func(&mut self, path: &surface::ExprPath) -> Option<ExprRes<NodeId>> {
+        let [krate, name] = &path.segments[..] else { return None };
+        let decl = self
+            .resolver
+            .extern_flux_items(krate.ident.name)?
+            .func_decls
+            .get(&name.ident.name)?;
+        Some(ExprRes::GlobalFunc(decl.kind, decl.name))
+    }
+
     fn resolve_sort_path(&mut self, path: &surface::SortPath) {
         let res = self
             .try_resolve_sort_param(path)
@@ -630,11 +637,24 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
     }
 
     fn try_resolve_user_sort(&self, path: &surface::SortPath) -> Option<fhir::SortRes> {
-        let [segment] = &path.segments[..] else { return None };
-        self.resolver
-            .sort_decls
-            .get(&segment.name)
-            .map(|decl| fhir::SortRes::User { name: decl.name })
+        match &path.segments[..] {
+            [segment] => {
+                self.resolver
+                    .sort_decls
+                    .get(&segment.name)
+                    .map(|decl| fhir::SortRes::User(FluxDefId::local(decl.name)))
+            }
+            [krate, segment] => {
+                let krate = self.resolver.extern_crate(krate.name)?;
+                self.resolver
+                    .genv
+                    .extern_flux_items(krate)?
+                    .sort_decls
+                    .contains(&segment.name)
+                    .then_some(fhir::SortRes::User(FluxDefId { krate, name: segment.name }))
+            }
+            _ => None,
+        }
     }
 
     fn try_resolve_prim_sort(&self, path: &surface::SortPath) -> Option<fhir::SortRes> {
@@ -645,6 +665,8 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
             Some(fhir::SortRes::PrimSort(fhir::PrimSort::Bool))
         } else if segment.name == SORTS.real {
             Some(fhir::SortRes::PrimSort(fhir::PrimSort::Real))
+        } else if segment.name == sym::str {
+            Some(fhir::SortRes::PrimSort(fhir::PrimSort::Str))
         } else if segment.name == SORTS.set {
             Some(fhir::SortRes::PrimSort(fhir::PrimSort::Set))
         } else if segment.name == SORTS.map {
@@ -764,10 +786,6 @@ impl ScopedVisitor for RefinementResolver<'_, '_, '_> {
         self.define_param(param.ident, fhir::ParamKind::Explicit(param.mode), param.node_id, None);
     }
 
-    fn on_func(&mut self, func: Ident, node_id: NodeId) {
-        self.resolve_ident(func, node_id);
-    }
-
     fn on_loc(&mut self, loc: Ident, node_id: NodeId) {
         self.resolve_ident(loc, node_id);
     }
@@ -886,14 +904,14 @@ impl ScopedVisitor for IllegalBinderVisitor<'_, '_, '_> {
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::{E9030, E9031, E9032};
     use flux_macros::Diagnostic;
     use flux_syntax::surface;
     use itertools::Itertools;
     use rustc_span::{symbol::Ident, Span, Symbol};
 
     #[derive(Diagnostic)]
-    #[diag(desugar_duplicate_param, code = E0999)]
+    #[diag(desugar_duplicate_param, code = E9032)]
     pub(super) struct DuplicateParam {
         #[primary_span]
         #[label]
@@ -911,7 +929,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(desugar_unresolved_sort, code = E0999)]
+    #[diag(desugar_unresolved_sort, code = E9030)]
     pub(super) struct UnresolvedSort {
         #[primary_span]
         #[label]
@@ -934,7 +952,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(desugar_unresolved_var, code = E0999)]
+    #[diag(desugar_unresolved_var, code = E9030)]
     pub(super) struct UnresolvedVar {
         #[primary_span]
         #[label]
@@ -961,7 +979,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(desugar_invalid_unrefined_param, code = E0999)]
+    #[diag(desugar_invalid_unrefined_param, code = E9031)]
     pub(super) struct InvalidUnrefinedParam {
         #[primary_span]
         #[label]
@@ -976,7 +994,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(desugar_illegal_binder, code = E0999)]
+    #[diag(desugar_illegal_binder, code = E9031)]
     pub(super) struct IllegalBinder {
         #[primary_span]
         #[label]
@@ -4,6 +4,18 @@ driver_duplicated_attr =
 driver_invalid_attr =
     invalid flux attribute
 
+driver_unknown_lint =
+    unknown flux lint `{$name}`
+
+driver_extern_spec_file =
+    cannot read extern spec file `{$path}`: {$err}
+
+driver_unsupported_in_spec_file =
+    {$what} are not supported in spec files
+
+driver_cannot_write_output =
+    cannot write the {$what} to `{$dir}`: {$err}
+
 driver_cfg_error =
     invalid flux configuration: {$message}
 
@@ -72,3 +84,28 @@ driver_mismatched_generics =
     .extern_def_label = external {$def_descr} found here
     .note = extern specs must exactly match the external definition, including the list of generic parameters and their names
 
+driver_mismatched_param_count =
+    invalid extern spec for `{$name}`
+    .label = extern spec has {$found} {$found ->
+        [one] parameter
+        *[other] parameters
+     }
+    .extern_def_label = `{$name}` has {$expected} {$expected ->
+        [one] parameter
+        *[other] parameters
+     }
+    .help = update the extern spec to match the current signature of `{$name}`
+
+driver_mismatched_sig_ty =
+    invalid extern spec for `{$name}`
+    .label = expected `{$expected}`, found `{$found}`
+    .extern_def_label = `{$name}` defined here
+    .note = the unrefined types in an extern spec must match the signature of the external function
+
+driver_mismatched_sig_lifetimes =
+    invalid extern spec for `{$name}`
+    .label = lifetimes don't match the signature of `{$name}`
+    .extern_def_label = `{$name}` defined here
+    .note = expected signature `{$expected}`
+    found signature `{$found}`
+
@@ -0,0 +1,468 @@
+//! Library interface to check a crate with flux. Instead of printing diagnostics, the results of
+//! checking each item are returned as data: whether it was verified, the errors reported for it and
+//! stats about the constraints sent to the solver.
+//!
+//! The solutions fixpoint infers for the kvars of an item are not part of the results: fixpoint only
+//! reports whether the constraint holds and the errors if it doesn't, so returning them needs
+//! support from the solver first.
+//!
+//! ```ignore
+//! use flux_driver::api;
+//!
+//! let mut args = vec!["rustc".to_string(), "src/lib.rs".to_string()];
+//! args.push("--crate-type=lib".to_string());
+//! let results = api::check_crate(api::Options::new(args))?;
+//! for item in &results.items {
+//!     println!("{}: {:?}", item.def_path, item.status);
+//! }
+//! ```
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use flux_infer::fixpoint_encoding::FixpointRecord;
+use rustc_driver::RunCompiler;
+use rustc_errors::{
+    emitter::Emitter,
+    translation::{to_fluent_args, Translate},
+    DiagInner, FluentBundle, LazyFallbackBundle,
+};
+use rustc_span::{source_map::SourceMap, Span};
+
+use crate::callbacks::FluxCallbacks;
+
+/// Options to check a crate
+pub struct Options {
+    /// Arguments for rustc. As in [`std::env::args`], the first one is the name of the program and
+    /// it's ignored. The sysroot of the toolchain flux was built with must be passed with
+    /// `--sysroot` if it cannot be found from the current executable.
+    pub args: Vec<String>,
+    /// Settings for flux with the same format as the config file, e.g., `check_def = "foo"`. The
+    /// configuration of flux is global to the process, so every call to [`check_crate`] must pass
+    /// the same settings as the first one, otherwise it fails with [`CheckError::ConfigChanged`].
+    pub config: toml::Table,
+    /// Directory with the precompiled `flux_core` and `flux_prelude` libraries, i.e., the
+    /// `flux_core` directory next to `flux-driver`. If `None`, the crate is checked without them.
+    pub flux_lib_dir: Option<PathBuf>,
+}
+
+impl Options {
+    pub fn new(args: Vec<String>) -> Options {
+        Options { args, config: toml::Table::new(), flux_lib_dir: None }
+    }
+}
+
+/// Reasons a crate could not be checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckError {
+    /// Rustc failed to compile the crate. The errors are printed by rustc.
+    Compile,
+    /// [`Options::config`] differs from the settings of a previous call to [`check_crate`]
+    ConfigChanged,
+}
+
+/// Results of checking a crate
+#[derive(Debug)]
+pub struct CrateResults {
+    /// Items in the order they were checked
+    pub items: Vec<ItemResult>,
+    /// Diagnostics not reported while checking a specific item, e.g., errors in the specs of the
+    /// crate which prevent checking any item.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug)]
+pub struct ItemResult {
+    /// Path identifying the item in the crate, with disambiguators for items without a name, e.g.,
+    /// `krate::Foo::{impl#0}::new`
+    pub def_path: String,
+    /// Path of the item as written in the source, e.g., `Foo::<T>::new`. It may not be unique.
+    pub path: String,
+    pub status: ItemStatus,
+    /// Diagnostics reported while checking the item, including warnings
+    pub diagnostics: Vec<Diagnostic>,
+    /// Stats of the constraints sent to the solver for the item. `None` if the item didn't
+    /// generate any non-trivial constraint.
+    pub stats: Option<ConstraintStats>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemStatus {
+    /// The item was checked without errors
+    Verified,
+    /// An error was reported while checking the item
+    Failed,
+    /// The item is a function marked with `#[flux::trusted]` so its body wasn't checked
+    Trusted,
+    /// The item is marked with `#[flux::ignore]`
+    Ignored,
+    /// The item was filtered out by `check_def` or `check_files`
+    Skipped,
+}
+
+/// Stats of the constraints of an item, summed over all the queries sent to the solver for it,
+/// e.g., the body of a method and its subtyping against the trait method it implements.
+#[derive(Debug, Default, Clone)]
+pub struct ConstraintStats {
+    /// Number of queries sent to the solver
+    pub queries: usize,
+    /// Number of queries answered from the cache
+    pub cached: usize,
+    /// Number of kvars to infer
+    pub kvars: usize,
+    /// Number of distinct places that can be reported as errors
+    pub tags: usize,
+    /// Stats reported by the solver
+    pub constraints: usize,
+    pub iterations: usize,
+    pub checked: usize,
+    pub valid: usize,
+    /// Time spent solving, including reading the cache
+    pub time: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub level: Level,
+    /// Error code, e.g., `E9001`
+    pub code: Option<String>,
+    pub message: String,
+    /// Primary span
+    pub span: Option<SpanInfo>,
+    /// Spans with their labels, e.g., the precondition that cannot be proven
+    pub labels: Vec<Label>,
+    /// Notes and help messages attached to the diagnostic
+    pub children: Vec<SubDiagnostic>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: SpanInfo,
+    pub message: Option<String>,
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubDiagnostic {
+    pub level: Level,
+    pub message: String,
+    pub span: Option<SpanInfo>,
+}
+
+/// A span in a source file. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanInfo {
+    pub file: String,
+    pub line_start: usize,
+    pub col_start: usize,
+    pub line_end: usize,
+    pub col_end: usize,
+}
+
+/// Checks a crate returning the results of checking each item
+pub fn check_crate(options: Options) -> Result<CrateResults, CheckError> {
+    flux_config::set_package_config(options.config).map_err(|_| CheckError::ConfigChanged)?;
+    let mut args = options.args;
+    crate::add_flux_args(&mut args, options.flux_lib_dir.as_deref());
+
+    let collector = Collector::default();
+    let mut callbacks = FluxCallbacks {
+        full_compilation: false,
+        verify: true,
+        trust_bodies: false,
+        dump_metadata: None,
+        collector: Some(collector.clone()),
+    };
+    // Flux aborts with a fatal error if there are errors in the specs. Diagnostics are collected
+    // anyways so they are returned in the results.
+    let _ = rustc_driver::catch_fatal_errors(|| RunCompiler::new(&args, &mut callbacks).run());
+
+    let mut inner = collector.0.lock().unwrap();
+    if !inner.started {
+        return Err(CheckError::Compile);
+    }
+    let mut diagnostics = std::mem::take(&mut inner.crate_diagnostics);
+    diagnostics.append(&mut inner.pending);
+    Ok(CrateResults { items: std::mem::take(&mut inner.items), diagnostics })
+}
+
+/// Collects the results of checking a crate. It's shared between [`FluxCallbacks`] and the
+/// emitter of the session used to report errors.
+#[derive(Clone, Default)]
+pub struct Collector(Arc<Mutex<CollectorInner>>);
+
+#[derive(Default)]
+struct CollectorInner {
+    /// Whether flux started checking the crate, i.e., rustc compiled it without errors
+    started: bool,
+    items: Vec<ItemResult>,
+    crate_diagnostics: Vec<Diagnostic>,
+    /// Diagnostics emitted since the last item was recorded
+    pending: Vec<Diagnostic>,
+}
+
+impl Collector {
+    pub(crate) fn start(&self) {
+        self.0.lock().unwrap().started = true;
+    }
+
+    pub(crate) fn emitter(
+        &self,
+        source_map: Arc<SourceMap>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> CollectorEmitter {
+        CollectorEmitter { collector: self.clone(), source_map, fallback_bundle }
+    }
+
+    /// Diagnostics emitted so far are not associated with any item
+    pub(crate) fn flush_crate_diagnostics(&self) {
+        let mut inner = self.0.lock().unwrap();
+        let inner = &mut *inner;
+        inner.crate_diagnostics.append(&mut inner.pending);
+    }
+
+    /// Records the result of checking an item. Diagnostics emitted since the last item was
+    /// recorded are associated with it.
+    pub(crate) fn record_item(
+        &self,
+        def_path: String,
+        path: String,
+        status: ItemStatus,
+        records: Vec<FixpointRecord>,
+    ) {
+        let stats = (!records.is_empty()).then(|| {
+            records
+                .iter()
+                .fold(ConstraintStats::default(), |mut stats, record| {
+                    stats.queries += 1;
+                    stats.cached += record.cached as usize;
+                    stats.kvars += record.kvars;
+                    stats.tags += record.tags;
+                    stats.constraints += record.stats.num_cstr as usize;
+                    stats.iterations += record.stats.num_iter as usize;
+                    stats.checked += record.stats.num_chck as usize;
+                    stats.valid += record.stats.num_vald as usize;
+                    stats.time += record.time;
+                    stats
+                })
+        });
+        let mut inner = self.0.lock().unwrap();
+        let diagnostics = std::mem::take(&mut inner.pending);
+        inner
+            .items
+            .push(ItemResult { def_path, path, status, diagnostics, stats });
+    }
+}
+
+/// Emitter converting diagnostics into [`Diagnostic`] and storing them in a [`Collector`]
+pub(crate) struct CollectorEmitter {
+    collector: Collector,
+    source_map: Arc<SourceMap>,
+    fallback_bundle: LazyFallbackBundle,
+}
+
+impl CollectorEmitter {
+    fn span_info(&self, span: Span) -> Option<SpanInfo> {
+        if span.is_dummy() {
+            return None;
+        }
+        let lo = self.source_map.lookup_char_pos(span.lo());
+        let hi = self.source_map.lookup_char_pos(span.hi());
+        Some(SpanInfo {
+            file: self
+                .source_map
+                .filename_for_diagnostics(&lo.file.name)
+                .to_string(),
+            line_start: lo.line,
+            col_start: lo.col.0 + 1,
+            line_end: hi.line,
+            col_end: hi.col.0 + 1,
+        })
+    }
+}
+
+impl Translate for CollectorEmitter {
+    fn fluent_bundle(&self) -> Option<&FluentBundle> {
+        None
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &self.fallback_bundle
+    }
+}
+
+impl Emitter for CollectorEmitter {
+    fn emit_diagnostic(&mut self, diag: DiagInner) {
+        let args = to_fluent_args(diag.args.iter());
+        let labels = diag
+            .span
+            .span_labels()
+            .into_iter()
+            .filter_map(|label| {
+                Some(Label {
+                    span: self.span_info(label.span)?,
+                    message: label.label.map(|msg| {
+                        self.translate_message(&msg, &args)
+                            .map_or_else(|err| err.to_string(), |msg| msg.to_string())
+                    }),
+                    primary: label.is_primary,
+                })
+            })
+            .collect();
+        let children = diag
+            .children
+            .iter()
+            .map(|child| {
+                SubDiagnostic {
+                    level: level(child.level),
+                    message: self.translate_messages(&child.messages, &args).to_string(),
+                    span: child
+                        .span
+                        .primary_span()
+                        .and_then(|span| self.span_info(span)),
+                }
+            })
+            .collect();
+        let diagnostic = Diagnostic {
+            level: level(diag.level()),
+            code: diag.code.map(|code| code.to_string()),
+            message: self.translate_messages(&diag.messages, &args).to_string(),
+            span: diag
+                .span
+                .primary_span()
+                .and_then(|span| self.span_info(span)),
+            labels,
+            children,
+        };
+        self.collector.0.lock().unwrap().pending.push(diagnostic);
+    }
+
+    fn source_map(&self) -> Option<&SourceMap> {
+        Some(&self.source_map)
+    }
+}
+
+fn level(level: rustc_errors::Level) -> Level {
+    use rustc_errors::Level as L;
+    match level {
+        L::Bug | L::Fatal | L::Error | L::DelayedBug => Level::Error,
+        L::ForceWarning(_) | L::Warning => Level::Warning,
+        L::Help | L::OnceHelp => Level::Help,
+        L::Note | L::OnceNote | L::FailureNote | L::Allow | L::Expect(_) => Level::Note,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path, process};
+
+    use super::*;
+
+    /// Checks `src` writing it and the outputs of rustc to `dir`
+    fn check(dir: &Path, name: &str, src: &str, config: &str) -> Result<CrateResults, CheckError> {
+        let file = dir.join(format!("{name}.rs"));
+        fs::write(&file, src).unwrap();
+        let args = vec![
+            "rustc".to_string(),
+            file.display().to_string(),
+            "--crate-type=lib".to_string(),
+            "--edition=2021".to_string(),
+            "--emit=metadata".to_string(),
+            format!("--out-dir={}", dir.display()),
+        ];
+        let config = toml::from_str(config).unwrap();
+        check_crate(Options { args, config, flux_lib_dir: None })
+    }
+
+    fn item<'a>(results: &'a CrateResults, def_path: &str) -> &'a ItemResult {
+        results
+            .items
+            .iter()
+            .find(|item| item.def_path == def_path)
+            .unwrap_or_else(|| panic!("no results for `{def_path}` in {results:#?}"))
+    }
+
+    // The config of flux is global to the process so all the checks are in a single test
+    #[test]
+    fn check_crates() {
+        let dir = env::temp_dir().join(format!("flux-api-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = r#"check_def = "Counter*, trusted, ignored, items.rs:16""#;
+
+        let results = check(
+            &dir,
+            "items",
+            "\
+pub struct Counter(pub u32);
+
+impl Counter {
+    pub fn reset(&mut self) {}
+}
+
+#[flux::trusted]
+pub fn trusted() {}
+
+#[flux::ignore]
+pub fn ignored() {}
+
+pub fn skipped() {}
+
+pub fn zeroed() -> [u8; 2] {
+    [0; 2]
+}
+",
+            config,
+        )
+        .unwrap();
+        assert!(results.diagnostics.is_empty(), "{:#?}", results.diagnostics);
+        let reset = item(&results, "items::{impl#0}::reset");
+        assert_eq!(reset.path, "Counter::reset");
+        assert_eq!(reset.status, ItemStatus::Verified);
+        assert!(reset.diagnostics.is_empty() && reset.stats.is_none());
+        assert_eq!(item(&results, "items::Counter").status, ItemStatus::Verified);
+        assert_eq!(item(&results, "items::trusted").status, ItemStatus::Trusted);
+        assert_eq!(item(&results, "items::ignored").status, ItemStatus::Ignored);
+        assert_eq!(item(&results, "items::skipped").status, ItemStatus::Skipped);
+        // The innermost definition at the line is the length of the array but that's checked with
+        // the function containing it
+        assert_eq!(item(&results, "items::zeroed").status, ItemStatus::Verified);
+
+        // Errors in the specs are reported before checking any item
+        let results = check(
+            &dir,
+            "specs",
+            "\
+#[flux::sig(fn(x: i32) -> bool[x])]
+pub fn bad(x: i32) -> bool {
+    x > 0
+}
+",
+            config,
+        )
+        .unwrap();
+        assert!(results.items.is_empty(), "{:#?}", results.items);
+        let [diagnostic] = &results.diagnostics[..] else {
+            panic!("expected one diagnostic: {:#?}", results.diagnostics)
+        };
+        assert_eq!(diagnostic.level, Level::Error);
+        assert_eq!(diagnostic.span.as_ref().unwrap().line_start, 1);
+
+        let err = check(&dir, "syntax", "pub fn f( {}", config).err();
+        assert_eq!(err, Some(CheckError::Compile));
+
+        let err = check(&dir, "config", "pub fn f() {}", "cache = true").err();
+        assert_eq!(err, Some(CheckError::ConfigChanged));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
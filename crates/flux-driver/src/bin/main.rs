@@ -1,16 +1,15 @@
-#![feature(rustc_private)]
+#![feature(rustc_private, let_chains)]
 
 extern crate rustc_driver;
 
 use std::{
     env, fs,
     io::{self, Read as _},
-    ops::Deref,
     path::{Path, PathBuf},
     process::exit,
 };
 
-use flux_driver::callbacks::FluxCallbacks;
+use flux_driver::{arg_value, callbacks::FluxCallbacks, FLUX_CORE};
 use rustc_driver::{catch_with_exit_code, RunCompiler};
 
 mod logger;
@@ -18,44 +17,53 @@ mod logger;
 fn main() -> io::Result<()> {
     let original_args = env::args().collect::<Vec<_>>();
 
+    let context = Context::new(&original_args);
+
+    // The config of the package must be set before reading any setting, including the ones used to
+    // install the logger.
+    if let Context::CargoFlux { metadata: Some(metadata), .. } = &context {
+        flux_config::set_package_config(metadata.config.clone()).map_err(io::Error::other)?;
+    }
+
     let resolve_logs = logger::install()?;
 
-    let context = Context::new(&original_args);
+    // Flux error codes are not in rustc's registry so we explain them ourselves. Any other code is
+    // explained by rustc below.
+    if let Some(explanation) =
+        arg_value(&original_args, "--explain", |_| true).and_then(flux_errors::explain)
+    {
+        print!("{explanation}");
+        exit(0);
+    }
 
     if context.be_rustc() {
+        // Cargo queries the version of the compiler with `-vV` and checks again every crate when
+        // the output changes. We add the version of flux so the `.fluxmeta` files of fresh crates
+        // are regenerated after updating flux instead of being rejected.
+        if original_args.iter().any(|arg| arg == "-vV") {
+            println!("flux-version: {}", flux_metadata::FLUX_VERSION);
+        }
         rustc_driver::install_ice_hook(rustc_driver::DEFAULT_BUG_REPORT_URL, |_| ());
         rustc_driver::main();
     }
 
-    // HACK(nilehmann)
-    // Disable incremental compilation because that makes the borrow checker to not run
-    // and we fail to retrieve the mir.
-    let mut args = vec![];
-    let mut is_codegen = false;
-    for arg in env::args() {
-        if arg.starts_with("-C") || arg.starts_with("--codegen") {
-            is_codegen = true;
-        } else if is_codegen && arg.starts_with("incremental=") {
-            is_codegen = false;
-        } else {
-            if is_codegen {
-                args.push("-C".to_string());
-                is_codegen = false;
-            }
-            args.push(arg);
-        }
-    }
-
+    let mut args = original_args.clone();
     args.push("--sysroot".into());
     args.push(sysroot().expect("Flux Rust requires rustup to be built."));
-    args.push("-Coverflow-checks=off".to_string());
-    args.push("-Zcrate-attr=feature(register_tool, custom_inner_attributes)".to_string());
-    args.push("-Zcrate-attr=register_tool(flux)".to_string());
-    args.push("-Zcrate-attr=register_tool(flux_tool)".to_string());
-    args.push("--cfg=flux".to_string());
-
-    let mut callbacks =
-        FluxCallbacks { full_compilation: context.full_compilation(), verify: context.verify() };
+    // `flux_core` and `flux_prelude` are installed in the `flux_core` directory next to the binary
+    let flux_lib_dir = env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(FLUX_CORE)));
+    flux_driver::add_flux_args(&mut args, flux_lib_dir.as_deref());
+
+    let mut callbacks = FluxCallbacks {
+        full_compilation: context.full_compilation(),
+        verify: context.verify(),
+        trust_bodies: context.trust_bodies(),
+        // Set by `flux-meta dump`
+        dump_metadata: env::var("FLUX_META_DUMP").ok(),
+        collector: None,
+    };
 
     let exit_code = catch_with_exit_code(move || RunCompiler::new(&args, &mut callbacks).run());
     resolve_logs()?;
@@ -70,30 +78,12 @@ fn sysroot() -> Option<String> {
     Some(format!("{home}/toolchains/{toolchain}"))
 }
 
-/// If a command-line option matches `find_arg`, then apply the predicate `pred` on its value. If
-/// true, then return it. The parameter is assumed to be either `--arg=value` or `--arg value`.
-pub fn arg_value<'a, T: Deref<Target = str>>(
-    args: &'a [T],
-    find_arg: &str,
-    pred: impl Fn(&str) -> bool,
-) -> Option<&'a str> {
-    let mut args = args.iter().map(Deref::deref);
-    while let Some(arg) = args.next() {
-        let mut arg = arg.splitn(2, '=');
-        if arg.next() != Some(find_arg) {
-            continue;
-        }
-
-        match arg.next().or_else(|| args.next()) {
-            Some(v) if pred(v) => return Some(v),
-            _ => {}
-        }
-    }
-    None
-}
-
+/// The `[package.metadata.flux]` table in the `Cargo.toml` manifest
 struct FluxMetadata {
     enabled: bool,
+    /// Every other key in the table, which are settings for the package with the same format as in
+    /// the config file
+    config: toml::Table,
 }
 
 impl FluxMetadata {
@@ -103,14 +93,18 @@ impl FluxMetadata {
         };
         let manifest_dir = PathBuf::from(manifest_dir);
         let manifest = FluxMetadata::read_manifest(&manifest_dir);
-        let enabled = manifest
+        let mut config = manifest
             .get("package")
             .and_then(|package| package.get("metadata"))
             .and_then(|metadata| metadata.get("flux"))
-            .and_then(|flux| flux.get("enabled"))
-            .and_then(toml::Value::as_bool)
+            .and_then(toml::Value::as_table)
+            .cloned()
+            .unwrap_or_default();
+        let enabled = config
+            .remove("enabled")
+            .and_then(|enabled| enabled.as_bool())
             .unwrap_or(false);
-        Some(FluxMetadata { enabled })
+        Some(FluxMetadata { enabled, config })
     }
 
     fn read_manifest(manifest_dir: &Path) -> toml::Value {
@@ -131,6 +125,8 @@ enum Context {
         build_script_build: bool,
         /// Metadata in the `Cargo.toml` manifest
         metadata: Option<FluxMetadata>,
+        /// Whether the package is a member of the workspace `cargo-flux` was called on
+        workspace_member: bool,
     },
     /// Called from `rustc-flux`
     RustcFlux {
@@ -144,7 +140,11 @@ impl Context {
         if env::var("FLUX_CARGO").is_ok() {
             let build_script_build =
                 arg_value(args, "--crate-name", |val| val == "build_script_build").is_some();
-            Context::CargoFlux { build_script_build, metadata: FluxMetadata::read() }
+            Context::CargoFlux {
+                build_script_build,
+                metadata: FluxMetadata::read(),
+                workspace_member: is_workspace_member(),
+            }
         } else {
             let force_full_compilation = env_var_is("FLUX_FULL_COMPILATION", "1");
             Context::RustcFlux { force_full_compilation }
@@ -153,7 +153,7 @@ impl Context {
 
     fn be_rustc(&self) -> bool {
         match self {
-            Context::CargoFlux { build_script_build, metadata: manifest } => {
+            Context::CargoFlux { build_script_build, metadata: manifest, .. } => {
                 *build_script_build || manifest.is_none()
             }
             Context::RustcFlux { .. } => false,
@@ -164,12 +164,19 @@ impl Context {
     /// `rustc-flux` on a single file or if Flux is explicitly enabled in the manifest.
     fn verify(&self) -> bool {
         match self {
-            Context::CargoFlux { metadata: Some(FluxMetadata { enabled }), .. } => *enabled,
+            Context::CargoFlux { metadata: Some(FluxMetadata { enabled, .. }), .. } => *enabled,
             Context::CargoFlux { metadata: None, .. } => false,
             Context::RustcFlux { .. } => true,
         }
     }
 
+    /// Whether to trust the bodies of the crate and only check its specs. Packages outside the
+    /// workspace are dependencies the user cannot change, so we only need their specs to check the
+    /// workspace against them.
+    fn trust_bodies(&self) -> bool {
+        matches!(self, Context::CargoFlux { workspace_member: false, .. })
+    }
+
     /// Whether to do a full compilation, i.e., continue after verification to generate artifacts.
     /// We always do a full compilation when called from `cargo-flux`. When called from `rustc-flux`
     /// we stop after verification so we don't generate artifacts unless full compilation is forced
@@ -182,6 +189,14 @@ impl Context {
     }
 }
 
+/// Whether the package being compiled is in the list of workspace members set by `cargo-flux`. If
+/// the list is not set every package is considered a member.
+fn is_workspace_member() -> bool {
+    let Some(members) = env::var_os("FLUX_WORKSPACE_MEMBERS") else { return true };
+    let Some(manifest_dir) = env::var_os("CARGO_MANIFEST_DIR") else { return true };
+    env::split_paths(&members).any(|member| member == Path::new(&manifest_dir))
+}
+
 fn env_var_is(name: &str, test: &str) -> bool {
     let Ok(val) = env::var(name) else { return false };
     val == test
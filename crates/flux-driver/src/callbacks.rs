@@ -1,5 +1,7 @@
-use flux_common::{bug, cache::QueryCache, dbg, iter::IterExt, result::ResultExt};
-use flux_config as config;
+use std::{env, io, path::Path, sync::Arc};
+
+use flux_common::{bug, iter::IterExt, result::ResultExt};
+use flux_config::{self as config, DefSelector};
 use flux_errors::FluxSession;
 use flux_fhir_analysis::compare_impl_item;
 use flux_infer::fixpoint_encoding::FixQueryCache;
@@ -8,24 +10,33 @@ use flux_middle::{fhir, global_env::GlobalEnv, queries::Providers, Specs};
 use flux_refineck as refineck;
 use itertools::Itertools;
 use refineck::CheckerConfig;
-use rustc_borrowck::consumers::ConsumerOptions;
+use rustc_data_structures::unord::UnordSet;
 use rustc_driver::{Callbacks, Compilation};
-use rustc_errors::ErrorGuaranteed;
-use rustc_hir::{
-    def::DefKind,
-    def_id::{DefId, LocalDefId},
-};
+use rustc_errors::{ErrorGuaranteed, LazyFallbackBundle};
+use rustc_hir::{def::DefKind, def_id::LocalDefId};
 use rustc_interface::{interface::Compiler, Queries};
 use rustc_middle::{query, ty::TyCtxt};
-use rustc_session::config::OutputType;
-use rustc_span::FileName;
+use rustc_session::{config::OutputType, Session};
+use rustc_span::{FileName, Span, Symbol};
 
-use crate::{collector::SpecCollector, DEFAULT_LOCALE_RESOURCES};
+use crate::{
+    api::{Collector, ItemStatus},
+    collector::SpecCollector,
+    coverage, references, spec_files, DEFAULT_LOCALE_RESOURCES,
+};
 
 #[derive(Default)]
 pub struct FluxCallbacks {
     pub full_compilation: bool,
     pub verify: bool,
+    /// Only check that the specs of the crate are well-formed, trusting the bodies of functions.
+    /// The specs are still saved in the metadata so dependent crates can be checked against them.
+    pub trust_bodies: bool,
+    /// Name of a dependency whose flux metadata should be printed instead of checking the crate
+    pub dump_metadata: Option<String>,
+    /// Set by [`crate::api::check_crate`] to collect diagnostics and the results of checking each item
+    /// instead of printing them
+    pub collector: Option<Collector>,
 }
 
 impl Callbacks for FluxCallbacks {
@@ -34,14 +45,36 @@ impl Callbacks for FluxCallbacks {
 
         config.override_queries = Some(|_, local| {
             local.mir_borrowck = mir_borrowck;
+            local.mir_drops_elaborated_and_const_checked = mir_drops_elaborated_and_const_checked;
+            local.promoted_mir = promoted_mir;
         });
     }
 
+    fn after_crate_root_parsing<'tcx>(
+        &mut self,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        if self.verify
+            && let Ok(mut krate) = queries.parse()
+        {
+            let flux_sess = self.flux_session(&compiler.sess);
+            spec_files::include_extern_spec_files(&compiler.sess, &flux_sess, krate.get_mut());
+            track_config(&compiler.sess);
+        }
+        Compilation::Continue
+    }
+
     fn after_analysis<'tcx>(
         &mut self,
         compiler: &Compiler,
         queries: &'tcx Queries<'tcx>,
     ) -> Compilation {
+        if let Some(krate) = &self.dump_metadata {
+            dump_metadata(compiler, queries, krate);
+            return Compilation::Stop;
+        }
+
         if self.verify {
             self.verify(compiler, queries);
         }
@@ -55,42 +88,142 @@ impl Callbacks for FluxCallbacks {
 }
 
 impl FluxCallbacks {
+    /// The session used to report flux errors. They are collected instead of printed when checking
+    /// a crate through [`crate::api`].
+    fn flux_session(&self, sess: &Session) -> FluxSession {
+        match &self.collector {
+            Some(collector) => {
+                let source_map = sess.psess.clone_source_map();
+                let emitter = collector.emitter(source_map.clone(), fallback_bundle());
+                FluxSession::with_emitter(source_map, Box::new(emitter))
+            }
+            None => flux_session(sess),
+        }
+    }
+
     fn verify<'tcx>(&self, compiler: &Compiler, queries: &'tcx Queries<'tcx>) {
         if compiler.sess.dcx().has_errors().is_some() {
             return;
         }
 
         queries.global_ctxt().unwrap().enter(|tcx| {
-            let sess = FluxSession::new(
-                &tcx.sess.opts,
-                tcx.sess.psess.clone_source_map(),
-                rustc_errors::fallback_fluent_bundle(DEFAULT_LOCALE_RESOURCES.to_vec(), false),
-            );
+            if let Some(collector) = &self.collector {
+                collector.start();
+            }
+            let sess = self.flux_session(tcx.sess);
 
             let mut providers = Providers::default();
             flux_desugar::provide(&mut providers);
             flux_fhir_analysis::provide(&mut providers);
             providers.collect_specs = collect_specs;
 
-            let cstore = CStore::load(tcx, &sess);
+            let cstore = CStore::load(tcx, &sess, SpecCollector::prelude_enabled(tcx));
             let arena = fhir::Arena::new();
             GlobalEnv::enter(tcx, &sess, Box::new(cstore), &arena, providers, |genv| {
-                if check_crate(genv).is_ok() {
+                if self.trust_bodies {
+                    if flux_fhir_analysis::check_crate_wf(genv).is_ok() {
+                        encode_and_save_metadata(genv);
+                    }
+                    return;
+                }
+                let result = check_crate(genv, self.collector.as_ref());
+                if config::dump_checker_trace()
+                    && let Err(err) = references::dump_references(genv)
+                {
+                    genv.sess()
+                        .emit_err(errors::CannotWriteOutput::new("references", err));
+                }
+                if config::coverage()
+                    && let Err(err) = coverage::dump_coverage(genv)
+                {
+                    genv.sess()
+                        .emit_err(errors::CannotWriteOutput::new("coverage", err));
+                }
+                if result.is_ok() {
                     encode_and_save_metadata(genv);
                 }
             });
-            sess.finish_diagnostics();
+            // Errors are part of the results when collecting so we don't abort
+            if self.collector.is_none() {
+                sess.finish_diagnostics();
+            }
         });
     }
 }
 
-fn check_crate(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
+/// Environment variables set by `cargo flux` and its options. They change what flux checks or
+/// outputs, so cargo must check a crate again when they change.
+const TRACKED_ENV_VARS: &[&str] = &[
+    "FLUX_WORKSPACE_MEMBERS",
+    "FLUX_CHECK_DEF",
+    "FLUX_CHECK_FILES",
+    "FLUX_EXTERN_SPECS",
+    "FLUX_DUMP_CONSTRAINT",
+    "FLUX_DUMP_CHECKER_TRACE",
+    "FLUX_DUMP_TIMINGS",
+    "FLUX_DUMP_MIR",
+    "FLUX_DUMP_FHIR",
+    "FLUX_DUMP_RTY",
+    "FLUX_CACHE",
+    "FLUX_EXPLAIN",
+    "FLUX_COVERAGE",
+    "FLUX_LOG_DIR",
+];
+
+/// Records the configuration of flux in the dep-info file so cargo checks the crate again when it
+/// changes. Otherwise, e.g., running `cargo flux --coverage` after `cargo flux` wouldn't produce a
+/// report for crates that are already fresh.
+fn track_config(sess: &Session) {
+    let mut env_depinfo = sess.psess.env_depinfo.lock();
+    for var in TRACKED_ENV_VARS {
+        let value = env::var(var).ok().map(|value| Symbol::intern(&value));
+        env_depinfo.insert((Symbol::intern(var), value));
+    }
+
+    // The config file and the manifest with the `[package.metadata.flux]` table
+    let mut file_depinfo = sess.psess.file_depinfo.lock();
+    if let Some(path) = config::CONFIG_PATH.as_ref() {
+        file_depinfo.insert(Symbol::intern(&path.to_string_lossy()));
+    }
+    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        let manifest = Path::new(&manifest_dir).join("Cargo.toml");
+        file_depinfo.insert(Symbol::intern(&manifest.to_string_lossy()));
+    }
+}
+
+fn dump_metadata<'tcx>(compiler: &Compiler, queries: &'tcx Queries<'tcx>, krate: &str) {
+    if compiler.sess.dcx().has_errors().is_some() {
+        return;
+    }
+
+    queries.global_ctxt().unwrap().enter(|tcx| {
+        let sess = flux_session(tcx.sess);
+        // Ignore broken pipes so the output can be piped into `head` and friends
+        if let Err(err) =
+            flux_metadata::dump_crate_metadata(tcx, &sess, krate, &mut io::stdout().lock())
+            && err.kind() != io::ErrorKind::BrokenPipe
+        {
+            bug!("failed to write flux metadata: {err}");
+        }
+        sess.finish_diagnostics();
+    });
+}
+
+fn flux_session(sess: &Session) -> FluxSession {
+    FluxSession::new(&sess.opts, sess.psess.clone_source_map(), fallback_bundle())
+}
+
+fn fallback_bundle() -> LazyFallbackBundle {
+    rustc_errors::fallback_fluent_bundle(DEFAULT_LOCALE_RESOURCES.to_vec(), false)
+}
+
+fn check_crate(genv: GlobalEnv, collector: Option<&Collector>) -> Result<(), ErrorGuaranteed> {
     tracing::info_span!("check_crate").in_scope(move || {
         tracing::info!("Callbacks::check_wf");
 
         flux_fhir_analysis::check_crate_wf(genv)?;
 
-        let mut ck = CrateChecker::new(genv);
+        let mut ck = CrateChecker::new(genv, collector);
 
         let crate_items = genv.tcx().hir_crate_items(());
 
@@ -134,25 +267,35 @@ fn encode_and_save_metadata(genv: GlobalEnv) {
     }
 }
 
-struct CrateChecker<'genv, 'tcx> {
+struct CrateChecker<'genv, 'tcx, 'a> {
     genv: GlobalEnv<'genv, 'tcx>,
     cache: FixQueryCache,
     checker_config: CheckerConfig,
+    collector: Option<&'a Collector>,
+    /// Definitions selected with [`config::check_def`], `None` if every definition is selected
+    selected: Option<UnordSet<LocalDefId>>,
 }
 
-impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
-    fn new(genv: GlobalEnv<'genv, 'tcx>) -> Self {
+impl<'genv, 'tcx, 'a> CrateChecker<'genv, 'tcx, 'a> {
+    fn new(genv: GlobalEnv<'genv, 'tcx>, collector: Option<&'a Collector>) -> Self {
         let crate_config = genv.crate_config().unwrap_or_default();
         let checker_config = CheckerConfig {
             check_overflow: crate_config.check_overflow,
             scrape_quals: crate_config.scrape_quals,
         };
-        CrateChecker { genv, cache: QueryCache::load(), checker_config }
+        let mut cache = FixQueryCache::load();
+        if let Some(collector) = collector {
+            cache.record();
+            collector.flush_crate_diagnostics();
+        }
+        let selected = select_defs(genv, config::check_def());
+        CrateChecker { genv, cache, checker_config, collector, selected }
     }
 
-    fn matches_check_def(&self, def_id: DefId) -> bool {
-        let def_path = self.genv.tcx().def_path_str(def_id);
-        def_path.contains(config::check_def())
+    fn matches_check_def(&self, def_id: LocalDefId) -> bool {
+        self.selected
+            .as_ref()
+            .map_or(true, |selected| selected.contains(&def_id))
     }
 
     fn matches_check_file(&self, def_id: LocalDefId) -> bool {
@@ -169,15 +312,66 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
     }
 
     fn check_def_catching_bugs(&mut self, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
-        let mut this = std::panic::AssertUnwindSafe(self);
+        let mut this = std::panic::AssertUnwindSafe(&mut *self);
         let msg = format!("def_id: {:?}, span: {:?}", def_id, this.genv.tcx().def_span(def_id));
-        flux_common::bug::catch_bugs(&msg, move || this.check_def(def_id))?
+        let result = flux_common::bug::catch_bugs(&msg, move || this.check_def(def_id))
+            .and_then(|result| result);
+        if let Some(collector) = self.collector {
+            self.record_item(collector, def_id, &result);
+        }
+        result
+    }
+
+    fn record_item(
+        &mut self,
+        collector: &Collector,
+        def_id: LocalDefId,
+        result: &Result<(), ErrorGuaranteed>,
+    ) {
+        let genv = self.genv;
+        let kind = genv.def_kind(def_id);
+        if !matches!(
+            kind,
+            DefKind::Fn
+                | DefKind::AssocFn
+                | DefKind::Struct
+                | DefKind::Enum
+                | DefKind::Impl { .. }
+                | DefKind::TyAlias
+        ) || genv.is_dummy(def_id)
+        {
+            return;
+        }
+        let resolved_id = genv.maybe_extern_id(def_id).resolved_id();
+        let status = if result.is_err() {
+            ItemStatus::Failed
+        } else if !self.matches_check_def(def_id) || !self.matches_check_file(def_id) {
+            ItemStatus::Skipped
+        } else if genv.ignored(def_id) {
+            ItemStatus::Ignored
+        } else if matches!(kind, DefKind::Fn | DefKind::AssocFn) && genv.trusted(def_id) {
+            ItemStatus::Trusted
+        } else {
+            ItemStatus::Verified
+        };
+        let tcx = genv.tcx();
+        let def_path = format!(
+            "{}{}",
+            tcx.crate_name(resolved_id.krate),
+            tcx.def_path(resolved_id).to_string_no_crate_verbose()
+        );
+        collector.record_item(
+            def_path,
+            tcx.def_path_str(resolved_id),
+            status,
+            self.cache.take_records(),
+        );
     }
 
     fn check_def(&mut self, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
         let def_id = self.genv.maybe_extern_id(def_id);
 
-        if !self.matches_check_def(def_id.resolved_id()) {
+        if !self.matches_check_def(def_id.local_id()) {
             return Ok(());
         }
         if self.genv.ignored(def_id.local_id()) || self.genv.is_dummy(def_id.local_id()) {
@@ -245,27 +439,93 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
     }
 }
 
+/// Returns the definitions selected by `selectors` or `None` if there are no selectors. Closures,
+/// coroutines and constants in bodies or types, e.g., array lengths, are checked along with the item
+/// they are defined in, so selecting one of them selects that item.
+fn select_defs(genv: GlobalEnv, selectors: &[DefSelector]) -> Option<UnordSet<LocalDefId>> {
+    if selectors.is_empty() {
+        return None;
+    }
+    let tcx = genv.tcx();
+    let candidates = tcx
+        .hir_crate_items(())
+        .definitions()
+        .chain(tcx.hir().body_owners())
+        .unique()
+        .collect_vec();
+    let owner = |def_id: LocalDefId| tcx.local_def_id_to_hir_id(def_id).owner.def_id;
+
+    let mut selected = UnordSet::default();
+    let mut paths = None;
+    for selector in selectors {
+        match selector {
+            DefSelector::Path(_) => {
+                // Extern specs are selected by the path of the item they refine
+                let paths = paths.get_or_insert_with(|| {
+                    candidates
+                        .iter()
+                        .map(|def_id| {
+                            (*def_id, tcx.def_path_str(genv.maybe_extern_id(*def_id).resolved_id()))
+                        })
+                        .collect_vec()
+                });
+                for (def_id, path) in paths.iter() {
+                    if selector.matches_path(path) {
+                        selected.insert(owner(*def_id));
+                    }
+                }
+            }
+            DefSelector::Location { file, line } => {
+                let innermost = candidates
+                    .iter()
+                    .filter_map(|def_id| {
+                        let span = span_with_attrs(tcx, *def_id);
+                        span_contains_line(tcx, span, file, *line).then_some((*def_id, span))
+                    })
+                    .min_by_key(|(_, span)| span.hi() - span.lo());
+                if let Some((def_id, _)) = innermost {
+                    selected.insert(owner(def_id));
+                }
+            }
+        }
+    }
+    Some(selected)
+}
+
+/// The span of a definition including its body and attributes, so a line with a spec selects the
+/// definition it's attached to.
+fn span_with_attrs(tcx: TyCtxt, def_id: LocalDefId) -> Span {
+    let hir_id = tcx.local_def_id_to_hir_id(def_id);
+    tcx.hir()
+        .attrs(hir_id)
+        .iter()
+        .map(|attr| attr.span)
+        .filter(|span| !span.from_expansion())
+        .fold(tcx.hir().span_with_body(hir_id), Span::to)
+}
+
+fn span_contains_line(tcx: TyCtxt, span: Span, file: &Path, line: usize) -> bool {
+    let sm = tcx.sess.source_map();
+    let lo = sm.lookup_char_pos(span.lo());
+    let hi = sm.lookup_char_pos(span.hi());
+    if !Arc::ptr_eq(&lo.file, &hi.file) || !(lo.line..=hi.line).contains(&line) {
+        return false;
+    }
+    if let FileName::Real(file_name) = &lo.file.name
+        && let Some(path) = file_name.local_path()
+    {
+        path.ends_with(file)
+    } else {
+        false
+    }
+}
+
 #[expect(clippy::needless_lifetimes, reason = "we want to be explicit about lifetimes here")]
 fn mir_borrowck<'tcx>(
     tcx: TyCtxt<'tcx>,
     def_id: LocalDefId,
 ) -> query::queries::mir_borrowck::ProvidedValue<'tcx> {
-    let body_with_facts = rustc_borrowck::consumers::get_body_with_borrowck_facts(
-        tcx,
-        def_id,
-        ConsumerOptions::RegionInferenceContext,
-    );
-
-    if config::dump_mir() {
-        rustc_middle::mir::pretty::write_mir_fn(
-            tcx,
-            &body_with_facts.body,
-            &mut |_, _| Ok(()),
-            &mut dbg::writer_for_item(tcx, def_id.to_def_id(), "mir").unwrap(),
-            rustc_middle::mir::pretty::PrettyPrintMirOptions::from_cli(tcx),
-        )
-        .unwrap();
-    }
+    let body_with_facts = flux_common::mir_storage::compute_mir_body(tcx, def_id);
 
     // SAFETY: This is safe because we are feeding in the same `tcx` that is
     // going to be used as a witness when pulling out the data.
@@ -277,3 +537,71 @@ fn mir_borrowck<'tcx>(
     let original_mir_borrowck = providers.mir_borrowck;
     original_mir_borrowck(tcx, def_id)
 }
+
+/// Steals the body returned by `mir_promoted`
+#[expect(clippy::needless_lifetimes, reason = "we want to be explicit about lifetimes here")]
+fn mir_drops_elaborated_and_const_checked<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+) -> query::queries::mir_drops_elaborated_and_const_checked::ProvidedValue<'tcx> {
+    store_mir_body_before_steal(tcx, def_id);
+    let mut providers = rustc_middle::util::Providers::default();
+    rustc_mir_transform::provide(&mut providers);
+    let original_mir_drops_elaborated_and_const_checked =
+        providers.queries.mir_drops_elaborated_and_const_checked;
+    original_mir_drops_elaborated_and_const_checked(tcx, def_id)
+}
+
+/// Steals the promoted bodies returned by `mir_promoted`
+#[expect(clippy::needless_lifetimes, reason = "we want to be explicit about lifetimes here")]
+fn promoted_mir<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+) -> query::queries::promoted_mir::ProvidedValue<'tcx> {
+    store_mir_body_before_steal(tcx, def_id);
+    let mut providers = rustc_middle::util::Providers::default();
+    rustc_mir_transform::provide(&mut providers);
+    let original_promoted_mir = providers.queries.promoted_mir;
+    original_promoted_mir(tcx, def_id)
+}
+
+/// Both queries stealing the result of `mir_promoted` run [`mir_borrowck`] first, which stores the
+/// body. But with incremental compilation rustc may reuse the result of the borrow checker from the
+/// previous session without running it, and then we must store the body here because we cannot
+/// compute it once `mir_promoted` is stolen.
+fn store_mir_body_before_steal(tcx: TyCtxt, def_id: LocalDefId) {
+    if tcx.is_synthetic_mir(def_id) || tcx.is_constructor(def_id.to_def_id()) {
+        return;
+    }
+    tcx.ensure().mir_borrowck(def_id);
+    if flux_common::mir_storage::is_mir_body_computed(def_id) {
+        return;
+    }
+    let body_with_facts = flux_common::mir_storage::compute_mir_body(tcx, def_id);
+    // SAFETY: This is safe because we are feeding in the same `tcx` that is
+    // going to be used as a witness when pulling out the data.
+    unsafe {
+        flux_common::mir_storage::store_mir_body(tcx, def_id, body_with_facts);
+    }
+}
+
+mod errors {
+    use std::{io, path::Path};
+
+    use flux_errors::E9080;
+    use flux_macros::Diagnostic;
+
+    #[derive(Diagnostic)]
+    #[diag(driver_cannot_write_output, code = E9080)]
+    pub(super) struct CannotWriteOutput {
+        what: &'static str,
+        dir: &'static Path,
+        err: io::Error,
+    }
+
+    impl CannotWriteOutput {
+        pub(super) fn new(what: &'static str, err: io::Error) -> Self {
+            Self { what, dir: flux_config::log_dir(), err }
+        }
+    }
+}
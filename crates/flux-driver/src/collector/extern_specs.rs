@@ -1,4 +1,4 @@
-use std::iter;
+use std::{collections::HashMap, iter};
 
 use flux_middle::ExternSpecMappingErr;
 use flux_rustc_bridge::lowering;
@@ -71,6 +71,7 @@ impl<'a, 'sess, 'tcx> ExternSpecCollector<'a, 'sess, 'tcx> {
         let extern_id = self.extract_extern_id_from_fn(item)?;
         self.insert_extern_id(item.owner_id.def_id, extern_id)?;
         self.check_generics(item.owner_id, extern_id)?;
+        self.check_fn_sig(item.owner_id, extern_id)?;
 
         Ok(())
     }
@@ -132,6 +133,7 @@ impl<'a, 'sess, 'tcx> ExternSpecCollector<'a, 'sess, 'tcx> {
         }
 
         let mut extern_impl_id = impl_of_trait;
+        let mut extern_fns = vec![];
         for item in impl_.items {
             let item_id = item.id.owner_id.def_id;
             let extern_item = if let hir::AssocItemKind::Fn { .. } = item.kind {
@@ -144,6 +146,7 @@ impl<'a, 'sess, 'tcx> ExternSpecCollector<'a, 'sess, 'tcx> {
             if *extern_impl_id.get_or_insert(extern_item.impl_id) != extern_item.impl_id {
                 return Err(self.invalid_impl_block());
             }
+            extern_fns.push((item.id.owner_id, extern_item.item_id));
         }
 
         if let Some(extern_impl_id) = extern_impl_id {
@@ -151,7 +154,12 @@ impl<'a, 'sess, 'tcx> ExternSpecCollector<'a, 'sess, 'tcx> {
             self.insert_extern_id(impl_id.def_id, extern_impl_id)?;
         }
 
-        Ok(())
+        // Signatures can only be compared once we know the generics of the impl match, and we
+        // check all of them to report every stale method in the block.
+        extern_fns
+            .into_iter()
+            .map(|(local_id, extern_id)| self.check_fn_sig(local_id, extern_id))
+            .fold(Ok(()), Result::and)
     }
 
     fn collect_extern_impl_fn(
@@ -208,6 +216,7 @@ impl<'a, 'sess, 'tcx> ExternSpecCollector<'a, 'sess, 'tcx> {
         let extern_fn_id = self.extract_extern_id_from_trait_fn(extern_trait_id, item)?;
         self.insert_extern_id(item.id.owner_id.def_id, extern_fn_id)?;
         self.check_generics(item_id, extern_fn_id)?;
+        self.check_fn_sig(item_id, extern_fn_id)?;
 
         Ok(())
     }
@@ -403,6 +412,108 @@ impl<'a, 'sess, 'tcx> ExternSpecCollector<'a, 'sess, 'tcx> {
         }
     }
 
+    /// Checks that the unrefined signature of a function in an extern spec matches the current
+    /// signature of the external function. The body of the spec calls the external function, but
+    /// type checking it only guarantees the arguments can be coerced and lifetimes are left to
+    /// inference, so a spec can go stale when a dependency changes without rustc complaining.
+    ///
+    /// Must be called after [`Self::check_generics`] succeeded for the function and its parent,
+    /// because we instantiate the signature of the external function with the generics of the spec.
+    fn check_fn_sig(&self, local_id: OwnerId, extern_id: DefId) -> Result {
+        let tcx = self.tcx();
+        let name = tcx.def_path_str(extern_id);
+        let args = ty::GenericArgs::identity_for_item(tcx, local_id);
+        let local_sig = tcx.fn_sig(local_id).instantiate_identity();
+        let extern_sig = tcx.fn_sig(extern_id).instantiate(tcx, args);
+        let decl = tcx.hir().fn_decl_by_hir_id(local_id.into()).unwrap();
+
+        let local_inputs = local_sig.inputs().skip_binder();
+        let extern_inputs = extern_sig.inputs().skip_binder();
+        if local_inputs.len() != extern_inputs.len() {
+            return Err(self.emit(errors::MismatchedParamCount {
+                span: ident_or_def_span(tcx, local_id),
+                extern_def: tcx.def_span(extern_id),
+                name,
+                found: local_inputs.len(),
+                expected: extern_inputs.len(),
+            }));
+        }
+
+        // Compare types modulo regions first. Projections are normalized because the external
+        // signature may refer to associated types the spec writes out explicitly.
+        let param_env = tcx.param_env(local_id);
+        let local_erased = tcx.instantiate_bound_regions_with_erased(local_sig);
+        let extern_erased = tcx.instantiate_bound_regions_with_erased(extern_sig);
+        let normalize = |sig: ty::FnSig<'tcx>| {
+            tcx.try_normalize_erasing_regions(param_env, sig)
+                .unwrap_or_else(|_| tcx.erase_regions(sig))
+        };
+        let params = tcx.hir().body_owned_by(local_id.def_id).params;
+        let spans = || {
+            iter::zip(decl.inputs, params)
+                .map(|(ty, param)| param_span(ty, param))
+                .chain([decl.output.span()])
+        };
+        let mut result = Ok(());
+        for ((found, expected), span) in iter::zip(
+            normalize(local_erased).inputs_and_output,
+            normalize(extern_erased).inputs_and_output,
+        )
+        .zip(spans())
+        {
+            if found != expected {
+                result = Err(self.emit(errors::MismatchedSigTy {
+                    span,
+                    extern_def: tcx.def_span(extern_id),
+                    name: name.clone(),
+                    found,
+                    expected,
+                }));
+            }
+        }
+        result?;
+
+        // The types match, so we check that the lifetimes in the spec can be renamed to the ones in
+        // the external signature, i.e., there's a bijection between their late-bound regions and
+        // other regions are the same. We can only compare regions position by position in types
+        // that are syntactically equal, so types that only match after normalization are skipped.
+        let mut local_to_extern = HashMap::new();
+        let mut extern_to_local = HashMap::new();
+        let mut regions_match = |a: ty::Region<'tcx>, b: ty::Region<'tcx>| {
+            match (a.kind(), b.kind()) {
+                (ty::ReBound(..), ty::ReBound(..)) => {
+                    *local_to_extern.entry(a).or_insert(b) == b
+                        && *extern_to_local.entry(b).or_insert(a) == a
+                }
+                _ => a == b,
+            }
+        };
+        let positions = iter::zip(
+            iter::zip(local_erased.inputs_and_output, extern_erased.inputs_and_output),
+            iter::zip(
+                local_sig.skip_binder().inputs_and_output,
+                extern_sig.skip_binder().inputs_and_output,
+            ),
+        );
+        for (((local_erased, extern_erased), (local_ty, extern_ty)), span) in positions.zip(spans())
+        {
+            if local_erased != extern_erased {
+                continue;
+            }
+            let regions = |ty: ty::Ty<'tcx>| ty.walk().filter_map(|arg| arg.as_region());
+            if !iter::zip(regions(local_ty), regions(extern_ty)).all(|(a, b)| regions_match(a, b)) {
+                return Err(self.emit(errors::MismatchedSigLifetimes {
+                    span,
+                    extern_def: tcx.def_span(extern_id),
+                    name,
+                    found: local_sig.to_string(),
+                    expected: extern_sig.to_string(),
+                }));
+            }
+        }
+        Ok(())
+    }
+
     #[track_caller]
     fn malformed(&self) -> ErrorGuaranteed {
         self.emit(errors::MalformedExternSpec::new(self.block.span))
@@ -491,13 +602,24 @@ fn ident_or_def_span(tcx: TyCtxt, def_id: impl Into<DefId>) -> Span {
         .unwrap_or_else(|| tcx.def_span(def_id))
 }
 
+/// The span of the type of a parameter in an extern spec, or of its pattern if the type wasn't
+/// written by the user, which happens for receivers rewritten by the extern spec macro.
+fn param_span(ty: &hir::Ty, param: &hir::Param) -> Span {
+    if param.pat.span.hi() <= ty.span.lo() {
+        ty.span
+    } else {
+        param.pat.span
+    }
+}
+
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::E9023;
     use flux_macros::Diagnostic;
+    use rustc_middle::ty::Ty;
     use rustc_span::Span;
 
     #[derive(Diagnostic)]
-    #[diag(driver_malformed_extern_spec, code = E0999)]
+    #[diag(driver_malformed_extern_spec, code = E9023)]
     pub(super) struct MalformedExternSpec {
         #[primary_span]
         span: Span,
@@ -510,7 +632,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_cannot_resolve_trait_impl, code = E0999)]
+    #[diag(driver_cannot_resolve_trait_impl, code = E9023)]
     #[note]
     pub(super) struct CannotResolveTraitImpl {
         #[primary_span]
@@ -518,7 +640,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_invalid_impl_block, code = E0999)]
+    #[diag(driver_invalid_impl_block, code = E9023)]
     pub(super) struct InvalidImplBlock {
         #[primary_span]
         #[label]
@@ -526,7 +648,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_item_not_in_trait_impl, code = E0999)]
+    #[diag(driver_item_not_in_trait_impl, code = E9023)]
     pub(super) struct ItemNotInTraitImpl {
         #[primary_span]
         #[label]
@@ -537,7 +659,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_invalid_item_in_inherent_impl, code = E0999)]
+    #[diag(driver_invalid_item_in_inherent_impl, code = E9023)]
     pub(super) struct InvalidItemInInherentImpl {
         #[primary_span]
         #[label]
@@ -548,7 +670,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_item_not_in_trait, code = E0999)]
+    #[diag(driver_item_not_in_trait, code = E9023)]
     pub(super) struct ItemNotInTrait {
         #[primary_span]
         #[label]
@@ -559,7 +681,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_extern_spec_for_local_def, code = E0999)]
+    #[diag(driver_extern_spec_for_local_def, code = E9023)]
     pub(super) struct ExternSpecForLocalDef {
         #[primary_span]
         pub span: Span,
@@ -569,7 +691,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_dup_extern_spec, code = E0999)]
+    #[diag(driver_dup_extern_spec, code = E9023)]
     pub(super) struct DupExternSpec {
         #[primary_span]
         #[label]
@@ -580,7 +702,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_mismatched_generics, code = E0999)]
+    #[diag(driver_mismatched_generics, code = E9023)]
     #[note]
     pub(super) struct MismatchedGenerics {
         #[primary_span]
@@ -590,4 +712,46 @@ mod errors {
         pub extern_def: Span,
         pub def_descr: &'static str,
     }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_mismatched_param_count, code = E9023)]
+    #[help]
+    pub(super) struct MismatchedParamCount {
+        #[primary_span]
+        #[label]
+        pub span: Span,
+        #[label(driver_extern_def_label)]
+        pub extern_def: Span,
+        pub name: String,
+        pub found: usize,
+        pub expected: usize,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_mismatched_sig_ty, code = E9023)]
+    #[note]
+    pub(super) struct MismatchedSigTy<'tcx> {
+        #[primary_span]
+        #[label]
+        pub span: Span,
+        #[label(driver_extern_def_label)]
+        pub extern_def: Span,
+        pub name: String,
+        pub found: Ty<'tcx>,
+        pub expected: Ty<'tcx>,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_mismatched_sig_lifetimes, code = E9023)]
+    #[note]
+    pub(super) struct MismatchedSigLifetimes {
+        #[primary_span]
+        #[label]
+        pub span: Span,
+        #[label(driver_extern_def_label)]
+        pub extern_def: Span,
+        pub name: String,
+        pub found: String,
+        pub expected: String,
+    }
 }
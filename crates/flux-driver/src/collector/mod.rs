@@ -8,7 +8,7 @@ use flux_common::{
     result::{ErrorCollector, ResultExt},
     tracked_span_assert_eq,
 };
-use flux_config::{self as config, CrateConfig};
+use flux_config::{self as config, CrateConfig, LintLevel, LintLevels};
 use flux_errors::{Errors, FluxSession};
 use flux_middle::{
     fhir::{CheckOverflow, Ignored, Trusted},
@@ -31,6 +31,9 @@ use rustc_span::{symbol::sym, Span, Symbol, SyntaxContext};
 
 type Result<T = ()> = std::result::Result<T, ErrorGuaranteed>;
 
+/// Prefix of the name of the modules in which standalone spec files are included
+pub(crate) const EXTERN_SPEC_FILE_MOD: &str = "__flux_extern_specs";
+
 pub(crate) struct SpecCollector<'sess, 'tcx> {
     tcx: TyCtxt<'tcx>,
     parse_sess: ParseSess,
@@ -82,10 +85,30 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         Ok(collector.specs)
     }
 
+    /// Whether the crate enables the flux prelude with `#![flux::cfg(prelude = true)]`. We need to
+    /// know this before collecting specs to decide which metadata to load. Errors in the attribute
+    /// are ignored here and reported when collecting specs.
+    pub(crate) fn prelude_enabled(tcx: TyCtxt) -> bool {
+        tcx.hir().krate_attrs().iter().any(|attr| {
+            if let AttrKind::Normal(attr_item, ..) = &attr.kind
+                && let [first, second] = &attr_item.item.path.segments[..]
+                && matches!(first.ident.as_str(), "flux" | "flux_tool")
+                && second.ident.as_str() == "cfg"
+            {
+                FluxAttrCFG::parse_cfg(&attr_item.item)
+                    .and_then(|mut cfg| cfg.try_into_crate_cfg())
+                    .is_ok_and(|crate_cfg| crate_cfg.prelude)
+            } else {
+                false
+            }
+        })
+    }
+
     fn collect_crate(&mut self) -> Result {
         let mut attrs = self.parse_attrs_and_report_dups(CRATE_DEF_ID)?;
         self.collect_ignore_and_trusted(&mut attrs, CRATE_DEF_ID);
         self.collect_check_overflow(&mut attrs, CRATE_DEF_ID);
+        self.collect_lint_levels(&mut attrs, CRATE_DEF_ID);
         self.specs
             .flux_items_by_parent
             .entry(CRATE_OWNER_ID)
@@ -101,6 +124,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         let mut attrs = self.parse_attrs_and_report_dups(owner_id.def_id)?;
         self.collect_ignore_and_trusted(&mut attrs, owner_id.def_id);
         self.collect_check_overflow(&mut attrs, owner_id.def_id);
+        self.collect_lint_levels(&mut attrs, owner_id.def_id);
 
         match &item.kind {
             ItemKind::Fn(..) => {
@@ -146,6 +170,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         let mut attrs = self.parse_attrs_and_report_dups(owner_id.def_id)?;
         self.collect_ignore_and_trusted(&mut attrs, owner_id.def_id);
         self.collect_check_overflow(&mut attrs, owner_id.def_id);
+        self.collect_lint_levels(&mut attrs, owner_id.def_id);
         if let rustc_hir::TraitItemKind::Fn(_, _) = trait_item.kind {
             self.collect_fn_spec(owner_id, attrs)?;
         }
@@ -159,6 +184,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         let mut attrs = self.parse_attrs_and_report_dups(owner_id.def_id)?;
         self.collect_ignore_and_trusted(&mut attrs, owner_id.def_id);
         self.collect_check_overflow(&mut attrs, owner_id.def_id);
+        self.collect_lint_levels(&mut attrs, owner_id.def_id);
 
         if let ImplItemKind::Fn(..) = &impl_item.kind {
             self.collect_fn_spec(owner_id, attrs)?;
@@ -168,6 +194,14 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
     }
 
     fn collect_mod(&mut self, module_id: OwnerId, mut attrs: FluxAttrs) -> Result {
+        if self
+            .tcx
+            .item_name(module_id.to_def_id())
+            .as_str()
+            .starts_with(EXTERN_SPEC_FILE_MOD)
+        {
+            self.specs.extern_spec_files.insert(module_id.def_id);
+        }
         self.specs
             .flux_items_by_parent
             .entry(module_id)
@@ -328,6 +362,10 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
             self.specs.should_fail.insert(owner_id.def_id);
         }
 
+        if attrs.explain() {
+            self.specs.explain.insert(owner_id.def_id);
+        }
+
         let qual_names: Option<surface::QualNames> = attrs.qual_names();
         Ok(self
             .specs
@@ -447,6 +485,12 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
                         .into(),
                 )
             }
+            ("trusted_derive", AttrArgs::Delimited(..)) => {
+                FluxAttrKind::TrustedDerive(self.parse_derive_names(attr_item)?)
+            }
+            ("ignore_derive", AttrArgs::Delimited(..)) => {
+                FluxAttrKind::IgnoreDerive(self.parse_derive_names(attr_item)?)
+            }
             ("trusted_impl", _) => {
                 FluxAttrKind::TrustedImpl(
                     parse_yes_no_with_reason(attr_item)
@@ -461,14 +505,58 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
                         .into(),
                 )
             }
+            ("allow" | "warn" | "deny", AttrArgs::Delimited(..)) => {
+                let level = LintLevel::from_name(segment.ident.as_str()).unwrap();
+                FluxAttrKind::LintLevels(self.parse_lint_levels(attr_item, level)?)
+            }
             ("opaque", AttrArgs::Empty) => FluxAttrKind::Opaque,
             ("extern_spec", AttrArgs::Empty) => FluxAttrKind::ExternSpec,
             ("should_fail", AttrArgs::Empty) => FluxAttrKind::ShouldFail,
+            ("explain", AttrArgs::Empty) => FluxAttrKind::Explain,
             _ => return Err(invalid_attr_err(self)),
         };
         Ok(FluxAttr { kind, span: attr_item.span() })
     }
 
+    fn parse_lint_levels(&mut self, attr_item: &AttrItem, level: LintLevel) -> Result<LintLevels> {
+        let Some(MetaItemKind::List(items)) = attr_item.meta_kind() else {
+            return Err(self
+                .errors
+                .emit(errors::InvalidAttr { span: attr_item.span() }));
+        };
+        let mut levels = LintLevels::default();
+        for item in &items {
+            let Some(ident) = item.ident().filter(|_| item.is_word()) else {
+                return Err(self.errors.emit(errors::InvalidAttr { span: item.span() }));
+            };
+            if !levels.set(ident.as_str(), level) {
+                return Err(self
+                    .errors
+                    .emit(errors::UnknownLint { span: item.span(), name: ident.name }));
+            }
+        }
+        Ok(levels)
+    }
+
+    /// Parses the list of derive macro names in `#[flux::trusted_derive(..)]` and
+    /// `#[flux::ignore_derive(..)]`
+    fn parse_derive_names(&mut self, attr_item: &AttrItem) -> Result<Vec<Symbol>> {
+        let Some(MetaItemKind::List(items)) = attr_item.meta_kind() else {
+            return Err(self
+                .errors
+                .emit(errors::InvalidAttr { span: attr_item.span() }));
+        };
+        items
+            .iter()
+            .map(|item| {
+                item.ident()
+                    .filter(|_| item.is_word())
+                    .map(|ident| ident.name)
+                    .ok_or_else(|| self.errors.emit(errors::InvalidAttr { span: item.span() }))
+            })
+            .try_collect_exhaust()
+    }
+
     fn parse<T>(
         &mut self,
         dargs: &rustc_ast::DelimArgs,
@@ -508,6 +596,12 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         if let Some(trusted_impl) = attrs.trusted_impl() {
             self.specs.trusted_impl.insert(def_id, trusted_impl);
         }
+        if let Some(derives) = attrs.trusted_derives() {
+            self.specs.trusted_derives.insert(def_id, derives);
+        }
+        if let Some(derives) = attrs.ignored_derives() {
+            self.specs.ignored_derives.insert(def_id, derives);
+        }
     }
 
     fn collect_check_overflow(&mut self, attrs: &mut FluxAttrs, def_id: LocalDefId) {
@@ -515,6 +609,12 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
             self.specs.check_overflows.insert(def_id, check_overflow);
         }
     }
+
+    fn collect_lint_levels(&mut self, attrs: &mut FluxAttrs, def_id: LocalDefId) {
+        if let Some(lint_levels) = attrs.lint_levels() {
+            self.specs.lint_levels.insert(def_id, lint_levels);
+        }
+    }
 }
 
 fn parse_yes_no_with_reason(attr_item: &AttrItem) -> std::result::Result<bool, ()> {
@@ -574,6 +674,8 @@ struct FluxAttr {
 enum FluxAttrKind {
     Trusted(Trusted),
     TrustedImpl(Trusted),
+    TrustedDerive(Vec<Symbol>),
+    IgnoreDerive(Vec<Symbol>),
     Opaque,
     FnSig(surface::FnSig),
     TraitAssocReft(surface::TraitAssocReft),
@@ -589,8 +691,10 @@ enum FluxAttrKind {
     Invariant(surface::Expr),
     Ignore(Ignored),
     ShouldFail,
+    Explain,
     ExternSpec,
     CheckOverflow(CheckOverflow),
+    LintLevels(LintLevels),
 }
 
 macro_rules! read_flag {
@@ -624,6 +728,9 @@ impl FluxAttr {
             FluxAttrKind::Invariant(..)
                 | FluxAttrKind::TraitAssocReft(..)
                 | FluxAttrKind::ImplAssocReft(..)
+                | FluxAttrKind::LintLevels(..)
+                | FluxAttrKind::TrustedDerive(..)
+                | FluxAttrKind::IgnoreDerive(..)
         )
     }
 }
@@ -652,6 +759,24 @@ impl FluxAttrs {
         read_attr!(self, Ignore)
     }
 
+    fn trusted_derives(&mut self) -> Option<Vec<Symbol>> {
+        read_attrs!(self, TrustedDerive)
+            .into_iter()
+            .reduce(|mut acc, names| {
+                acc.extend(names);
+                acc
+            })
+    }
+
+    fn ignored_derives(&mut self) -> Option<Vec<Symbol>> {
+        read_attrs!(self, IgnoreDerive)
+            .into_iter()
+            .reduce(|mut acc, names| {
+                acc.extend(names);
+                acc
+            })
+    }
+
     fn opaque(&self) -> bool {
         read_flag!(self, Opaque)
     }
@@ -712,9 +837,24 @@ impl FluxAttrs {
         read_flag!(self, ShouldFail)
     }
 
+    fn explain(&self) -> bool {
+        read_flag!(self, Explain)
+    }
+
     fn check_overflow(&mut self) -> Option<CheckOverflow> {
         read_attr!(self, CheckOverflow)
     }
+
+    /// Merges the levels of all `allow`, `warn` and `deny` attributes. Later attributes take
+    /// precedence.
+    fn lint_levels(&mut self) -> Option<LintLevels> {
+        read_attrs!(self, LintLevels)
+            .into_iter()
+            .reduce(|mut acc, levels| {
+                acc.extend(levels);
+                acc
+            })
+    }
 }
 
 impl FluxAttrKind {
@@ -722,6 +862,8 @@ impl FluxAttrKind {
         match self {
             FluxAttrKind::Trusted(_) => attr_name!(Trusted),
             FluxAttrKind::TrustedImpl(_) => attr_name!(TrustedImpl),
+            FluxAttrKind::TrustedDerive(_) => attr_name!(TrustedDerive),
+            FluxAttrKind::IgnoreDerive(_) => attr_name!(IgnoreDerive),
             FluxAttrKind::Opaque => attr_name!(Opaque),
             FluxAttrKind::FnSig(_) => attr_name!(FnSig),
             FluxAttrKind::TraitAssocReft(_) => attr_name!(TraitAssocReft),
@@ -737,8 +879,10 @@ impl FluxAttrKind {
             FluxAttrKind::Ignore(_) => attr_name!(Ignore),
             FluxAttrKind::Invariant(_) => attr_name!(Invariant),
             FluxAttrKind::ShouldFail => attr_name!(ShouldFail),
+            FluxAttrKind::Explain => attr_name!(Explain),
             FluxAttrKind::ExternSpec => attr_name!(ExternSpec),
             FluxAttrKind::CheckOverflow(_) => attr_name!(CheckOverflow),
+            FluxAttrKind::LintLevels(_) => attr_name!(LintLevels),
         }
     }
 }
@@ -834,6 +978,7 @@ impl FluxAttrCFG {
         let mut crate_config = CrateConfig::default();
         try_read_setting!(self, check_overflow, bool, crate_config);
         try_read_setting!(self, scrape_quals, bool, crate_config);
+        try_read_setting!(self, prelude, bool, crate_config);
 
         if let Some((name, setting)) = self.map.iter().next() {
             return Err(errors::CFGError {
@@ -846,15 +991,15 @@ impl FluxAttrCFG {
     }
 }
 
-mod errors {
-    use flux_errors::E0999;
+pub(crate) mod errors {
+    use flux_errors::{E9020, E9021, E9022};
     use flux_macros::Diagnostic;
     use rustc_hir::def_id::DefId;
     use rustc_middle::ty::TyCtxt;
-    use rustc_span::{symbol::Ident, Span};
+    use rustc_span::{symbol::Ident, Span, Symbol};
 
     #[derive(Diagnostic)]
-    #[diag(driver_duplicated_attr, code = E0999)]
+    #[diag(driver_duplicated_attr, code = E9021)]
     pub(super) struct DuplicatedAttr {
         #[primary_span]
         pub span: Span,
@@ -862,14 +1007,22 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_invalid_attr, code = E0999)]
+    #[diag(driver_invalid_attr, code = E9021)]
     pub(super) struct InvalidAttr {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_cfg_error, code = E0999)]
+    #[diag(driver_unknown_lint, code = E9021)]
+    pub(super) struct UnknownLint {
+        #[primary_span]
+        pub span: Span,
+        pub name: Symbol,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_cfg_error, code = E9021)]
     pub(super) struct CFGError {
         #[primary_span]
         pub span: Span,
@@ -877,15 +1030,15 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_syntax_err, code = E0999)]
-    pub(super) struct SyntaxErr {
+    #[diag(driver_syntax_err, code = E9020)]
+    pub(crate) struct SyntaxErr {
         #[primary_span]
         pub span: Span,
         pub msg: &'static str,
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_attr_on_opaque, code = E0999)]
+    #[diag(driver_attr_on_opaque, code = E9022)]
     pub(super) struct AttrOnOpaque {
         #[primary_span]
         span: Span,
@@ -901,7 +1054,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_missing_variant, code = E0999)]
+    #[diag(driver_missing_variant, code = E9022)]
     #[note]
     pub(super) struct MissingVariant {
         #[primary_span]
@@ -929,7 +1082,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(driver_mismatched_spec_name, code = E0999)]
+    #[diag(driver_mismatched_spec_name, code = E9022)]
     pub(super) struct MismatchedSpecName {
         #[primary_span]
         #[label]
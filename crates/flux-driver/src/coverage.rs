@@ -0,0 +1,297 @@
+//! Verification coverage of a crate, i.e., how many of its functions are verified against a refined
+//! signature, checked with default refinements, trusted or ignored.
+//!
+//! When `coverage` is enabled, the report for a crate is written to the log directory both as text
+//! (`<crate>.coverage.txt`) and as json (`<crate>.coverage.json`). `cargo flux --coverage` prints
+//! the text reports of the crates it checked. The json file follows the schema defined by [`CrateCoverage`].
+//! Any change to the schema must bump [`COVERAGE_VERSION`].
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+};
+
+use flux_config as config;
+use flux_middle::global_env::GlobalEnv;
+use rustc_hir::{
+    def::DefKind,
+    def_id::{LocalDefId, LOCAL_CRATE},
+    OwnerId,
+};
+use rustc_middle::ty;
+use serde::Serialize;
+
+/// The version of the coverage schema.
+const COVERAGE_VERSION: u32 = 1;
+
+/// Extension of the files containing the coverage report of a crate. Must be kept in sync with
+/// `cargo-flux`.
+const COVERAGE_EXT: &str = "coverage";
+
+#[derive(Serialize)]
+struct CrateCoverage {
+    version: u32,
+    #[serde(rename = "crate")]
+    krate: String,
+    total: Counts,
+    /// Modules sorted by path. Modules without functions are omitted.
+    modules: Vec<ModuleCoverage>,
+}
+
+#[derive(Serialize)]
+struct ModuleCoverage {
+    path: String,
+    counts: Counts,
+    functions: Vec<FnCoverage>,
+}
+
+#[derive(Serialize)]
+struct FnCoverage {
+    path: String,
+    status: Status,
+    /// Whether the unrefined signature of the function mentions an opaque struct
+    opaque: bool,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    /// Checked against a signature given with `#[flux::sig]`
+    Annotated,
+    /// Checked against the default refinement of its Rust signature
+    Default,
+    /// Marked with `#[flux::trusted]`, so its body is not checked
+    Trusted,
+    /// Marked with `#[flux::trusted_impl]`, so it is not checked against the trait method it
+    /// implements
+    TrustedImpl,
+    /// Marked with `#[flux::ignore]`
+    Ignored,
+}
+
+#[derive(Serialize, Default, Clone, Copy)]
+struct Counts {
+    fns: usize,
+    annotated: usize,
+    default: usize,
+    trusted: usize,
+    trusted_impl: usize,
+    ignored: usize,
+    opaque: usize,
+}
+
+impl Counts {
+    fn add(&mut self, status: Status, opaque: bool) {
+        self.fns += 1;
+        match status {
+            Status::Annotated => self.annotated += 1,
+            Status::Default => self.default += 1,
+            Status::Trusted => self.trusted += 1,
+            Status::TrustedImpl => self.trusted_impl += 1,
+            Status::Ignored => self.ignored += 1,
+        }
+        self.opaque += opaque as usize;
+    }
+}
+
+/// Computes the coverage of the local crate and writes it to the log directory.
+pub(crate) fn dump_coverage(genv: GlobalEnv) -> io::Result<()> {
+    let coverage = crate_coverage(genv);
+    fs::create_dir_all(config::log_dir())?;
+    let create = |ext: &str| {
+        let path = config::log_dir().join(format!("{}.{COVERAGE_EXT}.{ext}", coverage.krate));
+        Ok::<_, io::Error>(io::BufWriter::new(fs::File::create(path)?))
+    };
+
+    let mut writer = create("json")?;
+    serde_json::to_writer_pretty(&mut writer, &coverage)?;
+    writer.flush()?;
+
+    let mut writer = create("txt")?;
+    write_text(&mut writer, &coverage)?;
+    writer.flush()
+}
+
+fn crate_coverage(genv: GlobalEnv) -> CrateCoverage {
+    let tcx = genv.tcx();
+    let krate = tcx.crate_name(LOCAL_CRATE).to_string();
+
+    let mut total = Counts::default();
+    let mut modules = BTreeMap::<String, ModuleCoverage>::new();
+    for def_id in genv.iter_local_def_id() {
+        if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+            || tcx.hir().maybe_body_owned_by(def_id).is_none()
+        {
+            continue;
+        }
+        let status = fn_status(genv, def_id);
+        let opaque = mentions_opaque_struct(genv, def_id);
+
+        // Paths of local items are printed without the crate name, which we add to modules so the
+        // root module has a name.
+        let module = tcx.parent_module_from_def_id(def_id).to_def_id();
+        let module_path = match tcx.def_path_str(module) {
+            path if path.is_empty() => krate.clone(),
+            path => format!("{krate}::{path}"),
+        };
+        let module = modules.entry(module_path.clone()).or_insert_with(|| {
+            ModuleCoverage { path: module_path, counts: Counts::default(), functions: vec![] }
+        });
+        module.counts.add(status, opaque);
+        module
+            .functions
+            .push(FnCoverage { path: tcx.def_path_str(def_id), status, opaque });
+        total.add(status, opaque);
+    }
+
+    let modules = modules
+        .into_values()
+        .map(|mut module| {
+            module.functions.sort_by(|a, b| a.path.cmp(&b.path));
+            module
+        })
+        .collect();
+
+    CrateCoverage { version: COVERAGE_VERSION, krate, total, modules }
+}
+
+fn fn_status(genv: GlobalEnv, def_id: LocalDefId) -> Status {
+    if genv.ignored(def_id) {
+        Status::Ignored
+    } else if genv.trusted(def_id) {
+        Status::Trusted
+    } else if genv.trusted_impl(def_id) {
+        Status::TrustedImpl
+    } else if genv
+        .collect_specs()
+        .fn_sigs
+        .get(&OwnerId { def_id })
+        .is_some_and(|spec| spec.fn_sig.is_some())
+    {
+        Status::Annotated
+    } else {
+        Status::Default
+    }
+}
+
+/// Whether a type in the signature of the function is a struct marked as opaque, either locally or
+/// by the spec of a dependency. Functions manipulating the fields of such structs usually need to be
+/// trusted.
+fn mentions_opaque_struct(genv: GlobalEnv, def_id: LocalDefId) -> bool {
+    let fn_sig = genv.tcx().fn_sig(def_id).instantiate_identity();
+    fn_sig
+        .skip_binder()
+        .inputs_and_output
+        .iter()
+        .flat_map(|ty| ty.walk())
+        .filter_map(|arg| arg.as_type())
+        .any(|ty| {
+            if let ty::Adt(adt_def, _) = ty.kind() {
+                genv.adt_def(adt_def.did())
+                    .is_ok_and(|adt_def| adt_def.is_opaque())
+            } else {
+                false
+            }
+        })
+}
+
+fn write_text(w: &mut impl Write, coverage: &CrateCoverage) -> io::Result<()> {
+    let width = coverage
+        .modules
+        .iter()
+        .map(|module| module.path.len())
+        .chain(["module".len(), "total".len()])
+        .max()
+        .unwrap_or_default();
+
+    writeln!(w, "flux coverage for crate `{}`", coverage.krate)?;
+    writeln!(
+        w,
+        "{:width$}  {:>5}  {:>9}  {:>7}  {:>7}  {:>12}  {:>7}  {:>6}",
+        "module", "fns", "annotated", "default", "trusted", "trusted_impl", "ignored", "opaque"
+    )?;
+    let row = |w: &mut dyn Write, path: &str, counts: &Counts| {
+        writeln!(
+            w,
+            "{path:width$}  {:>5}  {:>9}  {:>7}  {:>7}  {:>12}  {:>7}  {:>6}",
+            counts.fns,
+            counts.annotated,
+            counts.default,
+            counts.trusted,
+            counts.trusted_impl,
+            counts.ignored,
+            counts.opaque
+        )
+    };
+    for module in &coverage.modules {
+        row(w, &module.path, &module.counts)?;
+    }
+    row(w, "total", &coverage.total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(statuses: &[(Status, bool)]) -> Counts {
+        let mut counts = Counts::default();
+        for &(status, opaque) in statuses {
+            counts.add(status, opaque);
+        }
+        counts
+    }
+
+    #[test]
+    fn counts_add_status_and_opaque() {
+        let counts = counts(&[
+            (Status::Annotated, false),
+            (Status::Annotated, true),
+            (Status::Default, false),
+            (Status::Trusted, true),
+            (Status::TrustedImpl, false),
+            (Status::Ignored, false),
+        ]);
+        assert_eq!(counts.fns, 6);
+        assert_eq!(counts.annotated, 2);
+        assert_eq!(counts.default, 1);
+        assert_eq!(counts.trusted, 1);
+        assert_eq!(counts.trusted_impl, 1);
+        assert_eq!(counts.ignored, 1);
+        assert_eq!(counts.opaque, 2);
+    }
+
+    #[test]
+    fn text_report_aligns_columns_to_longest_module() {
+        let root = counts(&[(Status::Annotated, false), (Status::Default, true)]);
+        let nested = counts(&[(Status::Trusted, false)]);
+        let coverage = CrateCoverage {
+            version: COVERAGE_VERSION,
+            krate: "krate".to_string(),
+            total: counts(&[
+                (Status::Annotated, false),
+                (Status::Default, true),
+                (Status::Trusted, false),
+            ]),
+            modules: vec![
+                ModuleCoverage { path: "krate".to_string(), counts: root, functions: vec![] },
+                ModuleCoverage {
+                    path: "krate::nested".to_string(),
+                    counts: nested,
+                    functions: vec![],
+                },
+            ],
+        };
+        let mut out = vec![];
+        write_text(&mut out, &coverage).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+flux coverage for crate `krate`
+module           fns  annotated  default  trusted  trusted_impl  ignored  opaque
+krate              2          1        1        0             0        0       1
+krate::nested      1          0        0        1             0        0       0
+total              3          1        1        1             0        0       1
+"
+        );
+    }
+}
@@ -2,16 +2,28 @@
 
 extern crate rustc_ast;
 extern crate rustc_borrowck;
+extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_errors;
 extern crate rustc_hir;
 extern crate rustc_interface;
 extern crate rustc_middle;
+extern crate rustc_mir_transform;
+extern crate rustc_parse;
 extern crate rustc_session;
 extern crate rustc_span;
 
+pub mod api;
 pub mod callbacks;
 mod collector;
+mod coverage;
+mod references;
+mod spec_files;
+
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 
 use flux_macros::fluent_messages;
 
@@ -26,3 +38,70 @@ pub static DEFAULT_LOCALE_RESOURCES: &[&str] = &[
     flux_refineck::DEFAULT_LOCALE_RESOURCE,
     flux_rustc_bridge::DEFAULT_LOCALE_RESOURCE,
 ];
+
+/// Name of the crate with the specs for `core` shipped in the flux sysroot
+pub const FLUX_CORE: &str = "flux_core";
+
+/// Name of the crate with the specs for the standard collections shipped in the flux sysroot
+const FLUX_PRELUDE: &str = "flux_prelude";
+
+/// Adds to the arguments for rustc the ones flux needs to check a crate: registering the `flux`
+/// tool and the `flux` cfg, and loading the specs for `core` and the prelude precompiled in
+/// `flux_lib_dir`, if any.
+pub fn add_flux_args(args: &mut Vec<String>, flux_lib_dir: Option<&Path>) {
+    args.push("-Coverflow-checks=off".to_string());
+    args.push("-Zcrate-attr=feature(register_tool, custom_inner_attributes)".to_string());
+    args.push("-Zcrate-attr=register_tool(flux)".to_string());
+    args.push("-Zcrate-attr=register_tool(flux_tool)".to_string());
+    args.push("--cfg=flux".to_string());
+
+    // Load the specs for `core` shipped in the sysroot. They live in a regular crate so they are
+    // picked up through the metadata mechanism like the specs of any other dependency.
+    if let Some(flux_core) = flux_lib_path(flux_lib_dir, FLUX_CORE)
+        && arg_value(args, "--crate-name", |name| name == FLUX_CORE).is_none()
+    {
+        // `flux_core` is installed along with the `flux_rs` it was compiled against.
+        if let Some(dir) = flux_core.parent() {
+            args.push(format!("-Ldependency={}", dir.display()));
+        }
+        args.push("-Zunstable-options".to_string());
+        args.push(format!("--extern=force:{FLUX_CORE}={}", flux_core.display()));
+
+        // The prelude is always passed but its specs are only loaded if the crate (or one of its
+        // dependencies) enables it.
+        if let Some(flux_prelude) = flux_lib_path(flux_lib_dir, FLUX_PRELUDE)
+            && arg_value(args, "--crate-name", |name| name == FLUX_PRELUDE).is_none()
+        {
+            args.push(format!("--extern=force:{FLUX_PRELUDE}={}", flux_prelude.display()));
+        }
+    }
+}
+
+/// Path to a precompiled library in `flux_lib_dir`, i.e., `flux_core` or `flux_prelude`. Returns
+/// `None` if the library hasn't been built, in which case we just check without it.
+fn flux_lib_path(flux_lib_dir: Option<&Path>, name: &str) -> Option<PathBuf> {
+    let path = flux_lib_dir?.join(format!("lib{name}.rlib"));
+    path.exists().then_some(path)
+}
+
+/// If a command-line option matches `find_arg`, then apply the predicate `pred` on its value. If
+/// true, then return it. The parameter is assumed to be either `--arg=value` or `--arg value`.
+pub fn arg_value<'a, T: Deref<Target = str>>(
+    args: &'a [T],
+    find_arg: &str,
+    pred: impl Fn(&str) -> bool,
+) -> Option<&'a str> {
+    let mut args = args.iter().map(Deref::deref);
+    while let Some(arg) = args.next() {
+        let mut arg = arg.splitn(2, '=');
+        if arg.next() != Some(find_arg) {
+            continue;
+        }
+
+        match arg.next().or_else(|| args.next()) {
+            Some(v) if pred(v) => return Some(v),
+            _ => {}
+        }
+    }
+    None
+}
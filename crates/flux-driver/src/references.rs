@@ -0,0 +1,137 @@
+//! The references to spec functions and qualifiers in the signatures of the local crate.
+//!
+//! When the checker trace is enabled, we write the references as resolved by flux to
+//! `<crate>.refs.json` in the log directory (see [`RefsTrace`]), so editors can go to the
+//! definition of the item a name refers to instead of searching for a declaration with the same
+//! name.
+use std::{
+    fs,
+    io::{self, Write},
+};
+
+use flux_common::trace::{RefTrace, RefsTrace, SpanTrace, REFS_EXT, TRACE_VERSION};
+use flux_config as config;
+use flux_middle::{
+    fhir::{self, visit::Visitor},
+    global_env::GlobalEnv,
+};
+use flux_syntax::surface;
+use rustc_data_structures::unord::UnordMap;
+use rustc_hir::{def::DefKind, def_id::LOCAL_CRATE};
+use rustc_span::{Span, Symbol};
+
+/// Writes the references of the local crate to the log directory.
+pub(crate) fn dump_references(genv: GlobalEnv) -> io::Result<()> {
+    let refs = RefsTrace { version: TRACE_VERSION, refs: collect_references(genv) };
+    fs::create_dir_all(config::log_dir())?;
+    let krate = genv.tcx().crate_name(LOCAL_CRATE);
+    let path = config::log_dir().join(format!("{krate}.{REFS_EXT}"));
+    let mut writer = io::BufWriter::new(fs::File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, &refs)?;
+    writer.flush()
+}
+
+fn collect_references(genv: GlobalEnv) -> Vec<RefTrace> {
+    let mut collector = ReferenceCollector {
+        genv,
+        funcs: Default::default(),
+        qualifiers: Default::default(),
+        refs: vec![],
+    };
+    for item in genv.collect_specs().flux_items_by_parent.values().flatten() {
+        match item {
+            surface::Item::FuncDef(defn) => {
+                collector.funcs.insert(defn.name.name, defn.name.span);
+            }
+            surface::Item::Qualifier(qualifier) => {
+                collector
+                    .qualifiers
+                    .insert(qualifier.name.name, qualifier.name.span);
+            }
+            surface::Item::SortDecl(_) => {}
+        }
+    }
+
+    for def_id in genv.tcx().hir_crate_items(()).definitions() {
+        if genv.ignored(def_id) || genv.is_dummy(def_id) {
+            continue;
+        }
+        if !matches!(
+            genv.def_kind(def_id),
+            DefKind::TyAlias { .. }
+                | DefKind::Struct
+                | DefKind::Enum
+                | DefKind::Fn
+                | DefKind::AssocFn
+                | DefKind::Trait
+                | DefKind::Impl { .. }
+        ) {
+            continue;
+        }
+        if let Ok(node) = genv.map().expect_owner_node(def_id) {
+            collector.visit_node(&node);
+        }
+    }
+    for defn in genv.map().spec_funcs() {
+        if let Some(body) = &defn.body {
+            collector.visit_expr(body);
+        }
+    }
+    for qualifier in genv.map().qualifiers() {
+        collector.visit_expr(&qualifier.expr);
+    }
+    collector.refs
+}
+
+struct ReferenceCollector<'genv, 'tcx> {
+    genv: GlobalEnv<'genv, 'tcx>,
+    /// The span of the name of each local spec function
+    funcs: UnordMap<Symbol, Span>,
+    /// The span of the name of each local qualifier
+    qualifiers: UnordMap<Symbol, Span>,
+    refs: Vec<RefTrace>,
+}
+
+impl ReferenceCollector<'_, '_> {
+    fn add(&mut self, span: Span, def: Span) {
+        let tcx = self.genv.tcx();
+        if let (Some(span), Some(def)) = (SpanTrace::new(tcx, span), SpanTrace::new(tcx, def)) {
+            self.refs.push(RefTrace { span, def });
+        }
+    }
+}
+
+impl<'v> Visitor<'v> for ReferenceCollector<'_, '_> {
+    fn visit_fn_sig(&mut self, sig: &fhir::FnSig<'v>) {
+        for qualifier in sig.qualifiers {
+            if qualifier.def_id.is_local()
+                && let Some(def) = self.qualifiers.get(&qualifier.def_id.name)
+            {
+                self.add(qualifier.span, *def);
+            }
+        }
+        fhir::visit::walk_fn_sig(self, sig);
+    }
+
+    fn visit_expr(&mut self, expr: &fhir::Expr<'v>) {
+        // The function in an application is not visited by `walk_expr`
+        if let fhir::ExprKind::App(func, _) = &expr.kind {
+            self.visit_path_expr(func);
+        }
+        fhir::visit::walk_expr(self, expr);
+    }
+
+    fn visit_path_expr(&mut self, path: &fhir::PathExpr<'v>) {
+        // Local variables shadow spec functions, so the name only refers to the function if it was
+        // resolved to it.
+        if let fhir::ExprRes::GlobalFunc(
+            fhir::SpecFuncKind::Uif(def_id) | fhir::SpecFuncKind::Def(def_id),
+            _,
+        ) = path.res
+            && def_id.is_local()
+            && let Some(def) = self.funcs.get(&def_id.name)
+        {
+            self.add(path.span, *def);
+        }
+    }
+}
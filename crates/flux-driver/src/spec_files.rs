@@ -0,0 +1,505 @@
+//! Standalone spec files listed in the `extern_specs` setting. A spec file contains specs for items
+//! defined in other crates written with the syntax of flux signatures, e.g.,
+//!
+//! ```ignore
+//! use bytes::Bytes;
+//!
+//! opaque struct Bytes[len: int];
+//!
+//! impl Bytes {
+//!     fn len(&Bytes[@n]) -> usize[n];
+//! }
+//! ```
+//!
+//! A spec file is parsed with `flux-syntax` and lowered to the items the `extern_spec` macro would
+//! generate for the same specs, which are added to a hidden module of the crate root. Rust resolves
+//! the paths in that module binding each spec to the item it refers to, and the specs are then
+//! collected like the ones written in the crate. The flux attributes of the generated items hold the
+//! tokens of the file, so errors in the specs are reported in the file.
+use std::{fmt::Write as _, fs, iter};
+
+use flux_errors::FluxSession;
+use flux_syntax::{
+    surface::{self, FnInput, FnRetTy, FnSig, GenericArgKind, Mutability, TyKind},
+    ParseSess,
+};
+use itertools::Itertools;
+use rustc_ast::{
+    self as ast,
+    mut_visit::MutVisitor,
+    token::{Token, TokenKind},
+    tokenstream::{DelimSpan, TokenStream, TokenTree},
+};
+use rustc_parse::{new_parser_from_source_str, parser::ForceCollect, source_str_to_stream};
+use rustc_session::Session;
+use rustc_span::{FileName, Span};
+
+use crate::collector::{errors::SyntaxErr, EXTERN_SPEC_FILE_MOD};
+
+type Result<T = String> = std::result::Result<T, errors::UnsupportedInSpecFile>;
+
+/// Prefix of the placeholders for the arguments of the flux attributes of the generated items
+const ATTR_PLACEHOLDER: &str = "__flux_spec_file_tokens_";
+
+/// Lifetime of the first input of a method when it is a reference to the self type
+const SELF_LIFETIME: &str = "'__self";
+
+/// Adds a module to the crate root for each file in [`flux_config::extern_specs`]
+pub(crate) fn include_extern_spec_files(
+    sess: &Session,
+    flux_sess: &FluxSession,
+    krate: &mut ast::Crate,
+) {
+    for (i, path) in flux_config::extern_specs().iter().enumerate() {
+        let source = fs::read_to_string(path).unwrap_or_else(|err| {
+            flux_sess.emit_fatal(errors::ExternSpecFile { path, err });
+        });
+        let name = FileName::from(path.clone());
+        let tokens = rustc_parse::unwrap_or_emit_fatal(source_str_to_stream(
+            &sess.psess,
+            name.clone(),
+            source,
+            None,
+        ));
+        let file = sess.source_map().get_source_file(&name).unwrap();
+        let span = Span::with_root_ctxt(file.start_pos, file.end_position());
+        let items = match ParseSess::default().parse_spec_file(&tokens, span) {
+            Ok(items) => items,
+            Err(err) => {
+                flux_sess.emit_err(SyntaxErr::from(err));
+                continue;
+            }
+        };
+
+        let mut lowering =
+            Lowering { tokens: &tokens, attr_args: vec![], self_ty: None, elided_lifetime: None };
+        let mut module = format!("#[doc(hidden)] mod {EXTERN_SPEC_FILE_MOD}_{i} {{\n");
+        for item in &items {
+            match lowering.lower_item(item) {
+                Ok(item) => module.push_str(&item),
+                Err(err) => {
+                    flux_sess.emit_err(err);
+                }
+            }
+        }
+        module.push('}');
+
+        let name = FileName::Custom(format!("extern specs generated from {}", path.display()));
+        let mut parser = rustc_parse::unwrap_or_emit_fatal(new_parser_from_source_str(
+            &sess.psess,
+            name,
+            module,
+        ));
+        match parser.parse_item(ForceCollect::No) {
+            Ok(Some(item)) => {
+                let mut fill = FillAttrArgs { attr_args: lowering.attr_args };
+                krate.items.extend(fill.flat_map_item(item));
+            }
+            Ok(None) => flux_common::bug!("failed to include extern spec file"),
+            Err(err) => {
+                err.emit();
+            }
+        }
+    }
+    flux_sess.abort_if_errors();
+}
+
+/// Lowers the items of a spec file to Rust source code
+struct Lowering<'a> {
+    /// The tokens of the file
+    tokens: &'a TokenStream,
+    /// The arguments of the flux attributes of the generated items and the span they come from. The
+    /// generated code uses placeholders for them, which are replaced by [`FillAttrArgs`].
+    attr_args: Vec<(TokenStream, Span)>,
+    /// The self type of the impl being lowered, if any
+    self_ty: Option<String>,
+    /// The lifetime of the references without one in the types being lowered, if any
+    elided_lifetime: Option<&'static str>,
+}
+
+impl Lowering<'_> {
+    fn lower_item(&mut self, item: &surface::SpecFileItem) -> Result {
+        match item {
+            surface::SpecFileItem::Use(path) => Ok(format!("use {};\n", self.path(path)?)),
+            surface::SpecFileItem::Fn(sig) => {
+                let ident = self.fn_ident(sig)?;
+                // The dummy function has the same name as the function so the name in the signature
+                // matches, and it calls the function through the module to skip itself.
+                let func = self.lower_fn(sig, &format!("self::{ident}"))?;
+                Ok(extern_spec_block(&func))
+            }
+            surface::SpecFileItem::Struct(struct_) => {
+                let ident = struct_.ident;
+                let generics = self.generics(&struct_.generics);
+                let mut items = format!(
+                    "struct __FluxExternSpecDummy__{ident}{generics}({ident}{generics});\n\
+                     #[flux_tool::opaque]\n"
+                );
+                if let Some((_, span)) = &struct_.refined_by {
+                    let placeholder = self.attr_placeholder(*span);
+                    writeln!(items, "#[flux_tool::refined_by({placeholder})]").unwrap();
+                }
+                writeln!(
+                    items,
+                    "struct __FluxExternSpecStruct__{ident}{generics}({ident}{generics});"
+                )
+                .unwrap();
+                Ok(extern_spec_block(&items))
+            }
+            surface::SpecFileItem::Impl(impl_) => {
+                let self_ty = self.path(&impl_.self_ty)?;
+                let generics = self.generics(&impl_.generics);
+                let dummy = format!("__FluxExternImplStruct{}", impl_.self_ty.last().ident);
+                let mut fields = String::new();
+                for param in &impl_.generics.params {
+                    if let surface::GenericParamKind::Lifetime = param.kind {
+                        write!(fields, "&{} (), ", param.name).unwrap();
+                    } else {
+                        write!(fields, "{}, ", param.name).unwrap();
+                    }
+                }
+                let mut items = format!(
+                    "struct {dummy}{generics}({fields}{self_ty});\nimpl{generics} {dummy}{generics} {{\n"
+                );
+                self.self_ty = Some(self_ty.clone());
+                let methods = impl_
+                    .items
+                    .iter()
+                    .map(|sig| {
+                        let ident = self.fn_ident(sig)?;
+                        self.lower_fn(sig, &format!("<{self_ty}>::{ident}"))
+                    })
+                    .try_collect();
+                self.self_ty = None;
+                let methods: Vec<_> = methods?;
+                items.extend(methods);
+                items.push_str("}\n");
+                Ok(extern_spec_block(&items))
+            }
+        }
+    }
+
+    /// A function with the unrefined signature of `sig` calling `callee` and the signature as a
+    /// flux attribute
+    fn lower_fn(&mut self, sig: &FnSig, callee: &str) -> Result {
+        if let surface::Async::Yes { span, .. } = sig.asyncness {
+            return Err(errors::UnsupportedInSpecFile::new(span, "async functions"));
+        }
+        let ident = self.fn_ident(sig)?;
+        let generics = self.generics(&sig.generics);
+        let self_lifetime = match sig.inputs.first() {
+            Some(input) => self.is_elided_self_ref(input)?,
+            None => false,
+        };
+        let params: Vec<_> = sig
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                let ty = match input {
+                    FnInput::Constr(_, path, _, _) => self.path(path)?,
+                    FnInput::StrgRef(_, ty, _) if i == 0 && self_lifetime => {
+                        format!("&{SELF_LIFETIME} mut {}", self.ty(ty)?)
+                    }
+                    FnInput::StrgRef(_, ty, _) => format!("&mut {}", self.ty(ty)?),
+                    FnInput::Ty(_, ty, _) if i == 0 && self_lifetime => {
+                        self.with_elided_lifetime(|this| this.ty(ty))?
+                    }
+                    FnInput::Ty(_, ty, _) => self.ty(ty)?,
+                };
+                Ok(format!("__arg{i}: {ty}"))
+            })
+            .try_collect()?;
+        let generics = if self_lifetime {
+            let params = sig
+                .generics
+                .params
+                .iter()
+                .map(|param| param.name.to_string());
+            format!(
+                "<{}>",
+                iter::once(SELF_LIFETIME.to_string())
+                    .chain(params)
+                    .join(", ")
+            )
+        } else {
+            generics
+        };
+        let ret = match &sig.output.returns {
+            FnRetTy::Default(_) => String::new(),
+            FnRetTy::Ty(ty) if self_lifetime => {
+                format!(" -> {}", self.with_elided_lifetime(|this| this.ty(ty))?)
+            }
+            FnRetTy::Ty(ty) => format!(" -> {}", self.ty(ty)?),
+        };
+        let predicates: Vec<_> = sig
+            .generics
+            .predicates
+            .iter()
+            .map(|pred| {
+                Ok(format!("{}: {}", self.ty(&pred.bounded_ty)?, self.bounds(&pred.bounds)?))
+            })
+            .try_collect()?;
+        let where_clause = if predicates.is_empty() {
+            String::new()
+        } else {
+            format!(" where {}", predicates.join(", "))
+        };
+        // Lifetime arguments are left to inference because they cannot be passed explicitly if the
+        // function has late-bound lifetimes
+        let type_params = sig
+            .generics
+            .params
+            .iter()
+            .filter(|param| !matches!(param.kind, surface::GenericParamKind::Lifetime))
+            .map(|param| param.name)
+            .collect_vec();
+        let turbofish = if type_params.is_empty() {
+            String::new()
+        } else {
+            format!("::<{}>", type_params.iter().join(", "))
+        };
+        let args = (0..sig.inputs.len())
+            .map(|i| format!("__arg{i}"))
+            .join(", ");
+        let placeholder = self.attr_placeholder(sig.span);
+        Ok(format!(
+            "#[flux_tool::sig({placeholder})]\n\
+             fn {ident}{generics}({params}){ret}{where_clause} {{ {callee}{turbofish}({args}) }}\n",
+            params = params.join(", "),
+        ))
+    }
+
+    /// Whether `input` is a reference to the self type without a lifetime, e.g., `&Self` or
+    /// `self: &strg Vec<T>[@n]` in `impl<T> Vec<T>`. A method taking `&self` lowers to a function
+    /// taking such a reference, and we name its lifetime [`SELF_LIFETIME`] to keep the elision
+    /// rules for methods, which assign the lifetime of `&self` to the references in the output.
+    fn is_elided_self_ref(&self, input: &FnInput) -> Result<bool> {
+        let Some(self_ty) = &self.self_ty else { return Ok(false) };
+        match input {
+            FnInput::Constr(..) => Ok(false),
+            FnInput::StrgRef(_, ty, _) => Ok(self.ty(ty)? == *self_ty),
+            FnInput::Ty(_, ty, _) => {
+                if let TyKind::Ref(None, _, ty) = &strip_refinements(ty).kind {
+                    Ok(self.ty(ty)? == *self_ty)
+                } else {
+                    Ok(false)
+                }
+            }
+        }
+    }
+
+    fn with_elided_lifetime<R>(&mut self, f: impl FnOnce(&Self) -> R) -> R {
+        self.elided_lifetime = Some(SELF_LIFETIME);
+        let r = f(self);
+        self.elided_lifetime = None;
+        r
+    }
+
+    fn fn_ident(&self, sig: &FnSig) -> Result<surface::Ident> {
+        sig.ident
+            .ok_or_else(|| errors::UnsupportedInSpecFile::new(sig.span, "functions without a name"))
+    }
+
+    fn attr_placeholder(&mut self, span: Span) -> String {
+        let placeholder = format!("{ATTR_PLACEHOLDER}{}", self.attr_args.len());
+        self.attr_args
+            .push((tokens_within(self.tokens, span), span));
+        placeholder
+    }
+
+    /// The generic parameters of an item, which are also the arguments to refer to it, e.g., `<T, U>`
+    fn generics(&self, generics: &surface::Generics) -> String {
+        if generics.params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", generics.params.iter().map(|param| param.name).join(", "))
+        }
+    }
+
+    /// The unrefined version of a type, e.g., `&mut i32` for `&strg i32{v: v > 0}`
+    fn ty(&self, ty: &surface::Ty) -> Result {
+        match &ty.kind {
+            TyKind::Base(bty) | TyKind::Indexed { bty, .. } | TyKind::Exists { bty, .. } => {
+                self.base_ty(bty)
+            }
+            TyKind::GeneralExists { ty, .. } | TyKind::Constr(_, ty) => self.ty(ty),
+            TyKind::Ref(lft, mutbl, ty) => {
+                let lft = match lft {
+                    Some(lft) => format!("{lft} "),
+                    None => {
+                        self.elided_lifetime
+                            .map(|lft| format!("{lft} "))
+                            .unwrap_or_default()
+                    }
+                };
+                let mutbl = if let Mutability::Mut = mutbl { "mut " } else { "" };
+                Ok(format!("&{lft}{mutbl}{}", self.ty(ty)?))
+            }
+            TyKind::Tuple(tys) => {
+                let tys: Vec<_> = tys.iter().map(|ty| self.ty(ty)).try_collect()?;
+                if let [ty] = &tys[..] {
+                    Ok(format!("({ty},)"))
+                } else {
+                    Ok(format!("({})", tys.join(", ")))
+                }
+            }
+            TyKind::Array(ty, len) => {
+                let len = match &len.kind {
+                    surface::ConstArgKind::Lit(len) => len.to_string(),
+                    surface::ConstArgKind::Expr(surface::Expr {
+                        kind: surface::ExprKind::Path(path),
+                        ..
+                    }) if path.segments.len() == 1 => path.segments[0].ident.to_string(),
+                    _ => {
+                        return Err(errors::UnsupportedInSpecFile::new(
+                            len.span,
+                            "array lengths other than literals and constants",
+                        ));
+                    }
+                };
+                Ok(format!("[{}; {len}]", self.ty(ty)?))
+            }
+            TyKind::ImplTrait(_, bounds) => Ok(format!("impl {}", self.bounds(bounds)?)),
+            TyKind::Hole => Err(errors::UnsupportedInSpecFile::new(ty.span, "type holes")),
+        }
+    }
+
+    fn base_ty(&self, bty: &surface::BaseTy) -> Result {
+        match &bty.kind {
+            surface::BaseTyKind::Path(None, path) => self.path(path),
+            surface::BaseTyKind::Path(Some(_), _) => {
+                Err(errors::UnsupportedInSpecFile::new(bty.span, "qualified paths"))
+            }
+            surface::BaseTyKind::Slice(ty) => Ok(format!("[{}]", self.ty(ty)?)),
+            surface::BaseTyKind::TraitObject(bounds) => Ok(format!("dyn {}", self.bounds(bounds)?)),
+        }
+    }
+
+    fn bounds(&self, bounds: &surface::GenericBounds) -> Result {
+        let bounds: Vec<_> = bounds
+            .iter()
+            .map(|bound| self.path(&bound.path))
+            .try_collect()?;
+        Ok(bounds.join(" + "))
+    }
+
+    fn path(&self, path: &surface::Path) -> Result {
+        if let Some(self_ty) = &self.self_ty
+            && let [segment] = &path.segments[..]
+            && segment.ident.name == rustc_span::symbol::kw::SelfUpper
+        {
+            return Ok(self_ty.clone());
+        }
+        let segments: Vec<_> = path
+            .segments
+            .iter()
+            .map(|segment| {
+                if segment.args.is_empty() {
+                    return Ok(segment.ident.to_string());
+                }
+                let args: Vec<_> = segment
+                    .args
+                    .iter()
+                    .map(|arg| {
+                        match &arg.kind {
+                            GenericArgKind::Type(ty) => self.ty(ty),
+                            GenericArgKind::Constraint(ident, ty) => {
+                                Ok(format!("{ident} = {}", self.ty(ty)?))
+                            }
+                            GenericArgKind::Lifetime(lft) => Ok(lft.to_string()),
+                        }
+                    })
+                    .try_collect()?;
+                Ok(format!("{}<{}>", segment.ident, args.join(", ")))
+            })
+            .try_collect()?;
+        Ok(segments.join("::"))
+    }
+}
+
+/// The type without the refinements wrapping it, e.g., `&T` for `{&T | p}`
+fn strip_refinements(ty: &surface::Ty) -> &surface::Ty {
+    match &ty.kind {
+        TyKind::GeneralExists { ty, .. } | TyKind::Constr(_, ty) => strip_refinements(ty),
+        _ => ty,
+    }
+}
+
+fn extern_spec_block(items: &str) -> String {
+    format!(
+        "#[allow(unused, dead_code, non_camel_case_types)]\n\
+         #[flux_tool::extern_spec]\n\
+         const _: () = {{\n{items}}};\n"
+    )
+}
+
+/// The tokens of `stream` within `span`, looking into the delimited group containing it if any
+fn tokens_within(stream: &TokenStream, span: Span) -> TokenStream {
+    let mut trees = vec![];
+    for tree in stream.trees() {
+        match tree {
+            TokenTree::Delimited(dspan, _, _, inner) if !span.contains(tree.span()) => {
+                if dspan.entire().contains(span) {
+                    return tokens_within(inner, span);
+                }
+            }
+            _ if span.contains(tree.span()) => trees.push(tree.clone()),
+            _ => {}
+        }
+    }
+    TokenStream::new(trees)
+}
+
+/// Replaces the placeholders in the arguments of the flux attributes of the generated items with the
+/// tokens of the spec file
+struct FillAttrArgs {
+    attr_args: Vec<(TokenStream, Span)>,
+}
+
+impl MutVisitor for FillAttrArgs {
+    fn visit_attribute(&mut self, attr: &mut ast::Attribute) {
+        if let ast::AttrKind::Normal(normal) = &mut attr.kind
+            && let ast::AttrArgs::Delimited(args) = &mut normal.item.args
+            && let Some(TokenTree::Token(Token { kind: TokenKind::Ident(name, _), .. }, _)) =
+                args.tokens.trees().next()
+            && let Some(i) = name
+                .as_str()
+                .strip_prefix(ATTR_PLACEHOLDER)
+                .and_then(|i| i.parse::<usize>().ok())
+        {
+            let (tokens, span) = &self.attr_args[i];
+            args.tokens = tokens.clone();
+            args.dspan = DelimSpan::from_single(*span);
+        }
+    }
+}
+
+pub(crate) mod errors {
+    use std::{io, path::Path};
+
+    use flux_errors::E9023;
+    use flux_macros::Diagnostic;
+    use rustc_span::Span;
+
+    #[derive(Diagnostic)]
+    #[diag(driver_extern_spec_file, code = E9023)]
+    pub(super) struct ExternSpecFile<'a> {
+        pub(super) path: &'a Path,
+        pub(super) err: io::Error,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_unsupported_in_spec_file, code = E9023)]
+    pub(super) struct UnsupportedInSpecFile {
+        #[primary_span]
+        span: Span,
+        what: &'static str,
+    }
+
+    impl UnsupportedInSpecFile {
+        pub(super) fn new(span: Span, what: &'static str) -> Self {
+            Self { span, what }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Flux error codes.
+//!
+//! rustc prints error codes as an `E` followed by four digits, so Flux uses codes in the range
+//! `E9000`-`E9999` which are not used by rustc. Every code has a long-form explanation in
+//! `src/error_codes/<CODE>.md` which can be printed with `rustc-flux --explain <CODE>`. Codes are
+//! never reused, if an error is removed its code should be removed from the list but not assigned to
+//! a different error.
+use rustc_errors::ErrCode;
+
+macro_rules! error_codes {
+    ($($code:ident: $num:literal, $descr:literal;)*) => {
+        $(
+            #[doc = $descr]
+            pub const $code: ErrCode = ErrCode::from_u32($num);
+        )*
+
+        /// Long-form explanations for all Flux error codes
+        pub static DIAGNOSTICS: &[(ErrCode, &str)] = &[
+            $(($code, include_str!(concat!("error_codes/", stringify!($code), ".md"))),)*
+        ];
+    };
+}
+
+error_codes! {
+    // Refinement checking
+    E9001: 9001, "A precondition might not hold";
+    E9002: 9002, "A postcondition might not hold";
+    E9003: 9003, "An assignment might be unsafe";
+    E9004: 9004, "Possible division or remainder by zero";
+    E9005: 9005, "An assertion might fail";
+    E9006: 9006, "The refinements of a type might not hold when folding";
+    E9007: 9007, "The refinements at a join point might not hold";
+    E9008: 9008, "An arithmetic operation might overflow";
+    E9009: 9009, "Unknown refinement type error";
+    E9010: 9010, "An invariant cannot be proven";
+    E9011: 9011, "An item marked with `should_fail` was verified";
+    E9012: 9012, "Refinement parameters at a call cannot be inferred";
+    E9013: 9013, "Access to fields of an opaque struct";
+
+    // Attributes
+    E9020: 9020, "Syntax error in a Flux annotation";
+    E9021: 9021, "Invalid Flux attribute";
+    E9022: 9022, "Annotation doesn't match the annotated item";
+    E9023: 9023, "Invalid extern spec";
+
+    // Name resolution and desugaring
+    E9030: 9030, "Unresolved name";
+    E9031: 9031, "Invalid expression in refinement";
+    E9032: 9032, "Duplicated refinement parameter";
+    E9033: 9033, "Unsupported Rust feature";
+
+    // Well-formedness
+    E9040: 9040, "Mismatched sorts";
+    E9041: 9041, "Wrong number of refinement arguments";
+    E9042: 9042, "Sort annotation needed";
+    E9043: 9043, "Refinement parameter cannot be determined";
+    E9044: 9044, "Unknown qualifier";
+    E9045: 9045, "Missing or duplicated ensures clause";
+
+    // Conversion of annotations to types
+    E9050: 9050, "Wrong number of generic arguments";
+    E9051: 9051, "Invalid refinement of a type";
+    E9052: 9052, "Unresolved associated type";
+    E9053: 9053, "Refinement annotation incompatible with Rust definition";
+    E9054: 9054, "Cycle in definitions";
+    E9055: 9055, "Invalid associated refinement";
+
+    // Metadata
+    E9060: 9060, "Duplicated spec";
+    E9061: 9061, "Invalid Flux metadata file";
+    E9062: 9062, "The Flux prelude is not installed";
+
+    // Use of items without a signature
+    E9070: 9070, "Use of ignored item";
+    E9071: 9071, "Invalid instantiation of base generic";
+
+    // Output files
+    E9080: 9080, "Cannot write a Flux output file";
+}
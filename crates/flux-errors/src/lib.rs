@@ -1,21 +1,22 @@
 #![feature(rustc_private, never_type)]
 
-extern crate rustc_data_structures;
 extern crate rustc_errors;
 extern crate rustc_session;
 extern crate rustc_span;
 
+mod codes;
+
 use std::{cell::Cell, io, sync::Arc};
 
+pub use codes::*;
 use flux_common::result::{ErrorCollector, ErrorEmitter};
-use rustc_data_structures::sync;
 pub use rustc_errors::ErrorGuaranteed;
 use rustc_errors::{
     annotate_snippet_emitter_writer::AnnotateSnippetEmitter,
-    emitter::{stderr_destination, Emitter, HumanEmitter, HumanReadableErrorType},
+    emitter::{stderr_destination, DynEmitter, HumanEmitter, HumanReadableErrorType},
     json::JsonEmitter,
     registry::Registry,
-    Diagnostic, ErrCode, FatalAbort, FatalError, LazyFallbackBundle,
+    Diagnostic, FatalAbort, FatalError, LazyFallbackBundle,
 };
 use rustc_session::{
     config::{self, ErrorOutputType},
@@ -27,8 +28,20 @@ pub struct FluxSession {
     pub parse_sess: ParseSess,
 }
 
-// FIXME(nilehmann) We probably need to move out of this error reporting
-pub const E0999: ErrCode = ErrCode::from_u32(999);
+/// Returns a registry with the long-form explanations of all Flux error codes
+pub fn registry() -> Registry {
+    Registry::new(DIAGNOSTICS)
+}
+
+/// Returns the explanation for a Flux error `code`. Both the `E9001` and `9001` forms are accepted.
+pub fn explain(code: &str) -> Option<&'static str> {
+    let code = code.to_ascii_uppercase();
+    let code: u32 = code.strip_prefix('E').unwrap_or(&code).parse().ok()?;
+    DIAGNOSTICS
+        .iter()
+        .find(|(c, _)| c.as_u32() == code)
+        .map(|(_, descr)| *descr)
+}
 
 impl FluxSession {
     pub fn new(
@@ -37,6 +50,12 @@ impl FluxSession {
         fallback_bundle: LazyFallbackBundle,
     ) -> Self {
         let emitter = emitter(opts, source_map.clone(), fallback_bundle);
+        Self::with_emitter(source_map, emitter)
+    }
+
+    /// Creates a session sending diagnostics to `emitter` instead of printing them according to
+    /// the options of rustc.
+    pub fn with_emitter(source_map: Arc<SourceMap>, emitter: Box<DynEmitter>) -> Self {
         let dcx = rustc_errors::DiagCtxt::new(emitter);
         Self { parse_sess: ParseSess::with_dcx(dcx, source_map) }
     }
@@ -65,6 +84,8 @@ impl FluxSession {
     }
 
     pub fn finish_diagnostics(&self) {
+        // We don't pass our registry here because rustc would suggest `rustc --explain` for codes
+        // in it, which doesn't know about Flux codes.
         self.parse_sess.dcx().print_error_count(&Registry::new(&[]));
         self.abort_if_errors();
     }
@@ -78,7 +99,7 @@ fn emitter(
     opts: &config::Options,
     source_map: Arc<SourceMap>,
     fallback_bundle: LazyFallbackBundle,
-) -> Box<dyn Emitter + sync::DynSend> {
+) -> Box<DynEmitter> {
     let bundle = None;
     let track_diagnostics = opts.unstable_opts.track_diagnostics;
 
@@ -114,7 +135,7 @@ fn emitter(
                     json_rendered,
                     color_config,
                 )
-                .registry(Some(Registry::new(&[])))
+                .registry(Some(registry()))
                 .fluent_bundle(bundle)
                 .track_diagnostics(track_diagnostics)
                 .diagnostic_width(opts.diagnostic_width)
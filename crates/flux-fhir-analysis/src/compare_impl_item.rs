@@ -114,13 +114,13 @@ fn check_assoc_reft(
 }
 
 pub(crate) mod errors {
-    use flux_errors::E0999;
+    use flux_errors::E9055;
     use flux_macros::Diagnostic;
     use flux_middle::rty;
     use rustc_span::{Span, Symbol};
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_incompatible_sort, code = E0999)]
+    #[diag(fhir_analysis_incompatible_sort, code = E9055)]
     pub(super) struct IncompatibleSort {
         #[primary_span]
         #[label]
@@ -142,7 +142,7 @@ pub(crate) mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_invalid_assoc_reft, code = E0999)]
+    #[diag(fhir_analysis_invalid_assoc_reft, code = E9055)]
     pub struct InvalidAssocReft {
         #[primary_span]
         span: Span,
@@ -157,7 +157,7 @@ pub(crate) mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_missing_assoc_reft, code = E0999)]
+    #[diag(fhir_analysis_missing_assoc_reft, code = E9055)]
     pub struct MissingAssocReft {
         #[primary_span]
         span: Span,
@@ -23,11 +23,11 @@ use flux_middle::{
         refining::{self, Refiner},
         ESpan, List, RefineArgsExt, WfckResults, INNERMOST,
     },
-    MaybeExternId,
+    FluxDefId, MaybeExternId,
 };
 use flux_rustc_bridge::{lowering::Lower, ToRustc};
 use itertools::Itertools;
-use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::{fx::FxIndexMap, unord::UnordMap};
 use rustc_errors::Diagnostic;
 use rustc_hash::FxHashSet;
 use rustc_hir::{def::DefKind, def_id::DefId, OwnerId, PrimTy, Safety};
@@ -57,6 +57,9 @@ pub(crate) struct AfterSortck<'a, 'genv, 'tcx> {
     next_type_index: u32,
     next_region_index: u32,
     next_const_index: u32,
+    /// The expected value of const holes created for arithmetic over const generics, which is
+    /// checked against the Rust type (see [`fhir::ConstArgKind::BinaryOp`])
+    const_exprs: UnordMap<rty::ConstVid, rty::Expr>,
 }
 
 /// We do conversion twice: once before sort checking when we don't have elaborated information
@@ -94,6 +97,10 @@ pub trait ConvPhase<'genv, 'tcx>: Sized {
     /// [`rty::AliasReft`]. Used during the first phase to collect the sorts of refinement aliases.
     fn insert_alias_reft_sort(&mut self, fhir_id: FhirId, fsort: rty::FuncSort);
 
+    /// Called after converting a const argument with arithmetic over const generics into the hole
+    /// `vid`. Used during the second phase to check the expression against the Rust type.
+    fn insert_const_expr(&mut self, vid: rty::ConstVid, expr: rty::Expr);
+
     fn into_conv_ctxt(self) -> ConvCtxt<Self> {
         ConvCtxt(self)
     }
@@ -161,6 +168,10 @@ impl<'genv, 'tcx> ConvPhase<'genv, 'tcx> for AfterSortck<'_, 'genv, 'tcx> {
     fn insert_bty_sort(&mut self, _: FhirId, _: rty::Sort) {}
 
     fn insert_alias_reft_sort(&mut self, _: FhirId, _: rty::FuncSort) {}
+
+    fn insert_const_expr(&mut self, vid: rty::ConstVid, expr: rty::Expr) {
+        self.const_exprs.insert(vid, expr);
+    }
 }
 
 impl WfckResultsProvider for WfckResults {
@@ -370,7 +381,7 @@ pub(crate) fn conv_defn(
         env.push_layer(Layer::list(wfckresults, 0, func.args));
         let expr = cx.conv_expr(&mut env, body)?;
         let expr = rty::Binder::bind_with_vars(expr, env.pop_layer().into_bound_vars(genv)?);
-        Ok(Some(rty::SpecFunc { name: func.name, expr }))
+        Ok(Some(rty::SpecFunc { def_id: FluxDefId::local(func.name), expr }))
     } else {
         Ok(None)
     }
@@ -386,7 +397,7 @@ pub(crate) fn conv_qualifier(
     env.push_layer(Layer::list(wfckresults, 0, qualifier.args));
     let body = cx.conv_expr(&mut env, &qualifier.expr)?;
     let body = rty::Binder::bind_with_vars(body, env.pop_layer().into_bound_vars(genv)?);
-    Ok(rty::Qualifier { name: qualifier.name, body, global: qualifier.global })
+    Ok(rty::Qualifier { def_id: FluxDefId::local(qualifier.name), body, global: qualifier.global })
 }
 
 pub(crate) fn conv_default_type_parameter(
@@ -415,10 +426,18 @@ impl<'a, 'genv, 'tcx> AfterSortck<'a, 'genv, 'tcx> {
             next_type_index: 1,
             next_region_index: 0,
             next_const_index: 0,
+            const_exprs: UnordMap::default(),
         }
     }
 }
 
+impl ConvCtxt<AfterSortck<'_, '_, '_>> {
+    /// The expected value of the const holes created for arithmetic over const generics.
+    pub(crate) fn const_exprs(&self) -> &UnordMap<rty::ConstVid, rty::Expr> {
+        &self.0.const_exprs
+    }
+}
+
 /// Delegate methods to P
 impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
     fn genv(&self) -> GlobalEnv<'genv, 'tcx> {
@@ -693,6 +712,10 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 self.check_prim_sort_generics(path, fhir::PrimSort::Real)?;
                 return Ok(rty::Sort::Real);
             }
+            fhir::SortRes::PrimSort(fhir::PrimSort::Str) => {
+                self.check_prim_sort_generics(path, fhir::PrimSort::Str)?;
+                return Ok(rty::Sort::Str);
+            }
             fhir::SortRes::SortParam(n) => return Ok(rty::Sort::Var(rty::ParamSort::from(n))),
             fhir::SortRes::TyParam(def_id) => {
                 if !path.args.is_empty() {
@@ -744,7 +767,7 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 self.check_prim_sort_generics(path, fhir::PrimSort::Map)?;
                 rty::SortCtor::Map
             }
-            fhir::SortRes::User { name } => {
+            fhir::SortRes::User(def_id) => {
                 if !path.args.is_empty() {
                     let err = errors::GenericsOnUserDefinedOpaqueSort::new(
                         path.segments.last().unwrap().span,
@@ -752,7 +775,7 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                     );
                     Err(self.emit(err))?;
                 }
-                rty::SortCtor::User { name }
+                rty::SortCtor::User(def_id)
             }
             fhir::SortRes::Adt(def_id) => {
                 let sort_def = self.genv().adt_sort_def_of(def_id)?;
@@ -1072,13 +1095,6 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 ))
             }
             fhir::TyKind::OpaqueDef(opaque_ty) => self.conv_opaque_def(env, opaque_ty),
-            fhir::TyKind::TraitObject(trait_bounds, lft, syn) => {
-                if matches!(syn, rustc_ast::TraitObjectSyntax::Dyn) {
-                    self.conv_trait_object(env, trait_bounds, *lft)
-                } else {
-                    span_bug!(ty.span, "dyn* traits not supported yet")
-                }
-            }
             fhir::TyKind::Infer => Ok(rty::Ty::infer(self.next_type_vid())),
         }
     }
@@ -1127,7 +1143,7 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
         env: &mut Env,
         trait_bounds: &[fhir::PolyTraitRef],
         lifetime: fhir::Lifetime,
-    ) -> QueryResult<rty::Ty> {
+    ) -> QueryResult<rty::BaseTy> {
         // We convert all the trait bounds into existential predicates. Some combinations won't yield
         // valid rust types (e.g., only one regular (non-auto) trait is allowed). We don't detect those
         // errors here, but that's fine because we should catch them when we check structural
@@ -1212,7 +1228,8 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
         };
 
         let region = self.conv_lifetime(env, lifetime);
-        Ok(rty::Ty::dynamic(existential_predicates, region))
+        let sort_def = self.genv().trait_object_sort_def(&existential_predicates)?;
+        Ok(rty::BaseTy::Dynamic(existential_predicates, region, sort_def))
     }
 
     pub(crate) fn conv_bty(
@@ -1241,6 +1258,17 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 let ty = rty::Ty::indexed(bty, rty::Expr::nu());
                 Ok(rty::TyOrCtor::Ctor(rty::Binder::bind_with_sort(ty, sort)))
             }
+            fhir::BaseTyKind::TraitObject(trait_bounds, lft, syn) => {
+                if !matches!(syn, rustc_ast::TraitObjectSyntax::Dyn) {
+                    span_bug!(bty.span, "`dyn*` trait objects are rejected during lifting");
+                }
+                let bty = self
+                    .conv_trait_object(env, trait_bounds, *lft)?
+                    .shift_in_escaping(1);
+                let sort = bty.sort();
+                let ty = rty::Ty::indexed(bty, rty::Expr::nu());
+                Ok(rty::TyOrCtor::Ctor(rty::Binder::bind_with_sort(ty, sort)))
+            }
         }
     }
 
@@ -1422,6 +1450,12 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
 
     fn conv_const_arg(&mut self, cst: fhir::ConstArg) -> rty::Const {
         match cst.kind {
+            fhir::ConstArgKind::BinaryOp(..) => {
+                let vid = self.next_const_vid();
+                let expr = self.conv_const_expr(cst);
+                self.0.insert_const_expr(vid, expr);
+                rty::Const { kind: rty::ConstKind::Infer(ty::InferConst::Var(vid)) }
+            }
             fhir::ConstArgKind::Lit(lit) => rty::Const::from_usize(self.tcx(), lit),
             fhir::ConstArgKind::Param(def_id) => {
                 rty::Const {
@@ -1436,6 +1470,27 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
         }
     }
 
+    fn conv_const_expr(&self, cst: fhir::ConstArg) -> rty::Expr {
+        match cst.kind {
+            fhir::ConstArgKind::Lit(lit) => rty::Expr::constant(rty::Constant::from(lit)),
+            fhir::ConstArgKind::Param(def_id) => {
+                rty::Expr::const_generic(def_id_to_param_const(self.genv(), def_id))
+            }
+            fhir::ConstArgKind::BinaryOp(op, [c1, c2]) => {
+                let op = match op {
+                    fhir::BinOp::Add => rty::BinOp::Add,
+                    fhir::BinOp::Sub => rty::BinOp::Sub,
+                    fhir::BinOp::Mul => rty::BinOp::Mul,
+                    fhir::BinOp::Div => rty::BinOp::Div,
+                    fhir::BinOp::Mod => rty::BinOp::Mod,
+                    _ => span_bug!(cst.span, "unexpected operator in const argument"),
+                };
+                rty::Expr::binary_op(op, self.conv_const_expr(*c1), self.conv_const_expr(*c2))
+            }
+            fhir::ConstArgKind::Infer => span_bug!(cst.span, "unexpected hole in const argument"),
+        }
+    }
+
     fn conv_qpath(
         &mut self,
         env: &mut Env,
@@ -2132,7 +2187,12 @@ pub fn conv_func_decl(genv: GlobalEnv, func: &fhir::SpecFunc) -> QueryResult<rty
         .take(func.params)
         .collect();
     let sort = rty::PolyFuncSort::new(params, rty::FuncSort { inputs_and_output });
-    let kind = if func.body.is_some() { fhir::SpecFuncKind::Def } else { fhir::SpecFuncKind::Uif };
+    let def_id = FluxDefId::local(func.name);
+    let kind = if func.body.is_some() {
+        fhir::SpecFuncKind::Def(def_id)
+    } else {
+        fhir::SpecFuncKind::Uif(def_id)
+    };
     Ok(rty::SpecFuncDecl { name: func.name, sort, kind })
 }
 
@@ -2153,7 +2213,7 @@ fn emit_prim_sort_generics_error(
 fn conv_lit(lit: fhir::Lit) -> rty::Constant {
     match lit {
         fhir::Lit::Int(n) => rty::Constant::from(n),
-        fhir::Lit::Real(r) => rty::Constant::Real(rty::Real(r)),
+        fhir::Lit::Real(r) => rty::Constant::Real(r),
         fhir::Lit::Bool(b) => rty::Constant::from(b),
         fhir::Lit::Str(s) => rty::Constant::from(s),
         fhir::Lit::Char(c) => rty::Constant::from(c),
@@ -2197,14 +2257,14 @@ fn ty_param_name(genv: GlobalEnv, def_id: DefId) -> Symbol {
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::{E9050, E9051, E9052};
     use flux_macros::Diagnostic;
     use flux_middle::{fhir, global_env::GlobalEnv};
     use rustc_hir::def_id::DefId;
     use rustc_span::{symbol::Ident, Span};
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_assoc_type_not_found, code = E0999)]
+    #[diag(fhir_analysis_assoc_type_not_found, code = E9052)]
     #[note]
     pub(super) struct AssocTypeNotFound {
         #[primary_span]
@@ -2219,7 +2279,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_ambiguous_assoc_type, code = E0999)]
+    #[diag(fhir_analysis_ambiguous_assoc_type, code = E9052)]
     pub(super) struct AmbiguousAssocType {
         #[primary_span]
         span: Span,
@@ -2233,7 +2293,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_invalid_base_instance, code = E0999)]
+    #[diag(fhir_analysis_invalid_base_instance, code = E9051)]
     pub(super) struct InvalidBaseInstance {
         #[primary_span]
         span: Span,
@@ -2246,7 +2306,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_generic_argument_count_mismatch, code = E0999)]
+    #[diag(fhir_analysis_generic_argument_count_mismatch, code = E9050)]
     pub(super) struct GenericArgCountMismatch {
         #[primary_span]
         #[label]
@@ -2273,7 +2333,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_too_few_generic_args, code = E0999)]
+    #[diag(fhir_analysis_too_few_generic_args, code = E9050)]
     pub(super) struct TooFewGenericArgs {
         #[primary_span]
         #[label]
@@ -2300,7 +2360,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_too_many_generic_args, code = E0999)]
+    #[diag(fhir_analysis_too_many_generic_args, code = E9050)]
     pub(super) struct TooManyGenericArgs {
         #[primary_span]
         #[label]
@@ -2327,7 +2387,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_refined_unrefinable_type, code = E0999)]
+    #[diag(fhir_analysis_refined_unrefinable_type, code = E9051)]
     pub(super) struct RefinedUnrefinableType {
         #[primary_span]
         span: Span,
@@ -2340,7 +2400,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_generics_on_primitive_sort, code = E0999)]
+    #[diag(fhir_analysis_generics_on_primitive_sort, code = E9050)]
     pub(super) struct GenericsOnPrimitiveSort {
         #[primary_span]
         #[label]
@@ -2357,7 +2417,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_incorrect_generics_on_sort, code = E0999)]
+    #[diag(fhir_analysis_incorrect_generics_on_sort, code = E9050)]
     pub(super) struct IncorrectGenericsOnSort {
         #[primary_span]
         #[label]
@@ -2380,7 +2440,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_generics_on_type_parameter, code = E0999)]
+    #[diag(fhir_analysis_generics_on_type_parameter, code = E9050)]
     pub(super) struct GenericsOnTyParam {
         #[primary_span]
         #[label]
@@ -2395,7 +2455,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_generics_on_self_alias, code = E0999)]
+    #[diag(fhir_analysis_generics_on_self_alias, code = E9050)]
     pub(super) struct GenericsOnSelf {
         #[primary_span]
         #[label]
@@ -2410,7 +2470,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_generics_on_opaque_sort, code = E0999)]
+    #[diag(fhir_analysis_generics_on_opaque_sort, code = E9050)]
     pub(super) struct GenericsOnUserDefinedOpaqueSort {
         #[primary_span]
         #[label]
@@ -18,7 +18,10 @@ use flux_middle::{
     },
     MaybeExternId,
 };
-use flux_rustc_bridge::ty::{self, FieldIdx, VariantIdx};
+use flux_rustc_bridge::{
+    ty::{self, FieldIdx, VariantIdx},
+    ToRustc,
+};
 use rustc_ast::Mutability;
 use rustc_data_structures::unord::UnordMap;
 use rustc_type_ir::{DebruijnIndex, InferConst, INNERMOST};
@@ -28,10 +31,11 @@ pub(crate) fn type_alias(
     alias: &fhir::TyAlias,
     alias_ty: &rty::TyCtor,
     def_id: MaybeExternId,
+    const_exprs: &UnordMap<rty::ConstVid, rty::Expr>,
 ) -> QueryResult<rty::TyCtor> {
     let rust_ty = genv.lower_type_of(def_id.resolved_id())?.skip_binder();
     let expected = Refiner::default_for_item(genv, def_id.resolved_id())?.refine_ty(&rust_ty)?;
-    let mut zipper = Zipper::new(genv, def_id);
+    let mut zipper = Zipper::new(genv, def_id, const_exprs);
 
     if zipper
         .enter_a_binder(alias_ty, |zipper, ty| zipper.zip_ty(ty, &expected))
@@ -52,12 +56,13 @@ pub(crate) fn fn_sig(
     decl: &fhir::FnDecl,
     fn_sig: &rty::PolyFnSig,
     def_id: MaybeExternId,
+    const_exprs: &UnordMap<rty::ConstVid, rty::Expr>,
 ) -> QueryResult<rty::PolyFnSig> {
     let rust_fn_sig = genv.lower_fn_sig(def_id.resolved_id())?.skip_binder();
     let expected =
         Refiner::default_for_item(genv, def_id.resolved_id())?.refine_poly_fn_sig(&rust_fn_sig)?;
 
-    let mut zipper = Zipper::new(genv, def_id);
+    let mut zipper = Zipper::new(genv, def_id, const_exprs);
     if let Err(err) = zipper.zip_poly_fn_sig(fn_sig, &expected) {
         zipper.emit_fn_sig_err(err, decl);
     }
@@ -71,9 +76,10 @@ pub(crate) fn variants(
     genv: GlobalEnv,
     variants: &[rty::PolyVariant],
     adt_def_id: MaybeExternId,
+    const_exprs: &UnordMap<rty::ConstVid, rty::Expr>,
 ) -> QueryResult<Vec<rty::PolyVariant>> {
     let refiner = Refiner::default_for_item(genv, adt_def_id.resolved_id())?;
-    let mut zipper = Zipper::new(genv, adt_def_id);
+    let mut zipper = Zipper::new(genv, adt_def_id, const_exprs);
     // TODO check same number of variants
     for (i, variant) in variants.iter().enumerate() {
         let variant_idx = VariantIdx::from_usize(i);
@@ -100,6 +106,8 @@ struct Zipper<'genv, 'tcx> {
     /// a corresponding binder in `a`. We assume that expressions filling holes will only contain
     /// variables pointing to some of these mapped binders.
     b_binder_to_a_binder: Vec<Option<u32>>,
+    /// The expected value of the const holes created for arithmetic over const generics
+    const_exprs: UnordMap<rty::ConstVid, rty::Expr>,
     errors: Errors<'genv>,
 }
 
@@ -177,7 +185,11 @@ impl Holes {
 }
 
 impl<'genv, 'tcx> Zipper<'genv, 'tcx> {
-    fn new(genv: GlobalEnv<'genv, 'tcx>, owner_id: MaybeExternId) -> Self {
+    fn new(
+        genv: GlobalEnv<'genv, 'tcx>,
+        owner_id: MaybeExternId,
+        const_exprs: &UnordMap<rty::ConstVid, rty::Expr>,
+    ) -> Self {
         Self {
             genv,
             owner_id,
@@ -185,6 +197,7 @@ impl<'genv, 'tcx> Zipper<'genv, 'tcx> {
             holes: Default::default(),
             a_binders: 0,
             b_binder_to_a_binder: vec![],
+            const_exprs: const_exprs.clone(),
             errors: Errors::new(genv.sess()),
         }
     }
@@ -350,7 +363,7 @@ impl<'genv, 'tcx> Zipper<'genv, 'tcx> {
             (rty::BaseTy::Param(pty_a), rty::BaseTy::Param(pty_b)) => {
                 assert_eq_or_incompatible(pty_a, pty_b)
             }
-            (rty::BaseTy::Dynamic(preds_a, re_a), rty::BaseTy::Dynamic(preds_b, re_b)) => {
+            (rty::BaseTy::Dynamic(preds_a, re_a, _), rty::BaseTy::Dynamic(preds_b, re_b, _)) => {
                 assert_eq_or_incompatible(preds_a.len(), preds_b.len())?;
                 for (pred_a, pred_b) in iter::zip(preds_a, preds_b) {
                     self.zip_poly_existential_pred(pred_a, pred_b)?;
@@ -410,6 +423,17 @@ impl<'genv, 'tcx> Zipper<'genv, 'tcx> {
     fn zip_const(&mut self, a: &rty::Const, b: &ty::Const) -> Result<(), Mismatch> {
         match (&a.kind, &b.kind) {
             (rty::ConstKind::Infer(ty::InferConst::Var(cid)), _) => {
+                // Arithmetic over const generics must be the same expression in the Rust type
+                if let Some(expected) = self.const_exprs.get(cid) {
+                    let tcx = self.genv.tcx();
+                    let found = match &b.kind {
+                        ty::ConstKind::Unevaluated(_) => rty::Expr::try_from_const(tcx, b),
+                        _ => None,
+                    };
+                    if found.as_ref() != Some(expected) {
+                        return Err(Mismatch::new(a, b));
+                    }
+                }
                 self.holes.consts.insert(*cid, b.clone());
                 Ok(())
             }
@@ -420,6 +444,17 @@ impl<'genv, 'tcx> Zipper<'genv, 'tcx> {
                 assert_eq_or_incompatible(ty_a, ty_b)?;
                 assert_eq_or_incompatible(val_a, val_b)
             }
+            // Rustc keeps constants in signatures unevaluated, e.g., the length in `[i32; BUFLEN]`,
+            // but we evaluate them when desugaring.
+            (rty::ConstKind::Value(..), ty::ConstKind::Unevaluated(_)) => {
+                let tcx = self.genv.tcx();
+                let param_env = tcx.param_env(self.owner_id.resolved_id());
+                let val_b = b
+                    .to_rustc(tcx)
+                    .normalize_internal(tcx, param_env)
+                    .try_to_target_usize(tcx);
+                assert_eq_or_incompatible(a.to_rustc(tcx).try_to_target_usize(tcx), val_b)
+            }
             (rty::ConstKind::Unevaluated(c1), ty::ConstKind::Unevaluated(c2)) => {
                 assert_eq_or_incompatible(c1, c2)
             }
@@ -632,7 +667,7 @@ enum FnSigErr {
 
 mod errors {
     use flux_common::span_bug;
-    use flux_errors::E0999;
+    use flux_errors::E9053;
     use flux_macros::Diagnostic;
     use flux_middle::{fhir, global_env::GlobalEnv, rty, MaybeExternId};
     use flux_rustc_bridge::{
@@ -642,7 +677,7 @@ mod errors {
     use rustc_span::{Span, DUMMY_SP};
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_incompatible_refinement, code = E0999)]
+    #[diag(fhir_analysis_incompatible_refinement, code = E9053)]
     #[note]
     pub(super) struct IncompatibleRefinement<'tcx> {
         #[primary_span]
@@ -786,7 +821,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_incompatible_param_count, code = E0999)]
+    #[diag(fhir_analysis_incompatible_param_count, code = E9053)]
     pub(super) struct IncompatibleParamCount {
         #[primary_span]
         #[label]
@@ -834,7 +869,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_field_count_mismatch, code = E0999)]
+    #[diag(fhir_analysis_field_count_mismatch, code = E9053)]
     pub(super) struct FieldCountMismatch {
         #[primary_span]
         #[label]
@@ -74,16 +74,11 @@ fn adt_sort_def_of(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<rty::AdtS
 }
 
 fn spec_func_decl(genv: GlobalEnv, name: Symbol) -> QueryResult<rty::SpecFuncDecl> {
-    if let Some(func) = genv.map().spec_func(name) {
-        conv::conv_func_decl(genv, func)
-    } else {
-        let itf = flux_middle::THEORY_FUNCS.get(&name).unwrap();
-        Ok(rty::SpecFuncDecl {
-            name: itf.name,
-            sort: itf.sort.clone(),
-            kind: fhir::SpecFuncKind::Thy(itf.fixpoint_name),
-        })
-    }
+    let func = genv
+        .map()
+        .spec_func(name)
+        .ok_or_else(|| query_bug!("cannot find spec function `{name}`"))?;
+    conv::conv_func_decl(genv, func)
 }
 
 fn spec_func_defns(genv: GlobalEnv) -> QueryResult<rty::SpecFuncDefns> {
@@ -99,13 +94,23 @@ fn spec_func_defns(genv: GlobalEnv) -> QueryResult<rty::SpecFuncDefns> {
             continue;
         };
         if let Some(defn) = defn {
-            defns.insert(defn.name, defn);
+            defns.insert(defn.def_id, defn);
         }
     }
     errors.into_result()?;
 
+    // Definitions exported by external crates are already normalized so they cannot be part of a
+    // cycle.
+    for (_, items) in genv.iter_extern_flux_items() {
+        for defn in &items.func_defns {
+            defns.insert(defn.def_id, defn.clone());
+        }
+    }
+
     let defns = rty::SpecFuncDefns::new(defns)
         .map_err(|cycle| {
+            // Extern definitions cannot be part of a cycle, so all functions in it are local.
+            let cycle = cycle.into_iter().map(|def_id| def_id.name).collect_vec();
             let span = genv.map().spec_func(cycle[0]).unwrap().body.unwrap().span;
             errors::DefinitionCycle::new(span, cycle)
         })
@@ -167,9 +172,14 @@ fn predicates_of(
                 .get_generics(def_id.local_id())?
                 .ok_or_else(|| query_bug!(def_id.local_id(), "no generics for {def_id:?}"))?;
             let wfckresults = genv.check_wf(def_id.local_id())?;
-            AfterSortck::new(genv, &wfckresults)
+            let predicates = AfterSortck::new(genv, &wfckresults)
                 .into_conv_ctxt()
-                .conv_generic_predicates(def_id, generics)
+                .conv_generic_predicates(def_id, generics)?;
+            if let DefKind::Fn | DefKind::AssocFn = genv.def_kind(def_id) {
+                add_missing_fn_trait_clauses(genv, def_id, predicates)
+            } else {
+                Ok(predicates)
+            }
         }
         DefKind::OpaqueTy | DefKind::Closure => {
             Ok(rty::EarlyBinder(rty::GenericPredicates {
@@ -186,6 +196,82 @@ fn predicates_of(
     }
 }
 
+/// The where clauses in a function signature replace the ones in Rust, but the `Fn` trait bounds are
+/// what makes us check the body of closures passed as arguments. Thus, we add the (unrefined) `Fn`
+/// clauses from Rust for every type that doesn't have a corresponding clause in the signature, such
+/// that closures are checked even if the signature doesn't mention their bounds.
+fn add_missing_fn_trait_clauses(
+    genv: GlobalEnv,
+    def_id: MaybeExternId,
+    predicates: rty::EarlyBinder<rty::GenericPredicates>,
+) -> QueryResult<rty::EarlyBinder<rty::GenericPredicates>> {
+    let tcx = genv.tcx();
+
+    // Returns the self type of a `Fn*` trait clause or an `FnOnce::Output` projection clause. The
+    // boolean distinguishes between the two.
+    let fn_trait_key = |clause: &rty::Clause| {
+        match clause.kind_skipping_binder() {
+            rty::ClauseKind::Trait(pred)
+                if tcx
+                    .fn_trait_kind_from_def_id(pred.trait_ref.def_id)
+                    .is_some() =>
+            {
+                Some((false, pred.trait_ref.self_ty().as_bty_skipping_binder().clone()))
+            }
+            rty::ClauseKind::Projection(pred)
+                if genv.is_fn_once_output(pred.projection_ty.def_id) =>
+            {
+                Some((true, pred.self_ty().as_bty_skipping_binder().clone()))
+            }
+            _ => None,
+        }
+    };
+
+    // We only lower the `Fn` clauses because other clauses may not be supported
+    let rust_predicates = tcx.predicates_of(def_id.resolved_id());
+    let rust_predicates = rustc_middle::ty::GenericPredicates {
+        parent: rust_predicates.parent,
+        predicates: tcx.arena.alloc_from_iter(
+            rust_predicates
+                .predicates
+                .iter()
+                .filter(|(clause, _)| {
+                    clause
+                        .as_trait_clause()
+                        .is_some_and(|pred| tcx.fn_trait_kind_from_def_id(pred.def_id()).is_some())
+                        || clause
+                            .as_projection_clause()
+                            .is_some_and(|pred| genv.is_fn_once_output(pred.projection_def_id()))
+                })
+                .copied(),
+        ),
+    };
+    let rust_predicates = rust_predicates
+        .lower(tcx)
+        .map_err(|err| QueryErr::unsupported(def_id.resolved_id(), err))?;
+    let rust_predicates = Refiner::default_for_item(genv, def_id.resolved_id())?
+        .refine_generic_predicates(&rust_predicates)?;
+
+    let rty::EarlyBinder(mut predicates) = predicates;
+    let keys: Vec<_> = predicates
+        .predicates
+        .iter()
+        .filter_map(fn_trait_key)
+        .collect();
+    let missing = rust_predicates
+        .predicates
+        .iter()
+        .filter(|clause| fn_trait_key(clause).is_some_and(|key| !keys.contains(&key)))
+        .cloned();
+    predicates.predicates = predicates
+        .predicates
+        .iter()
+        .cloned()
+        .chain(missing)
+        .collect();
+    Ok(rty::EarlyBinder(predicates))
+}
+
 fn assoc_refinements_of(
     genv: GlobalEnv,
     local_id: LocalDefId,
@@ -412,7 +498,13 @@ fn type_of(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<rty::EarlyBinder<
             let wfckresults = genv.check_wf(def_id.local_id())?;
             let mut cx = AfterSortck::new(genv, &wfckresults).into_conv_ctxt();
             let ty_alias = cx.conv_type_alias(def_id, fhir_ty_alias)?;
-            struct_compat::type_alias(genv, fhir_ty_alias, &ty_alias, def_id)?;
+            let ty_alias = struct_compat::type_alias(
+                genv,
+                fhir_ty_alias,
+                &ty_alias,
+                def_id,
+                cx.const_exprs(),
+            )?;
             rty::TyOrCtor::Ctor(ty_alias)
         }
         DefKind::TyParam => {
@@ -479,7 +571,8 @@ fn variants_of(
             let wfckresults = genv.check_wf(local_id)?;
             let mut cx = AfterSortck::new(genv, &wfckresults).into_conv_ctxt();
             let variants = cx.conv_enum_variants(def_id, enum_def)?;
-            let variants = rty::List::from_vec(struct_compat::variants(genv, &variants, def_id)?);
+            let variants = struct_compat::variants(genv, &variants, def_id, cx.const_exprs())?;
+            let variants = rty::List::from_vec(variants);
             rty::Opaqueness::Transparent(rty::EarlyBinder(variants))
         }
         fhir::ItemKind::Struct(struct_def) => {
@@ -487,7 +580,8 @@ fn variants_of(
             let mut cx = AfterSortck::new(genv, &wfckresults).into_conv_ctxt();
             cx.conv_struct_variant(def_id, struct_def)?
                 .map(|variant| -> QueryResult<_> {
-                    let variants = struct_compat::variants(genv, &[variant], def_id)?;
+                    let variants =
+                        struct_compat::variants(genv, &[variant], def_id, cx.const_exprs())?;
                     Ok(rty::List::from_vec(variants))
                 })
                 .transpose()?
@@ -509,10 +603,14 @@ fn fn_sig(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<rty::EarlyBinder<r
         .fn_sig()
         .unwrap();
     let wfckresults = genv.check_wf(def_id.local_id())?;
-    let fn_sig = AfterSortck::new(genv, &wfckresults)
-        .into_conv_ctxt()
-        .conv_fn_sig(def_id, fhir_fn_sig)?;
-    let fn_sig = struct_compat::fn_sig(genv, fhir_fn_sig.decl, &fn_sig, def_id)?;
+    let mut cx = AfterSortck::new(genv, &wfckresults).into_conv_ctxt();
+    let fn_sig = cx.conv_fn_sig(def_id, fhir_fn_sig)?;
+    let mut fn_sig =
+        struct_compat::fn_sig(genv, fhir_fn_sig.decl, &fn_sig, def_id, cx.const_exprs())?;
+
+    if let Some(adt_def) = rty::derive::derived_eq_enum(genv, def_id.local_id())? {
+        fn_sig = rty::derive::derived_eq_sig(&adt_def, &fn_sig);
+    }
 
     if config::dump_rty() {
         let generics = genv.generics_of(def_id)?;
@@ -535,9 +633,7 @@ fn check_wf(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<Rc<WfckResults>>
 }
 
 pub fn check_crate_wf(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
-    let mut errors = Errors::new(genv.sess());
-
-    let qualifiers = genv.map().qualifiers().map(|q| q.name).collect();
+    let errors = Errors::new(genv.sess());
 
     for def_id in genv.tcx().hir_crate_items(()).definitions() {
         if genv.ignored(def_id) || genv.is_dummy(def_id) {
@@ -561,11 +657,6 @@ pub fn check_crate_wf(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
             }
             _ => {}
         }
-        if matches!(def_kind, DefKind::Fn | DefKind::AssocFn) {
-            if let Ok(fn_quals) = genv.map().fn_quals_for(def_id).emit(&errors) {
-                wf::check_fn_quals(genv.sess(), &qualifiers, fn_quals).collect_err(&mut errors);
-            }
-        }
     }
 
     // Query qualifiers and spec funcs to report wf errors
@@ -576,12 +667,12 @@ pub fn check_crate_wf(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::E9054;
     use flux_macros::Diagnostic;
     use rustc_span::{Span, Symbol};
 
     #[derive(Diagnostic)]
-    #[diag(fhir_analysis_definition_cycle, code = E0999)]
+    #[diag(fhir_analysis_definition_cycle, code = E9054)]
     pub struct DefinitionCycle {
         #[primary_span]
         #[label]
@@ -1,10 +1,10 @@
-use flux_errors::E0999;
+use flux_errors::{E9031, E9040, E9041, E9042, E9043, E9045};
 use flux_macros::Diagnostic;
 use flux_middle::{fhir, rty};
 use rustc_span::{symbol::Ident, Span, Symbol};
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_sort_mismatch, code = E0999)]
+#[diag(fhir_analysis_sort_mismatch, code = E9040)]
 pub(super) struct SortMismatch {
     #[primary_span]
     #[label]
@@ -20,7 +20,7 @@ impl SortMismatch {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_arg_count_mismatch, code = E0999)]
+#[diag(fhir_analysis_arg_count_mismatch, code = E9041)]
 pub(super) struct ArgCountMismatch {
     #[primary_span]
     #[label]
@@ -37,7 +37,7 @@ impl ArgCountMismatch {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_early_bound_arg_count_mismatch, code = E0999)]
+#[diag(fhir_analysis_early_bound_arg_count_mismatch, code = E9041)]
 pub(super) struct EarlyBoundArgCountMismatch {
     #[primary_span]
     #[label]
@@ -53,7 +53,7 @@ impl EarlyBoundArgCountMismatch {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_duplicated_ensures, code = E0999)]
+#[diag(fhir_analysis_duplicated_ensures, code = E9045)]
 pub(super) struct DuplicatedEnsures {
     #[primary_span]
     span: Span,
@@ -67,20 +67,7 @@ impl DuplicatedEnsures {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_unknown_qualifier, code = E0999)]
-pub(super) struct UnknownQualifier {
-    #[primary_span]
-    span: Span,
-}
-
-impl UnknownQualifier {
-    pub(super) fn new(span: Span) -> UnknownQualifier {
-        Self { span }
-    }
-}
-
-#[derive(Diagnostic)]
-#[diag(fhir_analysis_missing_ensures, code = E0999)]
+#[diag(fhir_analysis_missing_ensures, code = E9045)]
 pub(super) struct MissingEnsures {
     #[primary_span]
     span: Span,
@@ -93,7 +80,7 @@ impl MissingEnsures {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_expected_fun, code = E0999)]
+#[diag(fhir_analysis_expected_fun, code = E9040)]
 pub(super) struct ExpectedFun<'a> {
     #[primary_span]
     span: Span,
@@ -107,7 +94,7 @@ impl<'a> ExpectedFun<'a> {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_invalid_param_in_func_pos, code = E0999)]
+#[diag(fhir_analysis_invalid_param_in_func_pos, code = E9031)]
 pub(super) struct InvalidParamPos<'a> {
     #[primary_span]
     #[label]
@@ -123,7 +110,7 @@ impl<'a> InvalidParamPos<'a> {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_unexpected_fun, code = E0999)]
+#[diag(fhir_analysis_unexpected_fun, code = E9040)]
 pub(super) struct UnexpectedFun<'a> {
     #[primary_span]
     #[label]
@@ -138,7 +125,7 @@ impl<'a> UnexpectedFun<'a> {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_unexpected_constructor, code = E0999)]
+#[diag(fhir_analysis_unexpected_constructor, code = E9040)]
 pub(super) struct UnexpectedConstructor<'a> {
     #[primary_span]
     #[label]
@@ -153,7 +140,7 @@ impl<'a> UnexpectedConstructor<'a> {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_param_count_mismatch, code = E0999)]
+#[diag(fhir_analysis_param_count_mismatch, code = E9041)]
 pub(super) struct ParamCountMismatch {
     #[primary_span]
     #[label]
@@ -169,7 +156,7 @@ impl ParamCountMismatch {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_field_not_found, code = E0999)]
+#[diag(fhir_analysis_field_not_found, code = E9040)]
 pub(super) struct FieldNotFound {
     #[primary_span]
     span: Span,
@@ -184,7 +171,7 @@ impl FieldNotFound {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_constructor_missing_fields, code = E0999)]
+#[diag(fhir_analysis_constructor_missing_fields, code = E9040)]
 pub(super) struct ConstructorMissingFields {
     #[primary_span]
     constructor_span: Span,
@@ -203,7 +190,7 @@ impl ConstructorMissingFields {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_duplicate_field_used, code = E0999)]
+#[diag(fhir_analysis_duplicate_field_used, code = E9040)]
 pub(super) struct DuplicateFieldUsed {
     #[primary_span]
     span: Span,
@@ -219,7 +206,7 @@ impl DuplicateFieldUsed {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_invalid_primitive_dot_access, code = E0999)]
+#[diag(fhir_analysis_invalid_primitive_dot_access, code = E9040)]
 pub(super) struct InvalidPrimitiveDotAccess<'a> {
     #[primary_span]
     span: Span,
@@ -233,7 +220,7 @@ impl<'a> InvalidPrimitiveDotAccess<'a> {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_param_not_determined, code = E0999)]
+#[diag(fhir_analysis_param_not_determined, code = E9043)]
 #[help]
 pub(super) struct ParamNotDetermined {
     #[primary_span]
@@ -249,7 +236,7 @@ impl ParamNotDetermined {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_sort_annotation_needed, code = E0999)]
+#[diag(fhir_analysis_sort_annotation_needed, code = E9042)]
 pub(super) struct SortAnnotationNeeded {
     #[primary_span]
     #[label]
@@ -263,7 +250,7 @@ impl SortAnnotationNeeded {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_cannot_infer_sort, code = E0999)]
+#[diag(fhir_analysis_cannot_infer_sort, code = E9042)]
 #[note]
 pub(super) struct CannotInferSort {
     #[primary_span]
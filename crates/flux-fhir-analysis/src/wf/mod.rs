@@ -9,7 +9,7 @@ mod sortck;
 use std::iter;
 
 use flux_common::result::{ErrorCollector, ResultExt as _};
-use flux_errors::{Errors, FluxSession};
+use flux_errors::Errors;
 use flux_middle::{
     fhir::{self, visit::Visitor, FhirId, FluxOwnerId},
     global_env::GlobalEnv,
@@ -17,7 +17,6 @@ use flux_middle::{
     rty::{self, WfckResults},
     MaybeExternId,
 };
-use rustc_data_structures::unord::UnordSet;
 use rustc_errors::ErrorGuaranteed;
 use rustc_hash::FxHashSet;
 use rustc_hir::{
@@ -25,7 +24,6 @@ use rustc_hir::{
     def_id::{CrateNum, DefId, DefIndex},
     OwnerId,
 };
-use rustc_span::{symbol::Ident, Symbol};
 
 use self::sortck::{ImplicitParamInferer, InferCtxt};
 use crate::conv::{ConvPhase, WfckResultsProvider};
@@ -107,20 +105,6 @@ fn resolve_params(infcx: &mut InferCtxt, node: &fhir::OwnerNode) -> Result {
     visit_refine_params(node, |param| infcx.resolve_param_sort(param))
 }
 
-pub(crate) fn check_fn_quals(
-    sess: &FluxSession,
-    qualifiers: &UnordSet<Symbol>,
-    fn_quals: &[Ident],
-) -> Result {
-    for qual in fn_quals {
-        if !qualifiers.contains(&qual.name) {
-            let span = qual.span;
-            return Err(sess.emit_err(errors::UnknownQualifier::new(span)));
-        }
-    }
-    Ok(())
-}
-
 struct Wf<'a, 'genv, 'tcx> {
     infcx: &'a mut InferCtxt<'genv, 'tcx>,
     errors: Errors<'genv>,
@@ -458,6 +442,8 @@ impl<'genv, 'tcx> ConvPhase<'genv, 'tcx> for Wf<'_, 'genv, 'tcx> {
     fn insert_alias_reft_sort(&mut self, fhir_id: FhirId, fsort: rty::FuncSort) {
         self.infcx.insert_sort_for_alias_reft(fhir_id, fsort);
     }
+
+    fn insert_const_expr(&mut self, _: rty::ConstVid, _: rty::Expr) {}
 }
 
 /// The purpose of doing conversion before sort checking is to collect the sorts of base types.
@@ -358,8 +358,9 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
                 Ok(rty::Sort::Bool)
             }
             fhir::UnOp::Neg => {
-                self.check_expr(e, &rty::Sort::Int)?;
-                Ok(rty::Sort::Int)
+                let sort = self.next_num_var();
+                self.check_expr(e, &sort)?;
+                Ok(sort)
             }
         }
     }
@@ -416,7 +417,13 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
                 };
                 fsort
             }
-            ExprRes::GlobalFunc(.., sym) => self.genv.func_decl(sym).emit(&self.genv)?.sort.clone(),
+            ExprRes::GlobalFunc(fhir::SpecFuncKind::Thy(_), name) => {
+                flux_middle::THEORY_FUNCS.get(&name).unwrap().sort.clone()
+            }
+            ExprRes::GlobalFunc(
+                fhir::SpecFuncKind::Uif(def_id) | fhir::SpecFuncKind::Def(def_id),
+                _,
+            ) => self.genv.func_decl(def_id).emit(&self.genv)?.sort.clone(),
             _ => span_bug!(func.span, "unexpected path in function position"),
         };
         Ok(self.instantiate_func_sort(poly_fsort))
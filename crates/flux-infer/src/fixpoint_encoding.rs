@@ -1,6 +1,10 @@
 //! Encoding of the refinement tree into a fixpoint constraint.
 
-use std::{hash::Hash, iter};
+use std::{
+    hash::Hash,
+    io, iter,
+    time::{Duration, Instant},
+};
 
 use flux_common::{
     bug,
@@ -19,17 +23,17 @@ use flux_middle::{
     global_env::GlobalEnv,
     queries::QueryResult,
     rty::{self, BoundVariableKind, ESpan, Lambda, List},
-    MaybeExternId,
+    FluxDefId, MaybeExternId,
 };
 use itertools::Itertools;
-use liquid_fixpoint::FixpointResult;
+use liquid_fixpoint::{FixpointResult, Stats};
 use rustc_data_structures::{
     fx::FxIndexMap,
     unord::{UnordMap, UnordSet},
 };
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_index::newtype_index;
-use rustc_span::{Span, Symbol};
+use rustc_span::Span;
 use rustc_type_ir::{BoundVar, DebruijnIndex};
 use serde::{Deserialize, Deserializer, Serialize};
 
@@ -141,7 +145,18 @@ pub mod fixpoint {
 
     impl FixpointFmt for SymStr {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(f, "\"{}\"", self.0)
+            // Fixpoint parses string literals with Haskell's escapes, so we escape quotes and
+            // backslashes, and write any other character as its decimal code followed by the
+            // empty escape `\&` to separate it from a subsequent digit.
+            write!(f, "\"")?;
+            for c in self.0.as_str().chars() {
+                match c {
+                    '"' | '\\' => write!(f, "\\{c}")?,
+                    ' '..='~' => write!(f, "{c}")?,
+                    _ => write!(f, "\\{}\\&", c as u32)?,
+                }
+            }
+            write!(f, "\"")
         }
     }
 
@@ -304,7 +319,7 @@ type ConstMap<'tcx> = FxIndexMap<Key<'tcx>, ConstInfo>;
 
 #[derive(Eq, Hash, PartialEq)]
 enum Key<'tcx> {
-    Uif(Symbol),
+    Uif(FluxDefId),
     Const(DefId),
     Alias(rustc_middle::ty::TraitRef<'tcx>),
     Lambda(Lambda),
@@ -324,7 +339,53 @@ pub struct FixpointCtxt<'genv, 'tcx, T: Eq + Hash> {
     def_id: MaybeExternId,
 }
 
-pub type FixQueryCache = QueryCache<FixpointResult<TagIdx>>;
+/// Cache of the queries sent to fixpoint. Results are cached across runs when caching is enabled.
+/// The cache can also record the queries answered in the current session, which is how the stats
+/// reported by `flux_driver::api` are collected.
+#[derive(Default)]
+pub struct FixQueryCache {
+    cache: QueryCache<FixpointResult<TagIdx>>,
+    records: Option<Vec<FixpointRecord>>,
+}
+
+/// A query answered by fixpoint, possibly from the cache
+pub struct FixpointRecord {
+    /// The item whose constraint was checked
+    pub def_id: LocalDefId,
+    /// Stats reported by fixpoint
+    pub stats: Stats,
+    /// Number of kvars in the constraint
+    pub kvars: usize,
+    /// Number of distinct tags in the constraint, i.e., of places that can be reported as errors
+    pub tags: usize,
+    /// Whether the result was found in the cache
+    pub cached: bool,
+    /// Time spent solving the constraint
+    pub time: Duration,
+}
+
+impl FixQueryCache {
+    pub fn load() -> Self {
+        FixQueryCache { cache: QueryCache::load(), records: None }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        self.cache.save()
+    }
+
+    /// Start recording the queries answered from now on
+    pub fn record(&mut self) {
+        self.records.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the queries recorded since the last call
+    pub fn take_records(&mut self) -> Vec<FixpointRecord> {
+        self.records
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
 
 impl<'genv, 'tcx, Tag> FixpointCtxt<'genv, 'tcx, Tag>
 where
@@ -359,6 +420,7 @@ where
         let def_span = self.def_span();
 
         let kvars = self.kcx.into_fixpoint();
+        let num_kvars = kvars.len();
 
         let constraint = self.ecx.assume_const_values(constraint);
 
@@ -405,7 +467,24 @@ where
 
         let task_key = self.genv.tcx().def_path_str(self.def_id);
 
-        match Self::run_task_with_cache(task, task_key, cache) {
+        let start = Instant::now();
+        let (result, cached) = Self::run_task_with_cache(task, task_key, cache);
+        if let Some(records) = &mut cache.records {
+            let stats = match &result {
+                FixpointResult::Safe(stats) | FixpointResult::Unsafe(stats, _) => stats.clone(),
+                FixpointResult::Crash(_) => Stats::default(),
+            };
+            records.push(FixpointRecord {
+                def_id: self.def_id.local_id(),
+                stats,
+                kvars: num_kvars,
+                tags: self.tags.len(),
+                cached,
+                time: start.elapsed(),
+            });
+        }
+
+        match result {
             FixpointResult::Safe(_) => Ok(vec![]),
             FixpointResult::Unsafe(_, errors) => {
                 Ok(errors
@@ -418,25 +497,26 @@ where
         }
     }
 
+    /// Runs the task returning the result and whether it was found in the cache
     fn run_task_with_cache(
         task: fixpoint::Task,
         key: String,
         cache: &mut FixQueryCache,
-    ) -> FixpointResult<TagIdx> {
+    ) -> (FixpointResult<TagIdx>, bool) {
         let hash = task.hash_with_default();
         if config::is_cache_enabled()
-            && let Some(result) = cache.lookup(&key, hash)
+            && let Some(result) = cache.cache.lookup(&key, hash)
         {
-            return result.clone();
+            return (result.clone(), true);
         }
         let result = task
             .run()
             .unwrap_or_else(|err| tracked_span_bug!("failed to run fixpoint {err:?}"));
 
         if config::is_cache_enabled() {
-            cache.insert(key, hash, result.clone());
+            cache.cache.insert(key, hash, result.clone());
         }
-        result
+        (result, false)
     }
 
     fn tag_idx(&mut self, tag: Tag) -> TagIdx
@@ -1012,10 +1092,10 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
             rty::ExprKind::GlobalFunc(_, SpecFuncKind::Thy(sym)) => {
                 fixpoint::Expr::Var(fixpoint::Var::Itf(*sym))
             }
-            rty::ExprKind::GlobalFunc(sym, SpecFuncKind::Uif) => {
-                fixpoint::Expr::Var(self.register_uif(*sym, scx).into())
+            rty::ExprKind::GlobalFunc(_, SpecFuncKind::Uif(def_id)) => {
+                fixpoint::Expr::Var(self.register_uif(*def_id, scx).into())
             }
-            rty::ExprKind::GlobalFunc(sym, SpecFuncKind::Def) => {
+            rty::ExprKind::GlobalFunc(sym, SpecFuncKind::Def(_)) => {
                 span_bug!(self.def_span, "unexpected global function `{sym}`. Function must be normalized away at this point")
             }
             rty::ExprKind::Hole(..)
@@ -1243,16 +1323,20 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
         }
     }
 
-    fn register_uif(&mut self, name: Symbol, scx: &mut SortEncodingCtxt) -> fixpoint::GlobalVar {
-        let key = Key::Uif(name);
+    fn register_uif(
+        &mut self,
+        def_id: FluxDefId,
+        scx: &mut SortEncodingCtxt,
+    ) -> fixpoint::GlobalVar {
+        let key = Key::Uif(def_id);
         self.const_map
             .entry(key)
             .or_insert_with(|| {
                 let sort = self
                     .genv
-                    .func_decl(name)
+                    .func_decl(def_id)
                     .map(|decl| {
-                        debug_assert_eq!(decl.kind, fhir::SpecFuncKind::Uif);
+                        debug_assert_eq!(decl.kind, fhir::SpecFuncKind::Uif(def_id));
                         scx.func_sort_to_fixpoint(&decl.sort)
                     })
                     .unwrap_or_else(|err| {
@@ -1263,7 +1347,7 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
                     name: self.global_var_gen.fresh(),
                     sort,
                     val: None,
-                    comment: format!("uif: {name}"),
+                    comment: format!("uif: {}", flux_def_path(self.genv, def_id)),
                 }
             })
             .name
@@ -1385,12 +1469,32 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
                 .map(|(name, var)| (name.into(), scx.sort_to_fixpoint(var.expect_sort())))
                 .collect();
 
-        let name = qualifier.name.to_string();
+        // Qualifiers imported from other crates are prefixed with the name of the crate to avoid
+        // clashes with local qualifiers of the same name.
+        let name = if qualifier.def_id.is_local() {
+            qualifier.def_id.name.to_string()
+        } else {
+            format!(
+                "{}.{}",
+                self.genv.tcx().crate_name(qualifier.def_id.krate),
+                qualifier.def_id.name
+            )
+        };
 
         Ok(fixpoint::Qualifier { name, args, body })
     }
 }
 
+/// The path of a flux item as written in the source of a dependent crate, i.e., `krate::name` for
+/// extern items or just `name` for local ones.
+fn flux_def_path(genv: GlobalEnv, def_id: FluxDefId) -> String {
+    if def_id.is_local() {
+        def_id.name.to_string()
+    } else {
+        format!("{}::{}", genv.tcx().crate_name(def_id.krate), def_id.name)
+    }
+}
+
 fn mk_implies(assumption: fixpoint::Pred, cstr: fixpoint::Constraint) -> fixpoint::Constraint {
     fixpoint::Constraint::ForAll(
         fixpoint::Bind {
@@ -25,7 +25,7 @@ use rustc_span::Span;
 
 use crate::{
     fixpoint_encoding::{KVarEncoding, KVarGen},
-    refine_tree::{RefineCtxt, RefineTree, Scope, Snapshot},
+    refine_tree::{Origin, RefineCtxt, RefineTree, Scope, Snapshot},
 };
 
 pub type InferResult<T = ()> = std::result::Result<T, InferErr>;
@@ -164,6 +164,15 @@ impl<'infcx, 'genv, 'tcx> InferCtxt<'infcx, 'genv, 'tcx> {
         InferCtxtAt { infcx: self, span }
     }
 
+    /// Records that the bindings and assumptions added to the refinement tree by `f` come from
+    /// `origin`. This is used to explain errors.
+    pub fn with_origin<R>(&mut self, origin: Origin, f: impl FnOnce(&mut Self) -> R) -> R {
+        let prev = self.rcx.replace_origin(Some(origin));
+        let r = f(self);
+        self.rcx.replace_origin(prev);
+        r
+    }
+
     pub fn instantiate_refine_args(&mut self, callee_def_id: DefId) -> InferResult<List<Expr>> {
         Ok(RefineArgs::for_item(self.genv, callee_def_id, |param, _| {
             self.fresh_infer_var(&param.sort, param.mode)
@@ -680,7 +689,7 @@ impl Sub {
             | (BaseTy::Str, BaseTy::Str)
             | (BaseTy::Char, BaseTy::Char)
             | (BaseTy::RawPtr(_, _), BaseTy::RawPtr(_, _)) => Ok(()),
-            (BaseTy::Dynamic(preds_a, _), BaseTy::Dynamic(preds_b, _)) => {
+            (BaseTy::Dynamic(preds_a, _, _), BaseTy::Dynamic(preds_b, _, _)) => {
                 tracked_span_assert_eq!(preds_a.erase_regions(), preds_b.erase_regions());
                 Ok(())
             }
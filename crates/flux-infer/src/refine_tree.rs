@@ -4,8 +4,12 @@ use std::{
     rc::{Rc, Weak},
 };
 
-use flux_common::{index::IndexVec, iter::IterExt};
-use flux_macros::DebugAsJson;
+use flux_common::{
+    bug,
+    index::IndexVec,
+    iter::IterExt,
+    trace::{RcxBind, RefineCtxtTrace},
+};
 use flux_middle::{
     global_env::GlobalEnv,
     pretty::{PrettyCx, PrettyNested},
@@ -14,13 +18,13 @@ use flux_middle::{
         canonicalize::{Hoister, HoisterDelegate},
         evars::EVarSol,
         fold::{TypeFoldable, TypeSuperVisitable, TypeVisitable, TypeVisitor},
-        BaseTy, EarlyBinder, EarlyReftParam, Expr, GenericArgs, Name, Sort, SpecFuncDefns, Ty,
-        TyCtor, TyKind, Var,
+        BaseTy, EarlyBinder, EarlyReftParam, Expr, ExprKind, GenericArgs, Name, Sort,
+        SpecFuncDefns, Ty, TyCtor, TyKind, Var,
     },
 };
 use itertools::Itertools;
 use rustc_hir::def_id::DefId;
-use serde::Serialize;
+use rustc_span::{Span, Symbol};
 
 use crate::{
     fixpoint_encoding::{fixpoint, FixpointCtxt},
@@ -69,6 +73,39 @@ pub struct RefineTree {
 pub struct RefineCtxt<'a> {
     tree: &'a mut RefineTree,
     ptr: NodePtr,
+    /// Origin recorded in the nodes pushed by this context. See [`Origin`]
+    origin: Option<Origin>,
+}
+
+/// Where the bindings and assumptions in a [`RefineTree`] come from. It's recorded in the nodes of
+/// the tree to explain refinement type errors to the user.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Origin {
+    /// The inputs and preconditions of the function defined at the span
+    FnInputs(Span),
+    /// The condition of the branch taken at the span, e.g., the arm of a `match` or the branch of
+    /// an `if`
+    Guard(Span),
+    /// The output and postconditions of the call at the span to the function with the given
+    /// [`DefId`], which is `None` for calls through function pointers.
+    Call(Option<DefId>, Span),
+}
+
+/// A step in the path from the root of a [`RefineTree`] to a head
+#[derive(Clone)]
+pub enum PathStep {
+    /// A variable with the name it stands for in the source, if any. See [`RefineCtxt::name_var`]
+    Bind(Name, Sort, Option<Symbol>),
+    Assume(Expr),
+}
+
+/// The path from the root of a [`RefineTree`] to a head, i.e., the variables and assumptions in scope
+/// when checking the predicate in the head.
+pub struct PathCondition {
+    /// Const and refinement generics of the function
+    pub params: Vec<(Var, Sort)>,
+    pub steps: Vec<(PathStep, Option<Origin>)>,
+    pub head: Expr,
 }
 
 /// A snapshot of a [`RefineCtxt`] at a particular point during type-checking. Alternatively, a
@@ -147,6 +184,9 @@ struct Node {
     nbindings: usize,
     parent: Option<WeakNodePtr>,
     children: Vec<NodePtr>,
+    origin: Option<Origin>,
+    /// The name in the source of the variable bound by a [`NodeKind::ForAll`] node, if any
+    symbol: Option<Symbol>,
 }
 
 #[derive(Clone)]
@@ -157,13 +197,15 @@ impl NodePtr {
         WeakNodePtr(Rc::downgrade(&this.0))
     }
 
-    fn push_node(&mut self, kind: NodeKind) -> NodePtr {
+    fn push_node(&mut self, kind: NodeKind, origin: Option<Origin>) -> NodePtr {
         debug_assert!(!matches!(self.borrow().kind, NodeKind::Head(..)));
         let node = Node {
             kind,
             nbindings: self.next_name_idx(),
             parent: Some(NodePtr::downgrade(self)),
             children: vec![],
+            origin,
+            symbol: None,
         };
         let node = NodePtr(Rc::new(RefCell::new(node)));
         self.borrow_mut().children.push(NodePtr::clone(&node));
@@ -228,8 +270,14 @@ impl RefineTree {
         } else {
             params
         };
-        let root =
-            Node { kind: NodeKind::Root(params), nbindings: 0, parent: None, children: vec![] };
+        let root = Node {
+            kind: NodeKind::Root(params),
+            nbindings: 0,
+            parent: None,
+            children: vec![],
+            origin: None,
+            symbol: None,
+        };
         let root = NodePtr(Rc::new(RefCell::new(root)));
         Ok(RefineTree { root })
     }
@@ -238,7 +286,7 @@ impl RefineTree {
         self.root.borrow_mut().simplify(defns);
     }
 
-    pub fn into_fixpoint(self, cx: &mut FixpointCtxt<Tag>) -> QueryResult<fixpoint::Constraint> {
+    pub fn to_fixpoint(&self, cx: &mut FixpointCtxt<Tag>) -> QueryResult<fixpoint::Constraint> {
         Ok(self
             .root
             .borrow()
@@ -247,7 +295,60 @@ impl RefineTree {
     }
 
     pub fn refine_ctxt_at_root(&mut self) -> RefineCtxt {
-        RefineCtxt { ptr: NodePtr(Rc::clone(&self.root)), tree: self }
+        RefineCtxt { ptr: NodePtr(Rc::clone(&self.root)), tree: self, origin: None }
+    }
+
+    /// Returns the path condition of every head checked with `tag`, in the order they appear in
+    /// the tree. The span of the condition in the tag is ignored because it's only set when
+    /// encoding heads into fixpoint.
+    pub fn path_conditions(&self, tag: Tag) -> Vec<PathCondition> {
+        fn go(
+            ptr: &NodePtr,
+            tag: Tag,
+            params: &[(Var, Sort)],
+            steps: &mut Vec<(PathStep, Option<Origin>)>,
+            paths: &mut Vec<PathCondition>,
+        ) {
+            let node = ptr.borrow();
+            let step = match &node.kind {
+                NodeKind::ForAll(name, sort) => {
+                    Some(PathStep::Bind(*name, sort.clone(), node.symbol))
+                }
+                NodeKind::Assumption(pred) => Some(PathStep::Assume(pred.clone())),
+                NodeKind::Head(pred, head_tag) => {
+                    if head_tag.reason == tag.reason && head_tag.src_span == tag.src_span {
+                        let steps = steps
+                            .iter()
+                            .map(|(step, origin)| (step.clone(), *origin))
+                            .collect();
+                        paths.push(PathCondition {
+                            params: params.to_vec(),
+                            steps,
+                            head: pred.clone(),
+                        });
+                    }
+                    None
+                }
+                NodeKind::Root(_) | NodeKind::Trace(_) | NodeKind::True => None,
+            };
+            let pushed = step.is_some();
+            if let Some(step) = step {
+                steps.push((step, node.origin));
+            }
+            for child in &node.children {
+                go(child, tag, params, steps, paths);
+            }
+            if pushed {
+                steps.pop();
+            }
+        }
+        let root = self.root.borrow();
+        let NodeKind::Root(params) = &root.kind else { bug!("unexpected root node") };
+        let mut paths = vec![];
+        for child in &root.children {
+            go(child, tag, params, &mut vec![], &mut paths);
+        }
+        paths
     }
 }
 
@@ -261,7 +362,7 @@ impl<'rcx> RefineCtxt<'rcx> {
     }
 
     pub(crate) fn change_root(&mut self, snapshot: &Snapshot) -> Option<RefineCtxt> {
-        Some(RefineCtxt { ptr: snapshot.ptr.upgrade()?, tree: self.tree })
+        Some(RefineCtxt { ptr: snapshot.ptr.upgrade()?, tree: self.tree, origin: self.origin })
     }
 
     pub fn snapshot(&self) -> Snapshot {
@@ -270,7 +371,12 @@ impl<'rcx> RefineCtxt<'rcx> {
 
     #[must_use]
     pub fn branch(&mut self) -> RefineCtxt {
-        RefineCtxt { tree: self.tree, ptr: NodePtr::clone(&self.ptr) }
+        RefineCtxt { tree: self.tree, ptr: NodePtr::clone(&self.ptr), origin: self.origin }
+    }
+
+    /// Sets the origin recorded in the nodes pushed from now on, returning the previous one
+    pub(crate) fn replace_origin(&mut self, origin: Option<Origin>) -> Option<Origin> {
+        std::mem::replace(&mut self.origin, origin)
     }
 
     pub fn scope(&self) -> Scope {
@@ -279,14 +385,16 @@ impl<'rcx> RefineCtxt<'rcx> {
 
     #[expect(dead_code, reason = "used for debugging")]
     pub(crate) fn push_trace(&mut self, trace: TypeTrace) {
-        self.ptr = self.ptr.push_node(NodeKind::Trace(trace));
+        self.ptr = self.ptr.push_node(NodeKind::Trace(trace), self.origin);
     }
 
     /// Defines a fresh refinement variable with the given `sort`. It returns the freshly generated
     /// name for the variable.
     pub fn define_var(&mut self, sort: &Sort) -> Name {
         let fresh = Name::from_usize(self.ptr.next_name_idx());
-        self.ptr = self.ptr.push_node(NodeKind::ForAll(fresh, sort.clone()));
+        self.ptr = self
+            .ptr
+            .push_node(NodeKind::ForAll(fresh, sort.clone()), self.origin);
         fresh
     }
 
@@ -305,24 +413,38 @@ impl<'rcx> RefineCtxt<'rcx> {
         Expr::fold_sort(sort, |sort| Expr::fvar(self.define_var(sort)))
     }
 
+    /// Records that the variable `name` stands for `symbol` in the source, e.g., the index of the
+    /// type of an argument of the function. The name is used to explain errors.
+    pub fn name_var(&mut self, name: Name, symbol: Symbol) {
+        for ptr in ParentsIter::new(NodePtr::clone(&self.ptr)) {
+            let mut node = ptr.borrow_mut();
+            if let NodeKind::ForAll(n, _) = node.kind
+                && n == name
+            {
+                node.symbol = Some(symbol);
+                return;
+            }
+        }
+    }
+
     pub fn assume_pred(&mut self, pred: impl Into<Expr>) {
         let pred = pred.into();
         if !pred.is_trivially_true() {
-            self.ptr = self.ptr.push_node(NodeKind::Assumption(pred));
+            self.ptr = self.ptr.push_node(NodeKind::Assumption(pred), self.origin);
         }
     }
 
     pub fn check_pred(&mut self, pred: impl Into<Expr>, tag: Tag) {
         let pred = pred.into();
         if !pred.is_trivially_true() {
-            self.ptr.push_node(NodeKind::Head(pred, tag));
+            self.ptr.push_node(NodeKind::Head(pred, tag), self.origin);
         }
     }
 
     pub(crate) fn check_impl(&mut self, pred1: impl Into<Expr>, pred2: impl Into<Expr>, tag: Tag) {
         self.ptr
-            .push_node(NodeKind::Assumption(pred1.into()))
-            .push_node(NodeKind::Head(pred2.into(), tag));
+            .push_node(NodeKind::Assumption(pred1.into()), self.origin)
+            .push_node(NodeKind::Head(pred2.into(), tag), self.origin);
     }
 
     pub fn unpack(&mut self, ty: &Ty) -> Ty {
@@ -773,23 +895,13 @@ mod pretty {
     );
 }
 
-/// A very explicit representation of [`RefineCtxt`] for debugging/tracing/serialization ONLY.
-#[derive(Serialize, DebugAsJson)]
-pub struct RefineCtxtTrace {
-    bindings: Vec<RcxBind>,
-    exprs: Vec<String>,
-}
-
-#[derive(Serialize)]
-struct RcxBind {
-    name: String,
-    sort: String,
-}
-impl RefineCtxtTrace {
-    pub fn new(genv: GlobalEnv, rcx: &RefineCtxt) -> Self {
-        let parents = ParentsIter::new(NodePtr::clone(&rcx.ptr)).collect_vec();
+impl RefineCtxt<'_> {
+    /// Returns a very explicit representation of the context for debugging/tracing/serialization
+    pub fn trace(&self, genv: GlobalEnv) -> RefineCtxtTrace {
+        let parents = ParentsIter::new(NodePtr::clone(&self.ptr)).collect_vec();
         let mut bindings = vec![];
         let mut exprs = vec![];
+        let mut kvars = vec![];
         let cx = PrettyCx::default_with_genv(genv);
 
         parents.into_iter().rev().for_each(|ptr| {
@@ -800,8 +912,14 @@ impl RefineCtxtTrace {
                     bindings.push(bind);
                 }
                 NodeKind::Assumption(e) if !e.simplify().is_trivially_true() => {
-                    let e = e.nested_string(&cx);
-                    exprs.push(e);
+                    for e in e.flatten_conjs() {
+                        let nested = e.fmt_nested(&cx).unwrap();
+                        if let ExprKind::KVar(_) = e.kind() {
+                            kvars.push(nested);
+                        } else {
+                            exprs.push(nested);
+                        }
+                    }
                 }
                 NodeKind::Root(binds) => {
                     for (name, sort) in binds {
@@ -812,6 +930,6 @@ impl RefineCtxtTrace {
                 _ => (),
             }
         });
-        Self { bindings, exprs }
+        RefineCtxtTrace { bindings, exprs, kvars }
     }
 }
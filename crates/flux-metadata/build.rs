@@ -0,0 +1,34 @@
+use std::{env, path::Path, process::Command};
+
+/// Sets `FLUX_VERSION` to the version of the package followed by the git commit flux is being
+/// built from, if available. It's stored in the header of `.fluxmeta` files to reject metadata
+/// produced by a different build of flux.
+fn main() {
+    let version = env::var("CARGO_PKG_VERSION").unwrap();
+    let version = match git_commit() {
+        Some(commit) => format!("{version} ({commit})"),
+        None => version,
+    };
+    println!("cargo:rustc-env=FLUX_VERSION={version}");
+
+    // Rerun when the commit changes. Cargo reruns the script on every build if a path doesn't
+    // exist, so we only emit the directives when building from a git checkout.
+    let git_dir = Path::new("../../.git");
+    for path in [git_dir.join("HEAD"), git_dir.join("refs/heads")] {
+        if path.exists() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+}
+
+fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8(output.stdout).ok()?;
+    Some(commit.trim().to_string())
+}
@@ -1,3 +1,14 @@
 metadata_decode_file_error = "error when decoding flux metadata file {$path}: {$err}"
 
+metadata_missing_metadata = no flux metadata found for crate `{$krate}`
+
+metadata_invalid_metadata_file = {$path} is not a flux metadata file
+
+metadata_incompatible_metadata =
+    the flux metadata of crate `{$krate}` is incompatible with this version of flux
+    .note = the metadata was produced with {$found}, but this is {$expected}
+    .help = recompile `{$krate}` with this version of flux
+
 metadata_duplicate_spec = "duplicate spec for {$def_name}"
+
+metadata_missing_prelude = "the flux prelude is enabled but it is not installed"
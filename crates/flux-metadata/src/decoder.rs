@@ -21,7 +21,10 @@ use rustc_span::{
 };
 use rustc_type_ir::TyDecoder;
 
-use crate::{CrateMetadata, METADATA_HEADER, SYMBOL_OFFSET, SYMBOL_PREINTERNED, SYMBOL_STR};
+use crate::{
+    rustc_commit_hash, CrateMetadata, FLUX_VERSION, METADATA_MAGIC, METADATA_VERSION,
+    SYMBOL_OFFSET, SYMBOL_PREINTERNED, SYMBOL_STR,
+};
 
 struct DecodeContext<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -31,6 +34,7 @@ struct DecodeContext<'a, 'tcx> {
 pub(super) fn decode_crate_metadata(
     tcx: TyCtxt,
     sess: &FluxSession,
+    crate_num: CrateNum,
     path: &Path,
 ) -> Option<CrateMetadata> {
     let mut file = match fs::File::open(path) {
@@ -42,15 +46,63 @@ pub(super) fn decode_crate_metadata(
     file.read_to_end(&mut buf)
         .unwrap_or_else(|err| sess.emit_fatal(errors::DecodeFileError::new(path, err)));
 
-    if !buf.starts_with(METADATA_HEADER) {
-        bug!("incompatible metadata version");
-    }
+    let opaque = decode_header(&buf, FLUX_VERSION, rustc_commit_hash()).unwrap_or_else(|err| {
+        match err {
+            HeaderError::InvalidMagic => sess.emit_fatal(errors::InvalidMetadataFile { path }),
+            HeaderError::Incompatible { found, expected } => {
+                sess.emit_fatal(errors::IncompatibleMetadata {
+                    krate: tcx.crate_name(crate_num),
+                    found,
+                    expected,
+                })
+            }
+        }
+    });
 
-    let mut decoder =
-        DecodeContext { tcx, opaque: MemDecoder::new(&buf, METADATA_HEADER.len()).unwrap() };
+    let mut decoder = DecodeContext { tcx, opaque };
     Some(CrateMetadata::decode(&mut decoder))
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum HeaderError {
+    /// The file doesn't start with [`METADATA_MAGIC`]
+    InvalidMagic,
+    /// The file was produced with a different version of the header, flux or rustc
+    Incompatible { found: String, expected: String },
+}
+
+/// Checks the header written by [`crate::encoder::encode_header`] and returns a decoder positioned
+/// right after it. Files are only accepted if they were produced with the current
+/// [`METADATA_VERSION`] by flux `flux_version` and the rustc with commit `rustc_commit_hash`.
+pub(crate) fn decode_header<'a>(
+    buf: &'a [u8],
+    flux_version: &str,
+    rustc_commit_hash: &str,
+) -> Result<MemDecoder<'a>, HeaderError> {
+    if !buf.starts_with(METADATA_MAGIC) {
+        return Err(HeaderError::InvalidMagic);
+    }
+    let mut opaque = MemDecoder::new(buf, METADATA_MAGIC.len()).unwrap();
+
+    // The layout of the rest of the header may change with the version so we check it first
+    let version = opaque.read_u8();
+    if version != METADATA_VERSION {
+        return Err(HeaderError::Incompatible {
+            found: format!("metadata version {version}"),
+            expected: format!("metadata version {METADATA_VERSION}"),
+        });
+    }
+    let found_flux = opaque.read_str().to_string();
+    let found_rustc = opaque.read_str();
+    if found_flux != flux_version || found_rustc != rustc_commit_hash {
+        return Err(HeaderError::Incompatible {
+            found: format!("flux {found_flux} and rustc {found_rustc}"),
+            expected: format!("flux {flux_version} and rustc {rustc_commit_hash}"),
+        });
+    }
+    Ok(opaque)
+}
+
 implement_ty_decoder!(DecodeContext<'a, 'tcx>);
 
 impl SpanDecoder for DecodeContext<'_, '_> {
@@ -167,19 +219,106 @@ impl<'tcx> TyDecoder for DecodeContext<'_, 'tcx> {
 mod errors {
     use std::{io, path::Path};
 
-    use flux_errors::E0999;
+    use flux_errors::E9061;
     use flux_macros::Diagnostic;
+    use rustc_span::Symbol;
 
     #[derive(Diagnostic)]
-    #[diag(metadata_decode_file_error, code = E0999)]
+    #[diag(metadata_decode_file_error, code = E9061)]
     pub(super) struct DecodeFileError<'a> {
         path: &'a Path,
         err: io::Error,
     }
 
+    #[derive(Diagnostic)]
+    #[diag(metadata_invalid_metadata_file, code = E9061)]
+    pub(super) struct InvalidMetadataFile<'a> {
+        pub(super) path: &'a Path,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(metadata_incompatible_metadata, code = E9061)]
+    #[note]
+    #[help]
+    pub(super) struct IncompatibleMetadata {
+        pub(super) krate: Symbol,
+        pub(super) found: String,
+        pub(super) expected: String,
+    }
+
     impl<'a> DecodeFileError<'a> {
         pub(super) fn new(path: &'a Path, err: io::Error) -> Self {
             Self { path, err }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process};
+
+    use rustc_serialize::opaque::FileEncoder;
+
+    use super::*;
+    use crate::encoder::encode_header;
+
+    const FLUX: &str = "0.1.0 (5f3a4c2b9d1e)";
+    const RUSTC: &str = "b3f75cc87";
+
+    fn header(name: &str, version: u8, flux_version: &str, rustc_commit_hash: &str) -> Vec<u8> {
+        let path = std::env::temp_dir().join(format!("flux-header-{}-{name}", process::id()));
+        let mut encoder = FileEncoder::new(&path).unwrap();
+        encode_header(&mut encoder, version, flux_version, rustc_commit_hash);
+        encoder.finish().unwrap();
+        let buf = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        buf
+    }
+
+    #[test]
+    fn accepts_same_version() {
+        let buf = header("same", METADATA_VERSION, FLUX, RUSTC);
+        assert!(decode_header(&buf, FLUX, RUSTC).is_ok());
+    }
+
+    #[test]
+    fn rejects_different_metadata_version() {
+        let buf = header("version", METADATA_VERSION + 1, FLUX, RUSTC);
+        assert_eq!(
+            decode_header(&buf, FLUX, RUSTC).err(),
+            Some(HeaderError::Incompatible {
+                found: format!("metadata version {}", METADATA_VERSION + 1),
+                expected: format!("metadata version {METADATA_VERSION}"),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_different_flux() {
+        let buf = header("flux", METADATA_VERSION, "0.1.0 (0a1b2c3d4e5f)", RUSTC);
+        assert_eq!(
+            decode_header(&buf, FLUX, RUSTC).err(),
+            Some(HeaderError::Incompatible {
+                found: format!("flux 0.1.0 (0a1b2c3d4e5f) and rustc {RUSTC}"),
+                expected: format!("flux {FLUX} and rustc {RUSTC}"),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_different_rustc() {
+        let buf = header("rustc", METADATA_VERSION, FLUX, RUSTC);
+        assert_eq!(
+            decode_header(&buf, FLUX, "0123456789").err(),
+            Some(HeaderError::Incompatible {
+                found: format!("flux {FLUX} and rustc {RUSTC}"),
+                expected: format!("flux {FLUX} and rustc 0123456789"),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_magic() {
+        assert_eq!(decode_header(b"rmeta", FLUX, RUSTC).err(), Some(HeaderError::InvalidMagic));
+    }
+}
@@ -0,0 +1,156 @@
+//! Pretty printing of the refined signatures stored in the metadata of a crate. Used by `flux-meta
+//! dump` to review the specs a dependency exports.
+use std::{hash::Hash, io};
+
+use flux_errors::FluxSession;
+use flux_middle::{queries::QueryErr, rty};
+use rustc_data_structures::unord::UnordMap;
+use rustc_hir::def::DefKind;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::DefId;
+
+use crate::{
+    decoder::decode_crate_metadata, flux_metadata_extern_location, rustc_commit_hash, Tables,
+    FLUX_VERSION,
+};
+
+/// Decodes the metadata of the dependency named `krate` and writes its contents to `w`.
+pub fn dump_crate_metadata(
+    tcx: TyCtxt,
+    sess: &FluxSession,
+    krate: &str,
+    w: &mut impl io::Write,
+) -> io::Result<()> {
+    let meta = tcx
+        .crates(())
+        .iter()
+        .find(|crate_num| tcx.crate_name(**crate_num).as_str() == krate)
+        .and_then(|crate_num| {
+            let path = flux_metadata_extern_location(tcx, *crate_num)?;
+            Some((*crate_num, decode_crate_metadata(tcx, sess, *crate_num, &path)?))
+        });
+    let Some((crate_num, meta)) = meta else {
+        sess.emit_fatal(errors::MissingMetadata { krate });
+    };
+
+    writeln!(w, "// flux metadata for crate `{krate}`")?;
+    writeln!(w, "// produced with flux {FLUX_VERSION} and rustc {}", rustc_commit_hash())?;
+    if meta.prelude {
+        writeln!(w, "// checked with the flux prelude")?;
+    }
+
+    let flux_items = &meta.flux_items;
+    let sort_decls = flux_items.sort_decls.to_sorted_stable_ord();
+    if !sort_decls.is_empty() {
+        writeln!(w)?;
+        for name in sort_decls {
+            writeln!(w, "opaque sort {name}")?;
+        }
+    }
+
+    let func_decls = flux_items.func_decls.to_sorted_stable_ord();
+    if !func_decls.is_empty() {
+        writeln!(w)?;
+        for (name, decl) in func_decls {
+            let defn = flux_items
+                .func_defns
+                .iter()
+                .find(|defn| defn.def_id.name == *name);
+            match defn {
+                Some(defn) => writeln!(w, "spec fn {name}: {:?} = {:?}", decl.sort, defn.expr)?,
+                None => writeln!(w, "spec fn {name}: {:?}", decl.sort)?,
+            }
+        }
+    }
+
+    if !flux_items.qualifiers.is_empty() {
+        writeln!(w)?;
+        for qualifier in &flux_items.qualifiers {
+            let kw = if qualifier.global { "qualifier" } else { "local qualifier" };
+            writeln!(w, "{kw} {}: {:?}", qualifier.def_id.name, qualifier.body)?;
+        }
+    }
+
+    dump_tables(tcx, w, &meta.local_tables, |index| DefId { krate: crate_num, index })?;
+    dump_tables(tcx, w, &meta.extern_tables, |def_id| def_id)?;
+    Ok(())
+}
+
+/// Writes the signatures of functions and the definitions of structs and enums in `tables`. Entries
+/// are sorted by path so the output is deterministic.
+fn dump_tables<K: Eq + Hash + Copy>(
+    tcx: TyCtxt,
+    w: &mut impl io::Write,
+    tables: &Tables<K>,
+    to_def_id: impl Fn(K) -> DefId,
+) -> io::Result<()> {
+    let fn_sigs = sorted_by_path(tcx, &tables.fn_sig, &to_def_id);
+    if !fn_sigs.is_empty() {
+        writeln!(w)?;
+        for (path, key) in fn_sigs {
+            match &tables.fn_sig[&key] {
+                Ok(fn_sig) => writeln!(w, "fn {path}: {:?}", fn_sig.skip_binder_ref())?,
+                Err(err) => writeln!(w, "fn {path}: {}", describe_err(err))?,
+            }
+        }
+    }
+
+    for (path, key) in sorted_by_path(tcx, &tables.adt_sort_def, &to_def_id) {
+        writeln!(w)?;
+        let sort_def = tables.adt_sort_def[&key].as_ref();
+        let variants = tables.variants.get(&key);
+        let kind = if tcx.def_kind(to_def_id(key)) == DefKind::Enum { "enum" } else { "struct" };
+        match sort_def {
+            Ok(sort_def) => {
+                let fields = sort_def
+                    .sort_by_field_name(&sort_def.identity_args())
+                    .into_iter()
+                    .map(|(name, sort)| format!("{name}: {sort:?}"))
+                    .collect::<Vec<_>>();
+                writeln!(w, "{kind} {path} refined_by {{ {} }}", fields.join(", "))?;
+            }
+            Err(err) => writeln!(w, "{kind} {path}: {}", describe_err(err))?,
+        }
+        match variants {
+            Some(Ok(rty::Opaqueness::Transparent(variants))) => {
+                for variant in variants.skip_binder_ref() {
+                    writeln!(w, "    {variant:?}")?;
+                }
+            }
+            Some(Ok(rty::Opaqueness::Opaque)) => writeln!(w, "    opaque")?,
+            Some(Err(err)) => writeln!(w, "    {}", describe_err(err))?,
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn sorted_by_path<K: Eq + Hash + Copy, V>(
+    tcx: TyCtxt,
+    table: &UnordMap<K, V>,
+    to_def_id: impl Fn(K) -> DefId,
+) -> Vec<(String, K)> {
+    table
+        .keys()
+        .map(|key| (tcx.def_path_str(to_def_id(*key)), *key))
+        .into_sorted_stable_ord_by_key(|(path, _)| path)
+}
+
+fn describe_err(err: &QueryErr) -> &'static str {
+    match err {
+        QueryErr::Ignored { .. } => "ignored",
+        QueryErr::Unsupported { .. } => "unsupported",
+        _ => "error",
+    }
+}
+
+mod errors {
+    use flux_errors::E9061;
+    use flux_macros::Diagnostic;
+
+    #[derive(Diagnostic)]
+    #[diag(metadata_missing_metadata, code = E9061)]
+    pub(super) struct MissingMetadata<'a> {
+        pub(super) krate: &'a str,
+    }
+}
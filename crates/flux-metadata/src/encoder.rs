@@ -17,7 +17,10 @@ use rustc_span::{
 };
 use rustc_type_ir::TyEncoder;
 
-use crate::{CrateMetadata, METADATA_HEADER, SYMBOL_OFFSET, SYMBOL_PREINTERNED, SYMBOL_STR};
+use crate::{
+    rustc_commit_hash, CrateMetadata, FLUX_VERSION, METADATA_MAGIC, METADATA_VERSION,
+    SYMBOL_OFFSET, SYMBOL_PREINTERNED, SYMBOL_STR,
+};
 
 struct EncodeContext<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -37,7 +40,7 @@ pub fn encode_metadata(genv: GlobalEnv, path: &std::path::Path) {
             .emit_fatal(FailCreateFileEncoder { err })
     });
 
-    encoder.emit_raw_bytes(METADATA_HEADER);
+    encode_header(&mut encoder, METADATA_VERSION, FLUX_VERSION, rustc_commit_hash());
 
     let crate_root = CrateMetadata::new(genv);
 
@@ -57,6 +60,19 @@ pub fn encode_metadata(genv: GlobalEnv, path: &std::path::Path) {
     ecx.opaque.finish().unwrap();
 }
 
+/// Writes the header checked by [`crate::decoder::decode_header`]
+pub(crate) fn encode_header(
+    encoder: &mut opaque::FileEncoder,
+    version: u8,
+    flux_version: &str,
+    rustc_commit_hash: &str,
+) {
+    encoder.emit_raw_bytes(METADATA_MAGIC);
+    encoder.emit_u8(version);
+    encoder.emit_str(flux_version);
+    encoder.emit_str(rustc_commit_hash);
+}
+
 impl SpanEncoder for EncodeContext<'_, '_> {
     fn encode_crate_num(&mut self, crate_num: CrateNum) {
         if crate_num != LOCAL_CRATE && self.is_proc_macro {
@@ -6,6 +6,7 @@ extern crate rustc_data_structures;
 extern crate rustc_errors;
 
 extern crate rustc_hir;
+extern crate rustc_interface;
 extern crate rustc_macros;
 extern crate rustc_metadata;
 extern crate rustc_middle;
@@ -14,7 +15,12 @@ extern crate rustc_session;
 extern crate rustc_span;
 extern crate rustc_type_ir;
 
+// Link the compiler for unit tests, which are not run through the flux driver
+#[cfg(test)]
+extern crate rustc_driver as _;
+
 mod decoder;
+mod dump;
 mod encoder;
 
 use std::{hash::Hash, path::PathBuf};
@@ -24,10 +30,10 @@ use derive_where::derive_where;
 use flux_errors::FluxSession;
 use flux_macros::fluent_messages;
 use flux_middle::{
-    cstore::{CrateStore, OptResult},
+    cstore::{CrateFluxItems, CrateStore, OptResult},
     global_env::GlobalEnv,
     queries::QueryResult,
-    rty,
+    rty, FluxDefId,
 };
 use rustc_data_structures::unord::{ExtendUnord, UnordMap};
 use rustc_hir::{def::DefKind, def_id::LocalDefId};
@@ -39,23 +45,54 @@ use rustc_span::{
     Symbol,
 };
 
-pub use crate::encoder::encode_metadata;
+pub use crate::{dump::dump_crate_metadata, encoder::encode_metadata};
 
 fluent_messages! { "../locales/en-US.ftl" }
 
-const METADATA_VERSION: u8 = 0;
-const METADATA_HEADER: &[u8] = &[b'f', b'l', b'u', b'x', 0, 0, 0, METADATA_VERSION];
+/// Magic bytes at the start of every `.fluxmeta` file. They are followed by [`METADATA_VERSION`],
+/// the [`FLUX_VERSION`] and the commit hash of the rustc the file was produced with (see
+/// [`rustc_commit_hash`]).
+const METADATA_MAGIC: &[u8] = &[b'f', b'l', b'u', b'x', 0, 0, 0];
+
+/// Version of the layout of the header of `.fluxmeta` files, checked before reading the rest of it
+const METADATA_VERSION: u8 = 5;
+
+/// Version of flux including the git commit it was built from (see `build.rs`). Metadata encodes
+/// refined types in a format that may change between any two commits, so we only accept files
+/// produced by the exact same build of flux.
+pub const FLUX_VERSION: &str = env!("FLUX_VERSION");
+
+/// The commit hash of the rustc flux is linked against. Metadata also stores types and spans
+/// encoded by rustc, whose format may change between any two commits of the compiler.
+pub fn rustc_commit_hash() -> &'static str {
+    // The version has the form `1.84.0-nightly (b3f75cc87 2024-11-02)`
+    let version = rustc_interface::util::rustc_version_str().unwrap_or("unknown");
+    version
+        .split_once('(')
+        .and_then(|(_, rest)| rest.split_whitespace().next())
+        .unwrap_or(version)
+}
+
+/// Name of the crate with the specs for the standard collections shipped in the flux sysroot
+const FLUX_PRELUDE: &str = "flux_prelude";
 
 #[derive(Default)]
 pub struct CStore {
     local_tables: UnordMap<CrateNum, Tables<DefIndex>>,
     extern_tables: Tables<DefId>,
+    flux_items: UnordMap<CrateNum, CrateFluxItems>,
+    prelude: bool,
 }
 
 #[derive(Default, TyEncodable, TyDecodable)]
 pub struct CrateMetadata {
     local_tables: Tables<DefIndex>,
     extern_tables: Tables<DefId>,
+    flux_items: CrateFluxItems,
+    /// Whether the crate was checked with the specs in the flux prelude. Signatures in a crate
+    /// checked with the prelude may mention the refinements it defines, so dependent crates must
+    /// load it as well.
+    prelude: bool,
 }
 
 /// Trait to deal with the fact that `assoc_refinmenents_of` and `assoc_refinements_def` use
@@ -119,17 +156,44 @@ pub struct Tables<K: Eq + Hash> {
 }
 
 impl CStore {
-    pub fn load(tcx: TyCtxt, sess: &FluxSession) -> Self {
-        let mut cstore = CStore::default();
+    /// Loads the metadata of all dependencies. The metadata of the flux prelude is only loaded if
+    /// `prelude` is true or if any dependency was checked with it.
+    pub fn load(tcx: TyCtxt, sess: &FluxSession, prelude: bool) -> Self {
+        let mut cstore = CStore { prelude, ..CStore::default() };
+        let mut flux_prelude = None;
         for crate_num in tcx.used_crates(()) {
             let Some(path) = flux_metadata_extern_location(tcx, *crate_num) else { continue };
-            let Some(meta) = decode_crate_metadata(tcx, sess, path.as_path()) else { continue };
-            cstore.local_tables.insert(*crate_num, meta.local_tables);
-            cstore.merge_extern_tables(tcx, sess, meta.extern_tables);
+            let Some(meta) = decode_crate_metadata(tcx, sess, *crate_num, path.as_path()) else {
+                continue;
+            };
+            if tcx.crate_name(*crate_num).as_str() == FLUX_PRELUDE {
+                flux_prelude = Some((*crate_num, meta));
+                continue;
+            }
+            cstore.prelude |= meta.prelude;
+            cstore.load_crate(tcx, sess, *crate_num, meta);
+        }
+        if cstore.prelude {
+            let Some((crate_num, meta)) = flux_prelude else {
+                sess.emit_fatal(errors::MissingPrelude);
+            };
+            cstore.load_crate(tcx, sess, crate_num, meta);
         }
         cstore
     }
 
+    fn load_crate(
+        &mut self,
+        tcx: TyCtxt,
+        sess: &FluxSession,
+        crate_num: CrateNum,
+        meta: CrateMetadata,
+    ) {
+        self.local_tables.insert(crate_num, meta.local_tables);
+        self.flux_items.insert(crate_num, meta.flux_items);
+        self.merge_extern_tables(tcx, sess, meta.extern_tables);
+    }
+
     fn merge_extern_tables(
         &mut self,
         tcx: TyCtxt,
@@ -243,6 +307,14 @@ impl CrateStore for CStore {
     ) -> OptResult<Option<rty::EarlyBinder<rty::FuncSort>>> {
         get!(self, sort_of_assoc_reft, key)
     }
+
+    fn prelude(&self) -> bool {
+        self.prelude
+    }
+
+    fn flux_items(&self, krate: CrateNum) -> Option<&CrateFluxItems> {
+        self.flux_items.get(&krate)
+    }
 }
 
 impl CrateMetadata {
@@ -258,8 +330,35 @@ impl CrateMetadata {
         let mut extern_tables = Tables::default();
         encode_def_ids(genv, genv.iter_extern_def_id(), &mut extern_tables, |def_id| def_id);
 
-        CrateMetadata { local_tables, extern_tables }
+        CrateMetadata {
+            local_tables,
+            extern_tables,
+            flux_items: encode_flux_items(genv),
+            prelude: genv.prelude_enabled(),
+        }
+    }
+}
+
+/// Collects the spec functions, qualifiers and opaque sorts defined in the local crate
+fn encode_flux_items(genv: GlobalEnv) -> CrateFluxItems {
+    let mut flux_items = CrateFluxItems::default();
+    for func in genv.map().spec_funcs() {
+        if let Ok(decl) = genv.func_decl(FluxDefId::local(func.name)) {
+            flux_items.func_decls.insert(func.name, decl);
+        }
+    }
+    if let Ok(defns) = genv.spec_func_defns() {
+        flux_items.func_defns = genv
+            .map()
+            .spec_funcs()
+            .filter_map(|func| defns.func_defn(&FluxDefId::local(func.name)).cloned())
+            .collect();
     }
+    if let Ok(qualifiers) = genv.qualifiers() {
+        flux_items.qualifiers = qualifiers.to_vec();
+    }
+    flux_items.sort_decls = genv.map().sort_decls().map(|decl| decl.name).collect();
+    flux_items
 }
 
 fn encode_def_ids<K: Eq + Hash + Copy>(
@@ -370,11 +469,15 @@ pub fn filename_for_metadata(tcx: TyCtxt) -> OutFileName {
 }
 
 fn flux_metadata_extern_location(tcx: TyCtxt, crate_num: CrateNum) -> Option<PathBuf> {
-    // Since we only save metadata when `--emit=metadata` is passed, we also only load flux metadata
-    // when the crate source is a `.rmeta` file.
-    tcx.used_crate_source(crate_num)
+    // Since we only save metadata when `--emit=metadata` is passed, we look for flux metadata next
+    // to the `.rmeta` file. Crates loaded directly from an `.rlib` (e.g., the specs for `core`
+    // shipped in the flux sysroot) are compiled with `--emit=metadata,link` so we also look next
+    // to the `.rlib` file.
+    let source = tcx.used_crate_source(crate_num);
+    source
         .rmeta
         .as_ref()
+        .or(source.rlib.as_ref())
         .map(|(path, _)| path.with_extension("fluxmeta"))
 }
 
@@ -384,14 +487,18 @@ const SYMBOL_OFFSET: u8 = 1;
 const SYMBOL_PREINTERNED: u8 = 2;
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::{E9060, E9062};
     use flux_macros::Diagnostic;
     use rustc_middle::ty::TyCtxt;
 
     use crate::Key;
 
     #[derive(Diagnostic)]
-    #[diag(metadata_duplicate_spec, code = E0999)]
+    #[diag(metadata_missing_prelude, code = E9062)]
+    pub(super) struct MissingPrelude;
+
+    #[derive(Diagnostic)]
+    #[diag(metadata_duplicate_spec, code = E9060)]
     pub(super) struct DuplicateSpec {
         def_name: String,
     }
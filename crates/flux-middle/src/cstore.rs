@@ -1,9 +1,30 @@
-use rustc_span::{def_id::DefId, Symbol};
+use rustc_data_structures::unord::{UnordMap, UnordSet};
+use rustc_macros::{TyDecodable, TyEncodable};
+use rustc_span::{
+    def_id::{CrateNum, DefId},
+    Symbol,
+};
 
 use crate::{queries::QueryResult, rty};
 
 pub type OptResult<T> = Option<QueryResult<T>>;
 
+/// The items declared with `flux::defs` that a crate exports to its dependents. Items are keyed by
+/// the name used inside the crate that defines them, and dependents identify them by that name
+/// together with the crate (see [`FluxDefId`]).
+///
+/// [`FluxDefId`]: crate::FluxDefId
+#[derive(Default, TyEncodable, TyDecodable)]
+pub struct CrateFluxItems {
+    pub func_decls: UnordMap<Symbol, rty::SpecFuncDecl>,
+    /// The (normalized) body of functions declared with a definition
+    pub func_defns: Vec<rty::SpecFunc>,
+    /// All the qualifiers of the crate. Dependents only use the ones they name explicitly.
+    pub qualifiers: Vec<rty::Qualifier>,
+    /// Opaque sorts declared with `opaque sort`
+    pub sort_decls: UnordSet<Symbol>,
+}
+
 pub trait CrateStore {
     fn fn_sig(&self, def_id: DefId) -> OptResult<rty::EarlyBinder<rty::PolyFnSig>>;
     fn adt_def(&self, def_id: DefId) -> OptResult<rty::AdtDef>;
@@ -30,6 +51,9 @@ pub trait CrateStore {
         def_id: DefId,
     ) -> OptResult<rty::Opaqueness<rty::EarlyBinder<rty::PolyVariants>>>;
     fn type_of(&self, def_id: DefId) -> OptResult<rty::EarlyBinder<rty::TyOrCtor>>;
+    /// Whether the specs in the flux prelude are loaded
+    fn prelude(&self) -> bool;
+    fn flux_items(&self, krate: CrateNum) -> Option<&CrateFluxItems>;
 }
 
 pub type CrateStoreDyn = dyn CrateStore;
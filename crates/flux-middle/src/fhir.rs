@@ -40,7 +40,7 @@ use rustc_span::{symbol::Ident, Span, Symbol};
 pub use rustc_target::abi::VariantIdx;
 use rustc_target::spec::abi;
 
-use crate::MaybeExternId;
+use crate::{rty::Real, FluxDefId, MaybeExternId};
 
 /// A boolean-like enum used to mark whether a piece of code is ignored.
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -406,11 +406,12 @@ pub type Arena = bumpalo::Bump;
 #[derive(Default)]
 pub struct FluxItems<'fhir> {
     pub items: FxHashMap<Symbol, FluxItem<'fhir>>,
+    pub sort_decls: SortDecls,
 }
 
 impl FluxItems<'_> {
     pub fn new() -> Self {
-        Self { items: Default::default() }
+        Self { items: Default::default(), sort_decls: Default::default() }
     }
 }
 
@@ -498,11 +499,19 @@ pub struct Requires<'fhir> {
 #[derive(Clone, Copy)]
 pub struct FnSig<'fhir> {
     pub header: FnHeader,
-    //// List of local qualifiers for this function
-    pub qualifiers: &'fhir [Ident],
+    //// List of qualifiers named in the `flux::qualifiers` attribute of this function
+    pub qualifiers: &'fhir [QualifierName],
     pub decl: &'fhir FnDecl<'fhir>,
 }
 
+/// A qualifier named in a `flux::qualifiers` attribute, e.g., `MyQual` for a qualifier in the local
+/// crate or `dep::MyQual` for one exported by the crate `dep`.
+#[derive(Clone, Copy, Debug)]
+pub struct QualifierName {
+    pub def_id: FluxDefId,
+    pub span: Span,
+}
+
 #[derive(Clone, Copy)]
 pub struct FnOutput<'fhir> {
     pub params: &'fhir [RefineParam<'fhir>],
@@ -544,10 +553,9 @@ pub enum TyKind<'fhir> {
     Ref(Lifetime, MutTy<'fhir>),
     BareFn(&'fhir BareFnTy<'fhir>),
     Tuple(&'fhir [Ty<'fhir>]),
-    Array(&'fhir Ty<'fhir>, ConstArg),
+    Array(&'fhir Ty<'fhir>, ConstArg<'fhir>),
     RawPtr(&'fhir Ty<'fhir>, Mutability),
     OpaqueDef(&'fhir OpaqueTy<'fhir>),
-    TraitObject(&'fhir [PolyTraitRef<'fhir>], Lifetime, TraitObjectSyntax),
     Never,
     Infer,
 }
@@ -645,6 +653,9 @@ impl<'fhir> BaseTy<'fhir> {
 pub enum BaseTyKind<'fhir> {
     Path(QPath<'fhir>),
     Slice(&'fhir Ty<'fhir>),
+    /// A trait object `dyn Trait`. Trait objects are refined by the values of the trait's
+    /// associated refinements, see [`crate::rty::BaseTy::Dynamic`].
+    TraitObject(&'fhir [PolyTraitRef<'fhir>], Lifetime, TraitObjectSyntax),
 }
 
 #[derive(Clone, Copy)]
@@ -690,7 +701,7 @@ pub enum AssocItemConstraintKind<'fhir> {
 pub enum GenericArg<'fhir> {
     Lifetime(Lifetime),
     Type(&'fhir Ty<'fhir>),
-    Const(ConstArg),
+    Const(ConstArg<'fhir>),
 }
 
 impl<'fhir> GenericArg<'fhir> {
@@ -703,16 +714,19 @@ impl<'fhir> GenericArg<'fhir> {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-pub struct ConstArg {
-    pub kind: ConstArgKind,
+#[derive(Clone, Copy)]
+pub struct ConstArg<'fhir> {
+    pub kind: ConstArgKind<'fhir>,
     pub span: Span,
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-pub enum ConstArgKind {
+#[derive(Clone, Copy)]
+pub enum ConstArgKind<'fhir> {
     Lit(usize),
     Param(DefId),
+    /// Arithmetic over const generics, e.g., `N + 1`. The value is taken from the Rust type, which
+    /// must contain the same expression.
+    BinaryOp(BinOp, &'fhir [ConstArg<'fhir>; 2]),
     Infer,
 }
 
@@ -842,6 +856,7 @@ pub enum PrimSort {
     Int,
     Bool,
     Real,
+    Str,
     Set,
     Map,
 }
@@ -852,6 +867,7 @@ impl PrimSort {
             PrimSort::Int => "int",
             PrimSort::Bool => "bool",
             PrimSort::Real => "real",
+            PrimSort::Str => "str",
             PrimSort::Set => "Set",
             PrimSort::Map => "Map",
         }
@@ -863,6 +879,7 @@ impl PrimSort {
             PrimSort::Int => 0,
             PrimSort::Bool => 0,
             PrimSort::Real => 0,
+            PrimSort::Str => 0,
             PrimSort::Set => 1,
             PrimSort::Map => 2,
         }
@@ -874,7 +891,7 @@ pub enum SortRes {
     /// A primitive sort.
     PrimSort(PrimSort),
     /// A user declared sort.
-    User { name: Symbol },
+    User(FluxDefId),
     /// A sort parameter inside a polymorphic function or data sort.
     SortParam(usize),
     /// The sort associated to a (generic) type parameter
@@ -1003,7 +1020,7 @@ impl Expr<'_> {
 #[derive(Clone, Copy)]
 pub enum Lit {
     Int(i128),
-    Real(i128),
+    Real(Real),
     Bool(bool),
     Str(Symbol),
     Char(char),
@@ -1182,9 +1199,9 @@ pub enum SpecFuncKind {
     /// Theory symbols "interpreted" by the SMT solver: `Symbol` is Fixpoint's name for the operation e.g. `set_cup` for flux's `set_union`
     Thy(Symbol),
     /// User-defined uninterpreted functions with no definition
-    Uif,
+    Uif(FluxDefId),
     /// User-defined functions with a body definition
-    Def,
+    Def(FluxDefId),
 }
 
 impl<'fhir> Generics<'fhir> {
@@ -1336,9 +1353,6 @@ impl fmt::Debug for Ty<'_> {
             TyKind::OpaqueDef(opaque_ty) => {
                 write!(f, "impl trait <def_id = {:?}>", opaque_ty.def_id.resolved_id(),)
             }
-            TyKind::TraitObject(poly_traits, _lft, _syntax) => {
-                write!(f, "dyn {poly_traits:?}")
-            }
         }
     }
 }
@@ -1368,17 +1382,18 @@ impl fmt::Debug for Lifetime {
     }
 }
 
-impl fmt::Debug for ConstArg {
+impl fmt::Debug for ConstArg<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self.kind)
     }
 }
 
-impl fmt::Debug for ConstArgKind {
+impl fmt::Debug for ConstArgKind<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ConstArgKind::Lit(n) => write!(f, "{n}"),
             ConstArgKind::Param(p) => write!(f, "{:?}", p),
+            ConstArgKind::BinaryOp(op, [c1, c2]) => write!(f, "({c1:?} {op:?} {c2:?})"),
             ConstArgKind::Infer => write!(f, "_"),
         }
     }
@@ -1389,6 +1404,9 @@ impl fmt::Debug for BaseTy<'_> {
         match &self.kind {
             BaseTyKind::Path(qpath) => write!(f, "{qpath:?}"),
             BaseTyKind::Slice(ty) => write!(f, "[{ty:?}]"),
+            BaseTyKind::TraitObject(poly_traits, _lft, _syntax) => {
+                write!(f, "dyn {poly_traits:?}")
+            }
         }
     }
 }
@@ -1508,7 +1526,7 @@ impl fmt::Debug for Lit {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Lit::Int(i) => write!(f, "{i}"),
-            Lit::Real(r) => write!(f, "{r}real"),
+            Lit::Real(r) => write!(f, "{r}"),
             Lit::Bool(b) => write!(f, "{b}"),
             Lit::Str(s) => write!(f, "\"{s:?}\""),
             Lit::Char(c) => write!(f, "\'{c}\'"),
@@ -1544,6 +1562,7 @@ impl fmt::Debug for SortRes {
             SortRes::PrimSort(PrimSort::Bool) => write!(f, "bool"),
             SortRes::PrimSort(PrimSort::Int) => write!(f, "int"),
             SortRes::PrimSort(PrimSort::Real) => write!(f, "real"),
+            SortRes::PrimSort(PrimSort::Str) => write!(f, "str"),
             SortRes::PrimSort(PrimSort::Set) => write!(f, "Set"),
             SortRes::PrimSort(PrimSort::Map) => write!(f, "Map"),
             SortRes::SortParam(n) => write!(f, "@{}", n),
@@ -1557,7 +1576,7 @@ impl fmt::Debug for SortRes {
             SortRes::SelfParamAssoc { ident: assoc, .. } => {
                 write!(f, "Self::{assoc}")
             }
-            SortRes::User { name } => write!(f, "{name}"),
+            SortRes::User(def_id) => write!(f, "{}", def_id.name),
             SortRes::Adt(def_id) => write!(f, "{}::sort", def_id_to_string(*def_id)),
         }
     }
@@ -306,6 +306,12 @@ impl<'a, 'genv, 'tcx> LiftCtxt<'a, 'genv, 'tcx> {
                 let opaque_ty = self.insert_opaque_ty(opaque_ty);
                 fhir::TyKind::OpaqueDef(opaque_ty)
             }
+            hir::TyKind::TraitObject(_, _, rustc_ast::TraitObjectSyntax::DynStar) => {
+                return self.emit_unsupported(&format!(
+                    "unsupported type: `{}`",
+                    rustc_hir_pretty::ty_to_string(&self.genv.tcx(), ty)
+                ));
+            }
             hir::TyKind::TraitObject(poly_traits, lft, syntax) => {
                 let poly_traits = try_alloc_slice!(self.genv, poly_traits, |poly_trait| {
                     if poly_trait.modifiers != hir::TraitBoundModifiers::NONE {
@@ -318,7 +324,9 @@ impl<'a, 'genv, 'tcx> LiftCtxt<'a, 'genv, 'tcx> {
                 })?;
 
                 let lft = self.lift_lifetime(lft)?;
-                fhir::TyKind::TraitObject(poly_traits, lft, syntax)
+                let kind = fhir::BaseTyKind::TraitObject(poly_traits, lft, syntax);
+                let bty = fhir::BaseTy { kind, fhir_id: self.next_fhir_id(), span: ty.span };
+                fhir::TyKind::BaseTy(bty)
             }
             _ => {
                 return self.emit_unsupported(&format!(
@@ -462,14 +470,14 @@ impl<'a, 'genv, 'tcx> LiftCtxt<'a, 'genv, 'tcx> {
         })
     }
 
-    fn lift_array_len(&mut self, len: hir::ArrayLen) -> Result<fhir::ConstArg> {
+    fn lift_array_len(&mut self, len: hir::ArrayLen) -> Result<fhir::ConstArg<'genv>> {
         match len {
             hir::ArrayLen::Body(const_arg) => Ok(self.lift_const_arg(const_arg)),
             hir::ArrayLen::Infer(_) => bug!("unexpected `ArrayLen::Infer`"),
         }
     }
 
-    fn lift_const_arg(&mut self, const_arg: &hir::ConstArg) -> fhir::ConstArg {
+    fn lift_const_arg(&mut self, const_arg: &hir::ConstArg) -> fhir::ConstArg<'genv> {
         fhir::ConstArg { kind: fhir::ConstArgKind::Infer, span: const_arg.span() }
     }
 
@@ -508,7 +516,7 @@ impl<'a, 'genv, 'tcx> LiftCtxt<'a, 'genv, 'tcx> {
 }
 
 pub mod errors {
-    use flux_errors::E0999;
+    use flux_errors::E9033;
     use flux_macros::Diagnostic;
     use rustc_hir::OwnerId;
     use rustc_middle::ty::TyCtxt;
@@ -517,7 +525,7 @@ pub mod errors {
     use crate::MaybeExternId;
 
     #[derive(Diagnostic)]
-    #[diag(middle_unsupported_hir, code = E0999)]
+    #[diag(middle_unsupported_hir, code = E9033)]
     #[note]
     pub struct UnsupportedHir<'a> {
         #[primary_span]
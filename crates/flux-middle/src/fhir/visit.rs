@@ -380,10 +380,6 @@ pub fn walk_ty<'v, V: Visitor<'v>>(vis: &mut V, ty: &Ty<'v>) {
         TyKind::OpaqueDef(opaque_ty) => {
             vis.visit_opaque_ty(opaque_ty);
         }
-        TyKind::TraitObject(poly_traits, lft, _) => {
-            walk_list!(vis, visit_poly_trait_ref, poly_traits);
-            vis.visit_lifetime(&lft);
-        }
         TyKind::Never | TyKind::Infer => {}
     }
 }
@@ -392,6 +388,10 @@ pub fn walk_bty<'v, V: Visitor<'v>>(vis: &mut V, bty: &BaseTy<'v>) {
     match &bty.kind {
         BaseTyKind::Path(path) => vis.visit_qpath(path),
         BaseTyKind::Slice(ty) => vis.visit_ty(ty),
+        BaseTyKind::TraitObject(poly_traits, lft, _) => {
+            walk_list!(vis, visit_poly_trait_ref, *poly_traits);
+            vis.visit_lifetime(lft);
+        }
     }
 }
 
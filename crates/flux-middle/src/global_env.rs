@@ -2,26 +2,27 @@ use std::{alloc, ptr, rc::Rc, slice};
 
 use flux_arc_interner::List;
 use flux_common::{bug, result::ErrorEmitter};
-use flux_config::CrateConfig;
+use flux_config::{self as config, CrateConfig};
 use flux_errors::FluxSession;
 use flux_rustc_bridge::{self, lowering::Lower, mir, ty};
 use rustc_hash::FxHashSet;
 use rustc_hir::{
     def::DefKind,
-    def_id::{DefId, LocalDefId},
+    def_id::{CrateNum, DefId, LocalDefId},
 };
 use rustc_middle::{
     query::IntoQueryParam,
     ty::{TyCtxt, Variance},
 };
+use rustc_span::hygiene::{ExpnData, ExpnKind, MacroKind};
 pub use rustc_span::{symbol::Ident, Symbol};
 
 use crate::{
-    cstore::CrateStoreDyn,
+    cstore::{CrateFluxItems, CrateStoreDyn},
     fhir::{self, VariantIdx},
     queries::{Providers, Queries, QueryErr, QueryResult},
     rty::{self, normalize::SpecFuncDefns, refining::Refiner},
-    MaybeExternId, ResolvedDefId,
+    FluxDefId, MaybeExternId, ResolvedDefId,
 };
 
 #[derive(Clone, Copy)]
@@ -138,25 +139,33 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         self.inner.queries.qualifiers(self)
     }
 
-    /// Return all the qualifiers that apply to an item, including both global and local qualifiers.
+    /// Return all the qualifiers that apply to an item, i.e., the global qualifiers of the local
+    /// crate and the qualifiers named in the `flux::qualifiers` attribute of the item. Qualifiers
+    /// exported by external crates only apply to items that name them explicitly.
     pub fn qualifiers_for(
         self,
         did: LocalDefId,
-    ) -> QueryResult<impl Iterator<Item = &'genv rty::Qualifier>> {
-        let names: FxHashSet<Symbol> = self
-            .map()
-            .fn_quals_for(did)?
+    ) -> QueryResult<impl Iterator<Item = &'genv rty::Qualifier> + use<'tcx, 'genv>> {
+        let quals = self.map().fn_quals_for(did)?;
+        let names: FxHashSet<FluxDefId> = quals.iter().map(|qual| qual.def_id).collect();
+        let extern_qualifiers = quals
             .iter()
-            .map(|qual| qual.name)
-            .collect();
+            .filter(|qual| !qual.def_id.is_local())
+            .filter_map(move |qual| {
+                self.extern_flux_items(qual.def_id.krate)?
+                    .qualifiers
+                    .iter()
+                    .find(|qualifier| qualifier.def_id == qual.def_id)
+            });
         Ok(self
             .qualifiers()?
             .iter()
-            .filter(move |qualifier| qualifier.global || names.contains(&qualifier.name)))
+            .filter(move |qualifier| qualifier.global || names.contains(&qualifier.def_id))
+            .chain(extern_qualifiers))
     }
 
-    pub fn func_decl(self, name: Symbol) -> QueryResult<rty::SpecFuncDecl> {
-        self.inner.queries.func_decl(self, name)
+    pub fn func_decl(self, def_id: FluxDefId) -> QueryResult<rty::SpecFuncDecl> {
+        self.inner.queries.func_decl(self, def_id)
     }
 
     pub fn variances_of(self, did: DefId) -> &'tcx [Variance] {
@@ -365,6 +374,17 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
             == def_id
     }
 
+    /// If `def_id` is the associated type rustc synthesizes for an `async fn` or `-> impl Trait`
+    /// in a trait method, returns the opaque type it was created from.
+    pub fn rpitit_opaque(&self, def_id: DefId) -> Option<DefId> {
+        match self.tcx().opt_rpitit_info(def_id)? {
+            rustc_middle::ty::ImplTraitInTraitData::Trait { opaque_def_id, .. } => {
+                Some(opaque_def_id)
+            }
+            rustc_middle::ty::ImplTraitInTraitData::Impl { .. } => None,
+        }
+    }
+
     /// Iterator over all local def ids that are not a extern spec
     pub fn iter_local_def_id(self) -> impl Iterator<Item = LocalDefId> + use<'tcx, 'genv> {
         self.tcx().iter_local_def_id().filter(move |&local_def_id| {
@@ -372,9 +392,20 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         })
     }
 
+    /// Iterator over the extern specs to export in the metadata of the crate, i.e., all extern
+    /// specs except the ones included from spec files (see [`Specs::extern_spec_files`]).
+    ///
+    /// [`Specs::extern_spec_files`]: crate::Specs::extern_spec_files
     pub fn iter_extern_def_id(self) -> impl Iterator<Item = DefId> + use<'tcx, 'genv> {
-        self.tcx()
-            .iter_local_def_id()
+        let tcx = self.tcx();
+        tcx.iter_local_def_id()
+            .filter(move |local_def_id| {
+                let module = tcx.parent_module_from_def_id(*local_def_id);
+                !self
+                    .collect_specs()
+                    .extern_spec_files
+                    .contains(&module.to_local_def_id())
+            })
             .filter_map(move |local_def_id| self.maybe_extern_id(local_def_id).as_extern())
     }
 
@@ -431,9 +462,37 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
     /// Transitively follow the parent-chain of `def_id` to find the first containing item with an
     /// explicit `#[flux::trusted(..)]` annotation and return whether that item is trusted or not.
     /// If no explicit annotation is found, return `false`.
+    ///
+    /// An item generated by a macro is annotated if the `macro_rules!` definition is marked with
+    /// `#[flux::trusted(..)]` or, for a derive, if the type is marked with
+    /// `#[flux::trusted_derive(..)]`.
     pub fn trusted(self, def_id: LocalDefId) -> bool {
-        self.traverse_parents(def_id, |did| self.collect_specs().trusted.get(&did))
-            .is_some_and(|trusted| trusted.to_bool())
+        let specs = self.collect_specs();
+        self.traverse_parents(def_id, |did| {
+            specs.trusted.get(&did).or_else(|| {
+                self.macro_expansion_attr(
+                    did,
+                    |macro_id| specs.trusted.get(&macro_id),
+                    |adt_id, name| {
+                        specs
+                            .trusted_derives
+                            .get(&adt_id)
+                            .is_some_and(|names| names.contains(&name))
+                            .then_some(&fhir::Trusted::Yes)
+                    },
+                )
+            })
+        })
+        .is_some_and(|trusted| trusted.to_bool())
+    }
+
+    /// Returns the level of `lint` for `def_id`. The level is taken from the closest containing item
+    /// with an explicit `#[flux::allow/warn/deny(..)]` annotation for the lint. If there's no such
+    /// item, the level is taken from the config file. Lints are denied by default.
+    pub fn lint_level(self, def_id: LocalDefId, lint: config::Lint) -> config::LintLevel {
+        self.traverse_parents(def_id, |did| self.collect_specs().lint_levels.get(&did)?.get(lint))
+            .or_else(|| config::lint_levels().get(lint))
+            .unwrap_or(config::LintLevel::Deny)
     }
 
     pub fn trusted_impl(self, def_id: LocalDefId) -> bool {
@@ -460,8 +519,23 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
     /// explicit `#[flux::ignore(..)]` annotation and return whether that item is ignored or not.
     /// If no explicit annotation is found, return `false`.
     pub fn ignored(self, def_id: LocalDefId) -> bool {
-        self.traverse_parents(def_id, |did| self.collect_specs().ignores.get(&did))
-            .is_some_and(|ignored| ignored.to_bool())
+        let specs = self.collect_specs();
+        self.traverse_parents(def_id, |did| {
+            specs.ignores.get(&did).or_else(|| {
+                self.macro_expansion_attr(
+                    did,
+                    |macro_id| specs.ignores.get(&macro_id),
+                    |adt_id, name| {
+                        specs
+                            .ignored_derives
+                            .get(&adt_id)
+                            .is_some_and(|names| names.contains(&name))
+                            .then_some(&fhir::Ignored::Yes)
+                    },
+                )
+            })
+        })
+        .is_some_and(|ignored| ignored.to_bool())
     }
 
     /// Whether the function is marked with `#[flux::should_fail]`
@@ -469,6 +543,71 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         self.collect_specs().should_fail.contains(&def_id)
     }
 
+    /// Whether to explain the refinement type errors of the function, i.e., whether it's marked
+    /// with `#[flux::explain]` or errors are explained for every function.
+    pub fn explain(self, def_id: LocalDefId) -> bool {
+        config::explain() || self.collect_specs().explain.contains(&def_id)
+    }
+
+    /// If `def_id` was generated by a `#[derive(..)]`, returns the name of the derive macro.
+    pub fn derived_by(self, def_id: LocalDefId) -> Option<Symbol> {
+        self.macro_expansions(def_id).find_map(|expn_data| {
+            if let ExpnKind::Macro(MacroKind::Derive, name) = expn_data.kind {
+                Some(name)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Look for an annotation on the macros that generated `def_id`, from the innermost expansion
+    /// to the outermost. For an expansion of a local `macro_rules!`, `for_macro` is called with the
+    /// definition of the macro. For an impl generated by a `#[derive(..)]` on a local type,
+    /// `for_derive` is called with the type and the name of the derive macro.
+    #[expect(
+        clippy::disallowed_methods,
+        reason = "macro definitions and derived types can't be extern specs"
+    )]
+    fn macro_expansion_attr<T>(
+        self,
+        def_id: LocalDefId,
+        for_macro: impl Fn(LocalDefId) -> Option<T>,
+        for_derive: impl Fn(LocalDefId, Symbol) -> Option<T>,
+    ) -> Option<T> {
+        self.macro_expansions(def_id).find_map(|expn_data| {
+            match expn_data.kind {
+                ExpnKind::Macro(MacroKind::Derive, name) => {
+                    if !matches!(self.def_kind(def_id), DefKind::Impl { .. }) {
+                        return None;
+                    }
+                    let adt_def = self
+                        .tcx()
+                        .type_of(def_id)
+                        .instantiate_identity()
+                        .ty_adt_def()?;
+                    for_derive(adt_def.did().as_local()?, name)
+                }
+                ExpnKind::Macro(MacroKind::Bang, _) => {
+                    for_macro(expn_data.macro_def_id?.as_local()?)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// The chain of macro expansions that generated `def_id` from the innermost to the outermost.
+    fn macro_expansions(self, def_id: LocalDefId) -> impl Iterator<Item = ExpnData> {
+        let mut ctxt = self.tcx().def_span(def_id).ctxt();
+        std::iter::from_fn(move || {
+            if ctxt.is_root() {
+                return None;
+            }
+            let expn_data = ctxt.outer_expn_data();
+            ctxt = expn_data.call_site.ctxt();
+            Some(expn_data)
+        })
+    }
+
     /// Traverse the parent chain of `def_id` until the first node for which `f` returns [`Some`].
     fn traverse_parents<T>(
         self,
@@ -491,6 +630,27 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
     pub fn crate_config(self) -> Option<CrateConfig> {
         self.collect_specs().crate_config
     }
+
+    /// Whether the specs in the flux prelude are loaded, either because the crate enables it or
+    /// because one of its dependencies was checked with it.
+    pub fn prelude_enabled(self) -> bool {
+        self.cstore().prelude()
+    }
+
+    /// The spec functions, qualifiers and opaque sorts exported by an external crate
+    pub fn extern_flux_items(self, krate: CrateNum) -> Option<&'genv CrateFluxItems> {
+        self.cstore().flux_items(krate)
+    }
+
+    /// Iterates over the flux items exported by all external crates
+    pub fn iter_extern_flux_items(
+        self,
+    ) -> impl Iterator<Item = (CrateNum, &'genv CrateFluxItems)> + use<'tcx, 'genv> {
+        self.tcx()
+            .crates(())
+            .iter()
+            .filter_map(move |krate| Some((*krate, self.extern_flux_items(*krate)?)))
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -549,6 +709,10 @@ impl<'genv, 'tcx> Map<'genv, 'tcx> {
         })
     }
 
+    pub fn sort_decls(self) -> impl Iterator<Item = &'genv fhir::SortDecl> {
+        self.fhir.sort_decls.values()
+    }
+
     pub fn qualifiers(self) -> impl Iterator<Item = &'genv fhir::Qualifier<'genv>> {
         self.fhir.items.values().filter_map(|item| {
             if let fhir::FluxItem::Qualifier(qual) = item {
@@ -559,7 +723,7 @@ impl<'genv, 'tcx> Map<'genv, 'tcx> {
         })
     }
 
-    pub fn fn_quals_for(self, def_id: LocalDefId) -> QueryResult<&'genv [Ident]> {
+    pub fn fn_quals_for(self, def_id: LocalDefId) -> QueryResult<&'genv [fhir::QualifierName]> {
         // This is called on adts when checking invariants
         if let Some(fn_sig) = self.expect_owner_node(def_id)?.fn_sig() {
             Ok(fn_sig.qualifiers)
@@ -59,9 +59,9 @@ use rustc_data_structures::{
     unord::{UnordMap, UnordSet},
 };
 use rustc_hir::OwnerId;
-use rustc_macros::extension;
+use rustc_macros::{extension, TyDecodable, TyEncodable};
 use rustc_span::{
-    def_id::{DefId, LocalDefId},
+    def_id::{CrateNum, DefId, LocalDefId, LOCAL_CRATE},
     symbol::Ident,
     Symbol,
 };
@@ -95,6 +95,46 @@ pub static THEORY_FUNCS: LazyLock<UnordMap<Symbol, TheoryFunc>> = LazyLock::new(
                 rty::FuncSort::new(vec![rty::Sort::Str], Int),
             ),
         },
+        TheoryFunc {
+            name: Symbol::intern("str_concat"),
+            fixpoint_name: Symbol::intern("strConcat"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, rty::Sort::Str], rty::Sort::Str),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("str_substr"),
+            fixpoint_name: Symbol::intern("strSubstr"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, Int, Int], rty::Sort::Str),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("str_prefix_of"),
+            fixpoint_name: Symbol::intern("strPrefixOf"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, rty::Sort::Str], Bool),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("str_suffix_of"),
+            fixpoint_name: Symbol::intern("strSuffixOf"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, rty::Sort::Str], Bool),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("str_contains"),
+            fixpoint_name: Symbol::intern("strContains"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, rty::Sort::Str], Bool),
+            ),
+        },
         // BitVector <-> int
         TheoryFunc {
             name: Symbol::intern("bv_zero_extend_32_to_64"),
@@ -372,9 +412,21 @@ pub struct Specs {
     pub ignores: UnordMap<LocalDefId, fhir::Ignored>,
     pub trusted: UnordMap<LocalDefId, fhir::Trusted>,
     pub trusted_impl: UnordMap<LocalDefId, fhir::Trusted>,
+    /// Names of the derive macros marked with `#[flux::trusted_derive(..)]` for each type. Every
+    /// item generated by one of these derives is considered trusted.
+    pub trusted_derives: UnordMap<LocalDefId, Vec<Symbol>>,
+    /// Names of the derive macros marked with `#[flux::ignore_derive(..)]` for each type. Every
+    /// item generated by one of these derives is ignored.
+    pub ignored_derives: UnordMap<LocalDefId, Vec<Symbol>>,
     pub check_overflows: UnordMap<LocalDefId, fhir::CheckOverflow>,
+    pub lint_levels: UnordMap<LocalDefId, config::LintLevels>,
     pub crate_config: Option<config::CrateConfig>,
     pub should_fail: UnordSet<LocalDefId>,
+    /// Functions marked with `#[flux::explain]`
+    pub explain: UnordSet<LocalDefId>,
+    /// Modules included from the standalone spec files listed in the config. Every crate checked
+    /// with the same config includes them, so the extern specs they contain are not exported.
+    pub extern_spec_files: UnordSet<LocalDefId>,
     /// Set of dummy items generated by the extern spec macro we must completely ignore. This is
     /// not the same as [ignored items] because, for ignored items, we still need to return errors
     /// for queries and handle them gracefully in order to report them at the use it.
@@ -446,6 +498,28 @@ pub struct ResolverOutput {
     pub implicit_params: UnordMap<NodeId, Vec<(Ident, NodeId)>>,
     pub sort_path_res_map: UnordMap<NodeId, fhir::SortRes>,
     pub expr_path_res_map: UnordMap<NodeId, fhir::ExprRes>,
+    /// Resolution of the qualifiers named in a `flux::qualifiers` attribute. The [`NodeId`] is the
+    /// id of the [`surface::ExprPath`] naming the qualifier.
+    pub qualifier_res_map: UnordMap<NodeId, FluxDefId>,
+}
+
+/// Identifies a spec function, qualifier or opaque sort declared in a `flux::defs` block. These
+/// items are namespaced by the crate declaring them, so two crates can declare items with the same
+/// name.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, TyEncodable, TyDecodable)]
+pub struct FluxDefId {
+    pub krate: CrateNum,
+    pub name: Symbol,
+}
+
+impl FluxDefId {
+    pub fn local(name: Symbol) -> Self {
+        FluxDefId { krate: LOCAL_CRATE, name }
+    }
+
+    pub fn is_local(self) -> bool {
+        self.krate == LOCAL_CRATE
+    }
 }
 
 /// This enum serves as a type-level reminder that a local definition _may be_ a wrapper for an
@@ -7,10 +7,9 @@ use rustc_hash::FxHashMap;
 use rustc_hir::def_id::DefId;
 use rustc_index::newtype_index;
 use rustc_middle::ty::TyCtxt;
-use rustc_span::{Pos, Span};
+use rustc_span::{Pos, Span, Symbol};
 use rustc_target::abi::FieldIdx;
 use rustc_type_ir::{BoundVar, DebruijnIndex, INNERMOST};
-use serde::Serialize;
 
 #[macro_export]
 macro_rules! _define_scoped {
@@ -160,7 +159,7 @@ pub fn pprint_with_default_cx<T: Pretty>(
 pub use crate::_impl_debug_with_default_cx as impl_debug_with_default_cx;
 use crate::{
     global_env::GlobalEnv,
-    rty::{BoundReft, BoundReftKind, BoundVariableKind},
+    rty::{BoundReft, BoundReftKind, BoundVariableKind, Name},
 };
 
 #[derive(Copy, Clone)]
@@ -185,6 +184,9 @@ pub struct PrettyCx<'genv, 'tcx> {
     pub hide_regions: bool,
     pub hide_sorts: bool,
     env: BoundVarEnv,
+    /// Names to print instead of the fresh names of free variables, e.g., the names of the arguments
+    /// of a function for the variables in their indices.
+    free_var_names: FxHashMap<Name, Symbol>,
 }
 
 newtype_index! {
@@ -291,6 +293,7 @@ impl<'genv, 'tcx> PrettyCx<'genv, 'tcx> {
             hide_regions: false,
             hide_sorts: true,
             env: BoundVarEnv::default(),
+            free_var_names: FxHashMap::default(),
         }
     }
 
@@ -403,6 +406,17 @@ impl<'genv, 'tcx> PrettyCx<'genv, 'tcx> {
     pub fn hide_sorts(self, b: bool) -> Self {
         Self { hide_sorts: b, ..self }
     }
+
+    pub fn free_var_names(self, free_var_names: FxHashMap<Name, Symbol>) -> Self {
+        Self { free_var_names, ..self }
+    }
+
+    pub fn fmt_free_var(&self, name: Name, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.free_var_names.get(&name) {
+            Some(symbol) => write!(f, "{symbol}"),
+            None => write!(f, "{name:?}"),
+        }
+    }
 }
 
 impl<'a, 'genv, 'tcx, T> WithCx<'a, 'genv, 'tcx, T> {
@@ -567,12 +581,7 @@ impl FromOpt for KVarArgs {
 
 // -------------------------------------------------------------------------------------------------------------
 
-#[derive(Serialize)]
-pub struct NestedString {
-    pub text: String,
-    pub key: Option<String>,
-    pub children: Option<Vec<NestedString>>,
-}
+pub use flux_common::trace::NestedString;
 
 pub fn debug_nested<T: Pretty>(cx: &PrettyCx, t: &T) -> Result<NestedString, fmt::Error> {
     let t = WithCx::new(cx, t);
@@ -602,9 +611,4 @@ pub fn float_children(children: Vec<Option<Vec<NestedString>>>) -> Option<Vec<Ne
 
 pub trait PrettyNested {
     fn fmt_nested(&self, cx: &PrettyCx) -> Result<NestedString, fmt::Error>;
-
-    fn nested_string(&self, cx: &PrettyCx) -> String {
-        let res = self.fmt_nested(cx).unwrap();
-        serde_json::to_string(&res).unwrap()
-    }
 }
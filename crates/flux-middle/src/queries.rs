@@ -4,7 +4,7 @@ use std::{
 };
 
 use flux_arc_interner::List;
-use flux_errors::{ErrorGuaranteed, E0999};
+use flux_errors::{ErrorGuaranteed, E9033, E9055, E9070, E9071};
 use flux_rustc_bridge::{
     self, def_id_to_string,
     lowering::{self, Lower, UnsupportedErr},
@@ -27,7 +27,7 @@ use crate::{
         self,
         refining::{self, Refiner},
     },
-    MaybeExternId, ResolvedDefId,
+    FluxDefId, MaybeExternId, ResolvedDefId,
 };
 
 type Cache<K, V> = RefCell<UnordMap<K, V>>;
@@ -200,7 +200,7 @@ pub struct Queries<'genv, 'tcx> {
     lower_type_of: Cache<DefId, QueryResult<ty::EarlyBinder<ty::Ty>>>,
     lower_fn_sig: Cache<DefId, QueryResult<ty::EarlyBinder<ty::PolyFnSig>>>,
     defns: OnceCell<QueryResult<rty::SpecFuncDefns>>,
-    func_decls: Cache<Symbol, QueryResult<rty::SpecFuncDecl>>,
+    func_decls: Cache<FluxDefId, QueryResult<rty::SpecFuncDecl>>,
     qualifiers: OnceCell<QueryResult<Vec<rty::Qualifier>>>,
     adt_sort_def_of: Cache<DefId, QueryResult<rty::AdtSortDef>>,
     check_wf: Cache<LocalDefId, QueryResult<Rc<rty::WfckResults>>>,
@@ -386,9 +386,18 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
     pub(crate) fn func_decl(
         &self,
         genv: GlobalEnv,
-        name: Symbol,
+        def_id: FluxDefId,
     ) -> QueryResult<rty::SpecFuncDecl> {
-        run_with_cache(&self.func_decls, name, || (self.providers.spec_func_decl)(genv, name))
+        run_with_cache(&self.func_decls, def_id, || {
+            if def_id.is_local() {
+                (self.providers.spec_func_decl)(genv, def_id.name)
+            } else {
+                genv.extern_flux_items(def_id.krate)
+                    .and_then(|items| items.func_decls.get(&def_id.name))
+                    .cloned()
+                    .ok_or_else(|| query_bug!("cannot find extern spec function `{def_id:?}`"))
+            }
+        })
     }
 
     pub(crate) fn qualifiers(&self, genv: GlobalEnv) -> QueryResult<&[rty::Qualifier]> {
@@ -404,6 +413,9 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
         def_id: DefId,
     ) -> QueryResult<rty::AdtSortDef> {
         run_with_cache(&self.adt_sort_def_of, def_id, || {
+            if genv.def_kind(def_id) == DefKind::Trait {
+                return genv.trait_object_sort_def_of(def_id);
+            }
             dispatch_query(
                 genv,
                 def_id,
@@ -748,7 +760,7 @@ impl<'a> Diagnostic<'a> for QueryErr {
                     QueryErr::Unsupported { def_id, err } => {
                         let span = err.span.unwrap_or_else(|| tcx.def_span(def_id));
                         let mut diag = dcx.struct_span_err(span, fluent::middle_query_unsupported);
-                        diag.code(E0999);
+                        diag.code(E9033);
                         diag.note(err.descr);
                         diag
                     }
@@ -756,14 +768,14 @@ impl<'a> Diagnostic<'a> for QueryErr {
                         let def_span = tcx.def_span(def_id);
                         let mut diag =
                             dcx.struct_span_err(def_span, fluent::middle_query_ignored_item);
-                        diag.code(E0999);
+                        diag.code(E9070);
                         diag
                     }
                     QueryErr::InvalidGenericArg { def_id } => {
                         let def_span = tcx.def_span(def_id);
                         let mut diag =
                             dcx.struct_span_err(def_span, fluent::middle_query_invalid_generic_arg);
-                        diag.code(E0999);
+                        diag.code(E9071);
                         diag
                     }
                     QueryErr::InvalidAssocReft { container_def_id: impl_id, name } => {
@@ -771,7 +783,7 @@ impl<'a> Diagnostic<'a> for QueryErr {
                         let mut diag =
                             dcx.struct_span_err(def_span, fluent::middle_query_invalid_assoc_reft);
                         diag.arg("name", name);
-                        diag.code(E0999);
+                        diag.code(E9055);
                         diag
                     }
                     QueryErr::Bug { def_id, location, msg } => {
@@ -807,7 +819,7 @@ impl<'a> Diagnostic<'a> for QueryErrAt {
             #[track_caller]
             |tcx| {
                 let tcx = tcx.expect("no TyCtxt stored in tls");
-                let mut diag = match self.err {
+                match self.err {
                     QueryErr::Unsupported { def_id, err, .. } => {
                         let mut diag =
                             dcx.struct_span_err(self.span, fluent::middle_query_unsupported_at);
@@ -816,6 +828,7 @@ impl<'a> Diagnostic<'a> for QueryErrAt {
                             diag.span_note(def_ident_span, fluent::_subdiag::note);
                         }
                         diag.note(err.descr);
+                        diag.code(E9033);
                         diag
                     }
                     QueryErr::Ignored { def_id } => {
@@ -824,12 +837,13 @@ impl<'a> Diagnostic<'a> for QueryErrAt {
                         diag.arg("kind", tcx.def_kind(def_id).descr(def_id));
                         diag.arg("name", def_id_to_string(def_id));
                         diag.span_label(self.span, fluent::_subdiag::label);
+                        diag.code(E9070);
                         diag
                     }
                     QueryErr::InvalidAssocReft { .. } => {
                         let mut diag = dcx
                             .struct_span_err(self.span, fluent::middle_query_invalid_assoc_reft_at);
-                        diag.code(E0999);
+                        diag.code(E9055);
                         diag
                     }
                     QueryErr::InvalidGenericArg { .. }
@@ -839,9 +853,7 @@ impl<'a> Diagnostic<'a> for QueryErrAt {
                         diag.span(self.span);
                         diag
                     }
-                };
-                diag.code(E0999);
-                diag
+                }
             },
         )
     }
@@ -0,0 +1,113 @@
+//! Built-in signatures for code generated by `#[derive(..)]`.
+//!
+//! Code generated by a derive is checked like any other code, but the signature we lift for it
+//! is sometimes too imprecise to be useful at call sites. For those cases we provide a more
+//! precise built-in signature and trust the generated body.
+
+use flux_arc_interner::List;
+use flux_common::bug;
+use itertools::Itertools;
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::ty::{BoundVar, INNERMOST};
+use rustc_span::sym;
+
+use super::{
+    AdtDef, BaseTy, Binder, BoundReftKind, BoundVariableKind, Expr, ExprKind, FnOutput, FnSig,
+    InferMode, Opaqueness, PolyFnSig, Ty, TyKind,
+};
+use crate::{global_env::GlobalEnv, queries::QueryResult};
+
+/// Returns the enum if `def_id` is the `eq` method generated by `#[derive(PartialEq)]` on an enum
+/// that gets the built-in signature in [`derived_eq_sig`]. This is the case for enums without
+/// generics whose variants have no fields and are indexed by distinct constants, e.g.,
+///
+/// ```ignore
+/// #[derive(PartialEq)]
+/// #[flux::refined_by(n: int)]
+/// enum Proto {
+///     #[flux::variant(Proto[0])]
+///     Tcp,
+///     #[flux::variant(Proto[1])]
+///     Udp,
+/// }
+/// ```
+pub fn derived_eq_enum(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<Option<AdtDef>> {
+    let tcx = genv.tcx();
+    if genv.derived_by(def_id) != Some(sym::PartialEq)
+        || tcx.item_name(def_id.to_def_id()) != sym::eq
+    {
+        return Ok(None);
+    }
+    let Some(impl_id) = tcx.impl_of_method(def_id.to_def_id()) else { return Ok(None) };
+    let Some(adt) = tcx.type_of(impl_id).instantiate_identity().ty_adt_def() else {
+        return Ok(None);
+    };
+    if !adt.is_enum() || tcx.generics_of(adt.did()).count() > 0 {
+        return Ok(None);
+    }
+    let Opaqueness::Transparent(variants) = genv.variants_of(adt.did())? else { return Ok(None) };
+
+    let mut idxs = vec![];
+    for variant in &variants.skip_binder() {
+        let variant = variant.skip_binder_ref();
+        if !variant.fields.is_empty() || !is_constant(&variant.idx) {
+            return Ok(None);
+        }
+        idxs.push(variant.idx.clone());
+    }
+    if !idxs.iter().all_unique() {
+        return Ok(None);
+    }
+    Ok(Some(genv.adt_def(adt.did())?))
+}
+
+/// The built-in signature for the `eq` method of an enum returned by [`derived_eq_enum`], i.e.,
+/// `fn(&E[@a], &E[@b]) -> bool[a == b]`. The regions are taken from the lifted signature.
+pub fn derived_eq_sig(adt_def: &AdtDef, lifted: &PolyFnSig) -> PolyFnSig {
+    let vars = lifted.vars();
+    let lifted = lifted.skip_binder_ref();
+    let bty = BaseTy::adt(adt_def.clone(), List::empty());
+
+    let inputs = lifted
+        .inputs()
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let TyKind::Indexed(BaseTy::Ref(re, _, mutbl), _) = input.kind() else {
+                bug!("unexpected input in derived `eq`: `{input:?}`");
+            };
+            let idx =
+                Expr::bvar(INNERMOST, BoundVar::from_usize(vars.len() + i), BoundReftKind::Annon);
+            Ty::mk_ref(*re, Ty::indexed(bty.clone(), idx), *mutbl)
+        })
+        .collect();
+
+    let [a, b] = [0, 1].map(|i| {
+        Expr::bvar(
+            INNERMOST.shifted_in(1),
+            BoundVar::from_usize(vars.len() + i),
+            BoundReftKind::Annon,
+        )
+    });
+    let output = Binder::bind_with_vars(
+        FnOutput::new(Ty::indexed(BaseTy::Bool, Expr::eq(a, b)), vec![]),
+        List::empty(),
+    );
+
+    let param = BoundVariableKind::Refine(adt_def.sort(&[]), InferMode::EVar, BoundReftKind::Annon);
+    let vars = vars.iter().cloned().chain([param.clone(), param]).collect();
+    Binder::bind_with_vars(
+        FnSig::new(lifted.safety, lifted.abi, List::empty(), inputs, output),
+        vars,
+    )
+}
+
+/// Whether `e` is built only from constants, in which case two such expressions are equal if and
+/// only if they are syntactically equal.
+fn is_constant(e: &Expr) -> bool {
+    match e.kind() {
+        ExprKind::Constant(_) => true,
+        ExprKind::Aggregate(_, flds) => flds.iter().all(is_constant),
+        _ => false,
+    }
+}
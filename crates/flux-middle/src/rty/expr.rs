@@ -14,7 +14,7 @@ use rustc_index::newtype_index;
 use rustc_macros::{Decodable, Encodable, TyDecodable, TyEncodable};
 use rustc_middle::{
     mir::Local,
-    ty::{ParamConst, ScalarInt, TyCtxt},
+    ty::{FloatTy, ParamConst, ScalarInt, TyCtxt},
 };
 use rustc_span::{Span, Symbol};
 use rustc_target::abi::FieldIdx;
@@ -625,13 +625,57 @@ impl Expr {
             ConstKind::Value(_ty, ValTree::Branch(_)) => {
                 bug!("todo: ValTree::Branch {c:?}")
             }
-            // We should have normalized away the unevaluated constants
-            ConstKind::Unevaluated(_) => bug!("unexpected `ConstKind::Unevaluated`"),
+            // Unevaluated constants that survive normalization are arithmetic over const generics
+            // (e.g., `[T; N + 1]` with `generic_const_exprs`), so we translate their abstract
+            // representation.
+            ConstKind::Unevaluated(_) => {
+                Expr::try_from_const(tcx, c)
+                    .unwrap_or_else(|| bug!("unsupported unevaluated const `{c:?}`"))
+            }
 
             ConstKind::Infer(_) => bug!("unexpected `ConstKind::Infer`"),
         }
     }
 
+    /// Translates the abstract representation of an unevaluated constant, returning `None` if it
+    /// contains something other than arithmetic over literals and const generics.
+    pub fn try_from_const(tcx: TyCtxt, c: &Const) -> Option<Expr> {
+        Expr::from_abstract_const(tcx, tcx.expand_abstract_consts(c.to_rustc(tcx)))
+    }
+
+    fn from_abstract_const<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        c: rustc_middle::ty::Const<'tcx>,
+    ) -> Option<Expr> {
+        use rustc_middle::{mir, ty};
+        match c.kind() {
+            ty::ConstKind::Param(param_const) => Some(Expr::const_generic(param_const)),
+            ty::ConstKind::Value(ty, ty::ValTree::Leaf(scalar)) => {
+                let val = match ty.kind() {
+                    ty::Uint(uint_ty) => Constant::from(scalar_to_uint(tcx, scalar, *uint_ty)),
+                    ty::Int(int_ty) => Constant::from(scalar_to_int(tcx, scalar, *int_ty)),
+                    _ => return None,
+                };
+                Some(Expr::constant(val))
+            }
+            ty::ConstKind::Expr(expr) if let ty::ExprKind::Binop(op) = expr.kind => {
+                let op = match op {
+                    mir::BinOp::Add | mir::BinOp::AddUnchecked => BinOp::Add,
+                    mir::BinOp::Sub | mir::BinOp::SubUnchecked => BinOp::Sub,
+                    mir::BinOp::Mul | mir::BinOp::MulUnchecked => BinOp::Mul,
+                    mir::BinOp::Div => BinOp::Div,
+                    mir::BinOp::Rem => BinOp::Mod,
+                    _ => return None,
+                };
+                let (_, _, c1, c2) = expr.binop_args();
+                let e1 = Expr::from_abstract_const(tcx, c1)?;
+                let e2 = Expr::from_abstract_const(tcx, c2)?;
+                Some(Expr::binary_op(op, e1, e2))
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_binary_op(&self) -> bool {
         matches!(self.kind(), ExprKind::BinaryOp(..))
     }
@@ -915,16 +959,114 @@ impl From<Local> for Loc {
     }
 }
 
+/// A real number literal denoting the decimal `mantissa * 10^-scale`. Literals are normalized such
+/// that two literals denoting the same number are structurally equal.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encodable, Decodable)]
-pub struct Real(pub i128);
+pub struct Real {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Real {
+    pub const ZERO: Real = Real { mantissa: 0, scale: 0 };
+
+    pub fn new(mut mantissa: i128, mut scale: u32) -> Real {
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        Real { mantissa, scale }
+    }
+
+    /// Parses a decimal literal as written in Rust source, e.g., `1.5`, `1_000.0` or `2.5e-3`.
+    /// Returns `None` if the literal is malformed or too large to be represented.
+    pub fn from_decimal_str(s: &str) -> Option<Real> {
+        let s = s.replace("_", "");
+        let (s, negative) = match s.strip_prefix('-') {
+            Some(s) => (s, true),
+            None => (&s[..], false),
+        };
+        let (base, exp) = match s.split_once(['e', 'E']) {
+            Some((base, exp)) => (base, exp.parse::<i64>().ok()?),
+            None => (s, 0),
+        };
+        let (int, frac) = base.split_once('.').unwrap_or((base, ""));
+        if int.is_empty() || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let mut mantissa = format!("{int}{frac}").parse::<i128>().ok()?;
+        if negative {
+            mantissa = -mantissa;
+        }
+        let scale = i64::try_from(frac.len()).ok()?.checked_sub(exp)?;
+        if scale < 0 {
+            let factor = 10i128.checked_pow(u32::try_from(-scale).ok()?)?;
+            Some(Real::new(mantissa.checked_mul(factor)?, 0))
+        } else {
+            Some(Real::new(mantissa, u32::try_from(scale).ok()?))
+        }
+    }
+
+    /// The real number denoted by a float constant given as its bits. We use the shortest decimal
+    /// that rounds to the float, i.e., the literal the user most likely wrote, instead of the exact
+    /// binary fraction. Returns `None` for infinities, NaN, and unsupported float types.
+    pub fn from_float_bits(bits: u128, float_ty: FloatTy) -> Option<Real> {
+        let s = match float_ty {
+            FloatTy::F32 => {
+                let f = f32::from_bits(bits as u32);
+                f.is_finite().then(|| format!("{f:e}"))?
+            }
+            FloatTy::F64 => {
+                let f = f64::from_bits(bits as u64);
+                f.is_finite().then(|| format!("{f:e}"))?
+            }
+            FloatTy::F16 | FloatTy::F128 => return None,
+        };
+        Real::from_decimal_str(&s)
+    }
+}
+
+impl From<i128> for Real {
+    fn from(n: i128) -> Self {
+        Real::new(n, 0)
+    }
+}
+
+impl fmt::Display for Real {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mantissa < 0 {
+            write!(f, "-")?;
+        }
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            write!(f, "{digits}.0")
+        } else if digits.len() > scale {
+            let (int, frac) = digits.split_at(digits.len() - scale);
+            write!(f, "{int}.{frac}")
+        } else {
+            write!(f, "0.{digits:0>scale$}")
+        }
+    }
+}
 
 impl liquid_fixpoint::FixpointFmt for Real {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0 < 0 {
-            write!(f, "(- {}.0)", self.0.unsigned_abs())
+        // We write the literal as an exact fraction `(/ mantissa 10^scale)` to avoid fixpoint
+        // reading it as a double.
+        let abs = self.mantissa.unsigned_abs();
+        if self.mantissa < 0 {
+            write!(f, "(- ")?;
+        }
+        if self.scale == 0 {
+            write!(f, "{abs}.0")?;
         } else {
-            write!(f, "{}.0", self.0)
+            write!(f, "(/ {abs}.0 1{:0>1$}.0)", "", self.scale as usize)?;
+        }
+        if self.mantissa < 0 {
+            write!(f, ")")?;
         }
+        Ok(())
     }
 }
 
@@ -1061,6 +1203,12 @@ impl From<i128> for Constant {
     }
 }
 
+impl From<Real> for Constant {
+    fn from(r: Real) -> Self {
+        Constant::Real(r)
+    }
+}
+
 impl From<bool> for Constant {
     fn from(b: bool) -> Self {
         Constant::Bool(b)
@@ -1268,7 +1416,7 @@ pub(crate) mod pretty {
             define_scoped!(cx, f);
             match self {
                 Constant::Int(i) => w!("{i}"),
-                Constant::Real(r) => w!("{}.0", ^r.0),
+                Constant::Real(r) => w!("{}", ^r),
                 Constant::Bool(b) => w!("{b}"),
                 Constant::Str(sym) => w!("\"{sym}\""),
                 Constant::Char(c) => write!(f, "\'{c}\'"),
@@ -1305,7 +1453,7 @@ pub(crate) mod pretty {
             match self {
                 Var::Bound(debruijn, var) => cx.fmt_bound_reft(*debruijn, *var, f),
                 Var::EarlyParam(var) => w!("{}", ^var.name),
-                Var::Free(name) => w!("{:?}", ^name),
+                Var::Free(name) => cx.fmt_free_var(*name, f),
                 Var::EVar(evar) => w!("{:?}", evar),
                 Var::ConstGeneric(param) => w!("{}", ^param.name),
             }
@@ -741,7 +741,7 @@ impl TypeSuperVisitable for BaseTy {
                 resume_ty.visit_with(visitor)?;
                 upvars.visit_with(visitor)
             }
-            BaseTy::Dynamic(exi_preds, _) => exi_preds.visit_with(visitor),
+            BaseTy::Dynamic(exi_preds, _, _) => exi_preds.visit_with(visitor),
             BaseTy::Int(_)
             | BaseTy::Uint(_)
             | BaseTy::Bool
@@ -788,8 +788,12 @@ impl TypeSuperFoldable for BaseTy {
                     args.try_fold_with(folder)?,
                 )
             }
-            BaseTy::Dynamic(preds, region) => {
-                BaseTy::Dynamic(preds.try_fold_with(folder)?, region.try_fold_with(folder)?)
+            BaseTy::Dynamic(preds, region, sort_def) => {
+                BaseTy::Dynamic(
+                    preds.try_fold_with(folder)?,
+                    region.try_fold_with(folder)?,
+                    sort_def.clone(),
+                )
             }
             BaseTy::Int(_)
             | BaseTy::Param(_)
@@ -1014,6 +1018,7 @@ TrivialTypeTraversalImpls! {
     crate::rty::KVid,
     rustc_span::Symbol,
     rustc_hir::def_id::DefId,
+    crate::FluxDefId,
     rustc_hir::Safety,
     rustc_target::spec::abi::Abi,
     rustc_type_ir::ClosureKind,
@@ -7,6 +7,7 @@
 
 mod binder;
 pub mod canonicalize;
+pub mod derive;
 pub mod evars;
 mod expr;
 pub mod fold;
@@ -64,6 +65,7 @@ use crate::{
     global_env::GlobalEnv,
     queries::QueryResult,
     rty::subst::SortSubst,
+    FluxDefId,
 };
 
 /// The definition of the data sort automatically generated for a struct or enum.
@@ -635,7 +637,7 @@ pub enum SortCtor {
     Set,
     Map,
     Adt(AdtSortDef),
-    User { name: Symbol },
+    User(FluxDefId),
 }
 
 newtype_index! {
@@ -922,7 +924,7 @@ pub enum SortParamKind {
 ///
 /// [sorts]: Sort
 /// [bit-vector sizes]: BvSize::Param
-#[derive(Clone, PartialEq, Eq, Hash, Debug, TyEncodable, TyDecodable)]
+#[derive(Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
 pub struct PolyFuncSort {
     /// The list of parameters including sorts and bit vector sizes
     params: List<SortParamKind>,
@@ -1051,19 +1053,20 @@ pub enum Ensures {
     Pred(Expr),
 }
 
-#[derive(Debug, TypeVisitable, TypeFoldable)]
+#[derive(Debug, Clone, TypeVisitable, TypeFoldable, TyEncodable, TyDecodable)]
 pub struct Qualifier {
-    pub name: Symbol,
+    pub def_id: FluxDefId,
     pub body: Binder<Expr>,
     pub global: bool,
 }
 
+#[derive(Clone, TyEncodable, TyDecodable)]
 pub struct SpecFunc {
-    pub name: Symbol,
+    pub def_id: FluxDefId,
     pub expr: Binder<Expr>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, TyEncodable, TyDecodable)]
 pub struct SpecFuncDecl {
     pub name: Symbol,
     pub sort: PolyFuncSort,
@@ -1107,10 +1110,6 @@ impl Ty {
         Ty::infer(TyVid::from_u32(0))
     }
 
-    pub fn dynamic(preds: impl Into<List<Binder<ExistentialPredicate>>>, region: Region) -> Ty {
-        BaseTy::Dynamic(preds.into(), region).to_ty()
-    }
-
     pub fn strg_ref(re: Region, path: Path, ty: Ty) -> Ty {
         TyKind::StrgRef(re, path, ty).intern()
     }
@@ -1435,7 +1434,11 @@ pub enum BaseTy {
     Never,
     Closure(DefId, /* upvar_tys */ List<Ty>, flux_rustc_bridge::ty::GenericArgs),
     Coroutine(DefId, /*resume_ty: */ Ty, /* upvar_tys: */ List<Ty>),
-    Dynamic(List<Binder<ExistentialPredicate>>, Region),
+    /// A trait object `dyn Trait`. A trait object is refined by the values of the associated
+    /// refinements of its principal trait, and the [`AdtSortDef`] describes its sort. The sort
+    /// is `None` for trait objects without a principal trait, e.g., `dyn Send`, which are
+    /// refined by unit. See [`GlobalEnv::trait_object_sort_def`].
+    Dynamic(List<Binder<ExistentialPredicate>>, Region, Option<AdtSortDef>),
     Param(ParamTy),
     Infer(TyVid),
 }
@@ -1597,7 +1600,11 @@ impl BaseTy {
         if sort.is_unit() {
             Ty::indexed(self.clone(), Expr::unit())
         } else {
-            Ty::exists(Binder::bind_with_sort(Ty::indexed(self.clone(), Expr::nu()), sort))
+            // Binder vars are folded under the binder, so both the sort and the base type must be
+            // shifted in to keep late-bound regions in their arguments pointing outside.
+            let bty = self.shift_in_escaping(1);
+            let sort = sort.shift_in_escaping(1);
+            Ty::exists(Binder::bind_with_sort(Ty::indexed(bty, Expr::nu()), sort))
         }
     }
 
@@ -1683,7 +1690,7 @@ impl<'tcx> ToRustc<'tcx> for BaseTy {
             }
             BaseTy::Never => tcx.types.never,
             BaseTy::Closure(did, _, args) => ty::Ty::new_closure(tcx, *did, args.to_rustc(tcx)),
-            BaseTy::Dynamic(exi_preds, re) => {
+            BaseTy::Dynamic(exi_preds, re, _) => {
                 let preds: Vec<_> = exi_preds
                     .iter()
                     .map(|pred| pred.to_rustc(tcx))
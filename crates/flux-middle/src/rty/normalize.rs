@@ -2,7 +2,6 @@ use std::ops::ControlFlow;
 
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
-use rustc_span::Symbol;
 use toposort_scc::IndexGraph;
 
 use super::{fold::TypeSuperFoldable, ESpan};
@@ -12,11 +11,12 @@ use crate::{
         fold::{TypeFoldable, TypeFolder, TypeSuperVisitable, TypeVisitable, TypeVisitor},
         Binder, Expr, ExprKind, SpecFunc,
     },
+    FluxDefId,
 };
 
 #[derive(Default)]
 pub struct SpecFuncDefns {
-    defns: FxHashMap<Symbol, SpecFunc>,
+    defns: FxHashMap<FluxDefId, SpecFunc>,
 }
 
 pub(super) struct Normalizer<'a> {
@@ -24,19 +24,19 @@ pub(super) struct Normalizer<'a> {
 }
 
 impl SpecFuncDefns {
-    pub fn new(defns: FxHashMap<Symbol, SpecFunc>) -> Result<Self, Vec<Symbol>> {
+    pub fn new(defns: FxHashMap<FluxDefId, SpecFunc>) -> Result<Self, Vec<FluxDefId>> {
         let raw = SpecFuncDefns { defns };
         raw.normalize()
     }
 
-    fn defn_deps(&self, expr: &Binder<Expr>) -> FxHashSet<Symbol> {
-        struct DepsVisitor(FxHashSet<Symbol>);
+    fn defn_deps(&self, expr: &Binder<Expr>) -> FxHashSet<FluxDefId> {
+        struct DepsVisitor(FxHashSet<FluxDefId>);
         impl TypeVisitor for DepsVisitor {
             fn visit_expr(&mut self, expr: &Expr) -> ControlFlow<!> {
                 if let ExprKind::App(func, _) = expr.kind()
-                    && let ExprKind::GlobalFunc(sym, SpecFuncKind::Def) = func.kind()
+                    && let ExprKind::GlobalFunc(_, SpecFuncKind::Def(def_id)) = func.kind()
                 {
-                    self.0.insert(*sym);
+                    self.0.insert(*def_id);
                 }
                 expr.super_visit_with(self)
             }
@@ -50,10 +50,10 @@ impl SpecFuncDefns {
     /// * either Ok(d1...dn) which are topologically sorted such that
     ///   forall i < j, di does not depend on i.e. "call" dj
     /// * or Err(d1...dn) where d1 'calls' d2 'calls' ... 'calls' dn 'calls' d1
-    fn sorted_defns(&self) -> Result<Vec<Symbol>, Vec<Symbol>> {
+    fn sorted_defns(&self) -> Result<Vec<FluxDefId>, Vec<FluxDefId>> {
         // 1. Make the Symbol-Index
-        let mut i2s: Vec<Symbol> = Vec::new();
-        let mut s2i: FxHashMap<Symbol, usize> = FxHashMap::default();
+        let mut i2s: Vec<FluxDefId> = Vec::new();
+        let mut s2i: FxHashMap<FluxDefId, usize> = FxHashMap::default();
         for (i, s) in self.defns.keys().enumerate() {
             i2s.push(*s);
             s2i.insert(*s, i);
@@ -78,7 +78,7 @@ impl SpecFuncDefns {
             Ok(is) => Ok(is.iter().map(|i| i2s[*i]).collect()),
             Err(mut scc) => {
                 let cycle = scc.pop().unwrap();
-                let mut names: Vec<Symbol> = cycle.iter().map(|i| i2s[*i]).collect();
+                let mut names: Vec<FluxDefId> = cycle.iter().map(|i| i2s[*i]).collect();
                 names.sort();
                 Err(names)
             }
@@ -86,7 +86,7 @@ impl SpecFuncDefns {
     }
 
     // private function normalize (expand_defns) which does the SCC-expansion
-    fn normalize(mut self) -> Result<Self, Vec<Symbol>> {
+    fn normalize(mut self) -> Result<Self, Vec<FluxDefId>> {
         // 1. Topologically sort the Defns
         let ds = self.sorted_defns()?;
 
@@ -102,7 +102,7 @@ impl SpecFuncDefns {
         Ok(exp_defns)
     }
 
-    fn func_defn(&self, f: &Symbol) -> Option<&SpecFunc> {
+    pub fn func_defn(&self, f: &FluxDefId) -> Option<&SpecFunc> {
         self.defns.get(f)
     }
 }
@@ -121,8 +121,8 @@ impl<'a> Normalizer<'a> {
 
     fn app(&mut self, func: &Expr, args: &[Expr], espan: Option<ESpan>) -> Expr {
         match func.kind() {
-            ExprKind::GlobalFunc(sym, SpecFuncKind::Def)
-                if let Some(defn) = self.defs.func_defn(sym) =>
+            ExprKind::GlobalFunc(_, SpecFuncKind::Def(def_id))
+                if let Some(defn) = self.defs.func_defn(def_id) =>
             {
                 let res = defn.expr.replace_bound_refts(args);
                 Self::at_base(res, espan)
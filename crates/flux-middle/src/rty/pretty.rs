@@ -71,7 +71,7 @@ impl Pretty for SortCtor {
         match self {
             SortCtor::Set => w!("Set"),
             SortCtor::Map => w!("Map"),
-            SortCtor::User { name, .. } => w!("{}", ^name),
+            SortCtor::User(def_id) => w!("{}", ^def_id.name),
             SortCtor::Adt(adt_sort_def) => {
                 w!("{:?}", adt_sort_def.did())
             }
@@ -458,7 +458,7 @@ impl Pretty for BaseTy {
                 }
                 Ok(())
             }
-            BaseTy::Dynamic(preds, re) => {
+            BaseTy::Dynamic(preds, re, _) => {
                 w!("dyn {:?} + {:?}", join!(" + ", preds), re)
             }
             BaseTy::Infer(ty_vid) => {
@@ -595,6 +595,7 @@ impl_debug_with_default_cx!(
     VariantSig,
     PtrKind,
     FuncSort,
+    PolyFuncSort,
     SortCtor,
     SubsetTy,
     BvSize,
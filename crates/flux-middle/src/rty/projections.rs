@@ -15,8 +15,8 @@ use super::{
     fold::{FallibleTypeFolder, TypeFoldable, TypeSuperFoldable},
     subst::{GenericsSubstDelegate, GenericsSubstFolder},
     AliasKind, AliasReft, AliasTy, BaseTy, Binder, Clause, ClauseKind, Const, EarlyBinder, Expr,
-    ExprKind, GenericArg, ProjectionPredicate, RefineArgs, Region, Sort, SubsetTy, SubsetTyCtor,
-    Ty, TyKind,
+    ExprKind, FieldProj, GenericArg, ProjectionPredicate, RefineArgs, Region, Sort, SubsetTy,
+    SubsetTyCtor, Ty, TyKind,
 };
 use crate::{
     global_env::GlobalEnv,
@@ -69,7 +69,28 @@ impl<'genv, 'tcx, 'cx> Normalizer<'genv, 'tcx, 'cx> {
         alias_reft: &AliasReft,
         refine_args: &RefineArgs,
     ) -> QueryResult<Expr> {
-        if let Some(impl_def_id) = self.get_impl_id_of_alias_reft(alias_reft)? {
+        if let Some(proj) = trait_object_field(alias_reft) {
+            // `<dyn Trait as Trait>::f(x)` is the field `f` of the trait object's index
+            let [arg] = &refine_args[..] else {
+                bug!("unexpected arguments for `{alias_reft:?}`: `{refine_args:?}`");
+            };
+            Ok(Expr::field_proj(arg.try_fold_with(self)?, proj))
+        } else if let Some(impl_def_id) = self.get_impl_id_of_alias_reft(alias_reft)? {
+            // The impl doesn't define the assoc refinement (e.g., an extern impl without a spec),
+            // so we use the default in the trait, which is generic over the trait's arguments.
+            if self
+                .genv
+                .assoc_refinements_of(impl_def_id)?
+                .find(alias_reft.name)
+                .is_none()
+                && let Some(lam) = self
+                    .genv
+                    .default_assoc_refinement_def(alias_reft.trait_id, alias_reft.name)?
+            {
+                let pred = lam.instantiate(self.tcx(), &alias_reft.args, &[]);
+                return pred.apply(refine_args).try_fold_with(self);
+            }
+
             let impl_trait_ref = self
                 .genv
                 .impl_trait_ref(impl_def_id)?
@@ -431,6 +452,23 @@ impl FallibleTypeFolder for Normalizer<'_, '_, '_> {
     }
 }
 
+/// If the `Self` type of `alias_reft` is a trait object of the same trait, returns the
+/// projection for the field of the trait object's index corresponding to the associated
+/// refinement.
+fn trait_object_field(alias_reft: &AliasReft) -> Option<FieldProj> {
+    let self_bty = match alias_reft.args.first()? {
+        GenericArg::Ty(ty) => ty.as_bty_skipping_existentials()?,
+        GenericArg::Base(ctor) => ctor.as_bty_skipping_binder(),
+        _ => return None,
+    };
+    let BaseTy::Dynamic(_, _, Some(sort_def)) = self_bty else { return None };
+    if sort_def.did() != alias_reft.trait_id {
+        return None;
+    }
+    let (proj, _) = sort_def.field_by_name(&[], alias_reft.name)?;
+    Some(proj)
+}
+
 #[derive(Debug)]
 pub enum Candidate {
     UserDefinedImpl(DefId),
@@ -101,7 +101,7 @@ impl<'genv, 'tcx> Refiner<'genv, 'tcx> {
         })
     }
 
-    pub(crate) fn refine_generic_predicates(
+    pub fn refine_generic_predicates(
         &self,
         generics: &ty::GenericPredicates,
     ) -> QueryResult<rty::GenericPredicates> {
@@ -384,6 +384,18 @@ impl<'genv, 'tcx> Refiner<'genv, 'tcx> {
                 let args = self.refine_generic_args(*def_id, args)?;
                 rty::BaseTy::fn_def(*def_id, args)
             }
+            // We refine projections on the associated type synthesized for an `async fn` or
+            // `-> impl Trait` in a trait as the original opaque type, which is the type used in
+            // the signature of the trait method.
+            ty::TyKind::Alias(ty::AliasKind::Projection, alias_ty)
+                if let Some(opaque_def_id) = self.genv.rpitit_opaque(alias_ty.def_id) =>
+            {
+                let alias_ty = ty::AliasTy { def_id: opaque_def_id, args: alias_ty.args.clone() };
+                let alias_ty = self
+                    .as_default()
+                    .refine_alias_ty(ty::AliasKind::Opaque, &alias_ty)?;
+                rty::BaseTy::opaque(alias_ty)
+            }
             ty::TyKind::Alias(kind, alias_ty) => {
                 let alias_ty = self.as_default().refine_alias_ty(*kind, alias_ty)?;
                 rty::BaseTy::Alias(*kind, alias_ty)
@@ -401,11 +413,12 @@ impl<'genv, 'tcx> Refiner<'genv, 'tcx> {
                 rty::BaseTy::RawPtr(self.as_default().refine_ty(ty)?, *mu)
             }
             ty::TyKind::Dynamic(exi_preds, r) => {
-                let exi_preds = exi_preds
+                let exi_preds: List<_> = exi_preds
                     .iter()
                     .map(|ty| self.refine_existential_predicate(ty))
                     .try_collect()?;
-                rty::BaseTy::Dynamic(exi_preds, *r)
+                let sort_def = self.genv.trait_object_sort_def(&exi_preds)?;
+                rty::BaseTy::Dynamic(exi_preds, *r, sort_def)
             }
         };
         Ok(rty::TyOrBase::Base((self.refine)(bty)))
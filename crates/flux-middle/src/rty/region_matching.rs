@@ -146,7 +146,7 @@ impl RegionSubst {
                 debug_assert_eq!(mutbl_a, mutbl_b);
                 self.ty_infer_from_ty(ty_a, ty_b);
             }
-            (rty::BaseTy::Dynamic(preds_a, re_a), ty::TyKind::Dynamic(preds_b, re_b)) => {
+            (rty::BaseTy::Dynamic(preds_a, re_a, _), ty::TyKind::Dynamic(preds_b, re_b)) => {
                 debug_assert_eq!(preds_a.len(), preds_b.len());
                 self.infer_from_region(*re_a, *re_b);
                 for (pred_a, pred_b) in iter::zip(preds_a, preds_b) {
@@ -277,7 +277,7 @@ impl RegionSubst {
             (rty::BaseTy::Array(ty_a, _), rty::BaseTy::Array(ty_b, _)) => {
                 self.rty_infer_from_ty(ty_a, ty_b);
             }
-            (rty::BaseTy::Dynamic(preds_a, re_a), rty::BaseTy::Dynamic(preds_b, re_b)) => {
+            (rty::BaseTy::Dynamic(preds_a, re_a, _), rty::BaseTy::Dynamic(preds_b, re_b, _)) => {
                 for (pred_a, pred_b) in iter::zip(preds_a, preds_b) {
                     self.rty_infer_from_existential_pred(pred_a, pred_b);
                 }
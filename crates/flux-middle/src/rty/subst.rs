@@ -342,6 +342,24 @@ impl<D: GenericsSubstDelegate> FallibleTypeFolder for GenericsSubstFolder<'_, D>
     }
 
     fn try_fold_const(&mut self, c: &Const) -> Result<Const, D::Error> {
+        // Arithmetic over const generics (e.g., `N + 1`) is an unevaluated constant whose
+        // arguments mention the const parameters. We only substitute those, since array lengths
+        // cannot depend on type parameters.
+        if let ConstKind::Unevaluated(uneval) = &c.kind {
+            let args = uneval
+                .args
+                .iter()
+                .map(|arg| {
+                    Ok(match arg {
+                        ty::GenericArg::Const(c) => ty::GenericArg::Const(self.try_fold_const(c)?),
+                        ty::GenericArg::Ty(ty) => ty::GenericArg::Ty(ty.clone()),
+                        ty::GenericArg::Lifetime(re) => ty::GenericArg::Lifetime(*re),
+                    })
+                })
+                .try_collect()?;
+            let uneval = ty::UnevaluatedConst { def: uneval.def, args };
+            return Ok(Const { kind: ConstKind::Unevaluated(uneval) });
+        }
         Ok(self.delegate.const_for_param(c))
     }
 }
@@ -38,6 +38,7 @@ impl GlobalEnv<'_, '_> {
             ty::TyKind::Bool => Some(rty::Sort::Bool),
             ty::TyKind::Slice(_) | ty::TyKind::Int(_) | ty::TyKind::Uint(_) => Some(rty::Sort::Int),
             ty::TyKind::Char => Some(rty::Sort::Char),
+            ty::TyKind::Float(_) => Some(rty::Sort::Real),
             ty::TyKind::Str => Some(rty::Sort::Str),
             ty::TyKind::Adt(adt_def, args) => {
                 let mut sort_args = vec![];
@@ -58,8 +59,7 @@ impl GlobalEnv<'_, '_> {
                     .param_at(p.index as usize, self.tcx());
                 self.sort_of_generic_param(generic_param_def.def_id)?
             }
-            ty::TyKind::Float(_)
-            | ty::TyKind::RawPtr(..)
+            ty::TyKind::RawPtr(..)
             | ty::TyKind::Ref(..)
             | ty::TyKind::Tuple(_)
             | ty::TyKind::Array(..)
@@ -70,6 +70,51 @@ impl GlobalEnv<'_, '_> {
         Ok(sort)
     }
 
+    /// Returns the sort definition for trait objects with the given predicates, i.e., the sort
+    /// of the principal trait. Returns `None` if there's no principal trait.
+    pub fn trait_object_sort_def(
+        self,
+        preds: &[rty::PolyExistentialPredicate],
+    ) -> QueryResult<Option<rty::AdtSortDef>> {
+        preds
+            .iter()
+            .find_map(|pred| {
+                if let rty::ExistentialPredicate::Trait(trait_ref) = pred.skip_binder_ref() {
+                    Some(trait_ref.def_id)
+                } else {
+                    None
+                }
+            })
+            .map(|trait_id| self.adt_sort_def_of(trait_id))
+            .transpose()
+    }
+
+    /// The sort of a trait object `dyn Trait` is a record with a field for every associated
+    /// refinement of `Trait` that takes `Self` as its only argument and returns a sort not
+    /// mentioning the trait's generics, e.g., for
+    ///
+    /// ```ignore
+    /// #[flux::generics(Self as base)]
+    /// #[flux::assoc(fn area(self: Self) -> int)]
+    /// trait Shape { .. }
+    /// ```
+    ///
+    /// the sort of `dyn Shape` has a single field `area: int`.
+    pub(crate) fn trait_object_sort_def_of(self, trait_id: DefId) -> QueryResult<rty::AdtSortDef> {
+        let mut fields = vec![];
+        for assoc in &self.assoc_refinements_of(trait_id)?.items {
+            let Some(fsort) = self.sort_of_assoc_reft(trait_id, assoc.name)? else { continue };
+            let fsort = fsort.skip_binder();
+            if let [rty::Sort::Param(param)] = fsort.inputs()
+                && param.index == 0
+                && is_ground(fsort.output())
+            {
+                fields.push((assoc.name, fsort.output().clone()));
+            }
+        }
+        Ok(rty::AdtSortDef::new(trait_id, vec![], fields))
+    }
+
     pub fn normalize_weak_alias_sort(self, alias_ty: &rty::AliasTy) -> QueryResult<rty::Sort> {
         match self.def_kind(alias_ty.def_id) {
             DefKind::Impl { .. } => Ok(self.sort_of_self_ty_alias(alias_ty.def_id)?.unwrap()),
@@ -90,12 +135,26 @@ impl GlobalEnv<'_, '_> {
     }
 }
 
+fn is_ground(sort: &rty::Sort) -> bool {
+    match sort {
+        rty::Sort::Int
+        | rty::Sort::Bool
+        | rty::Sort::Real
+        | rty::Sort::Str
+        | rty::Sort::Char
+        | rty::Sort::BitVec(_) => true,
+        rty::Sort::Tuple(sorts) => sorts.iter().all(is_ground),
+        _ => false,
+    }
+}
+
 impl rty::BaseTy {
     pub fn sort(&self) -> rty::Sort {
         match self {
             rty::BaseTy::Int(_) | rty::BaseTy::Uint(_) | rty::BaseTy::Slice(_) => rty::Sort::Int,
             rty::BaseTy::Bool => rty::Sort::Bool,
             rty::BaseTy::Char => rty::Sort::Char,
+            rty::BaseTy::Float(_) => rty::Sort::Real,
             rty::BaseTy::Adt(adt_def, args) => adt_def.sort(args),
             rty::BaseTy::Param(param_ty) => rty::Sort::Param(*param_ty),
             rty::BaseTy::Str => rty::Sort::Str,
@@ -108,8 +167,7 @@ impl rty::BaseTy {
                     rty::AliasTy::new(alias_ty.def_id, alias_ty.args.clone(), List::empty());
                 rty::Sort::Alias(*kind, alias_ty)
             }
-            rty::BaseTy::Float(_)
-            | rty::BaseTy::RawPtr(..)
+            rty::BaseTy::RawPtr(..)
             | rty::BaseTy::Ref(..)
             | rty::BaseTy::FnPtr(..)
             | rty::BaseTy::FnDef(..)
@@ -117,8 +175,9 @@ impl rty::BaseTy {
             | rty::BaseTy::Array(_, _)
             | rty::BaseTy::Closure(..)
             | rty::BaseTy::Coroutine(..)
-            | rty::BaseTy::Dynamic(_, _)
+            | rty::BaseTy::Dynamic(_, _, None)
             | rty::BaseTy::Never => rty::Sort::unit(),
+            rty::BaseTy::Dynamic(_, _, Some(sort_def)) => sort_def.to_sort(&[]),
             rty::BaseTy::Infer(_) => tracked_span_bug!(),
         }
     }
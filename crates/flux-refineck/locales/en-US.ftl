@@ -52,6 +52,9 @@ refineck_unsupported_call =
 refineck_expected_neg =
     {$def_descr} marked with `#[should_fail]` didn't produce a refinement type error
 
+refineck_cannot_write_trace =
+    cannot write the checker trace to `{$dir}`: {$err}
+
 # Invariant checking
 
 refineck_invalid_invariant =
@@ -1,11 +1,17 @@
 use std::{collections::hash_map::Entry, iter};
 
-use flux_common::{bug, dbg, index::IndexVec, iter::IterExt, tracked_span_bug};
+use flux_common::{
+    bug, dbg,
+    index::IndexVec,
+    iter::IterExt,
+    trace::{self, FnTrace},
+    tracked_span_bug,
+};
 use flux_config as config;
 use flux_infer::{
     fixpoint_encoding::{self, KVarGen},
     infer::{ConstrReason, InferCtxt, InferCtxtRoot, InferResult, SubtypeReason},
-    refine_tree::{AssumeInvariants, RefineCtxtTrace, RefineTree, Snapshot},
+    refine_tree::{AssumeInvariants, Origin, RefineTree, Snapshot},
 };
 use flux_middle::{
     global_env::GlobalEnv,
@@ -51,7 +57,7 @@ use crate::{
     ghost_statements::{GhostStatement, GhostStatements, Point},
     primops,
     queue::WorkQueue,
-    type_env::{BasicBlockEnv, BasicBlockEnvShape, PtrToRefBound, TypeEnv, TypeEnvTrace},
+    type_env::{BasicBlockEnv, BasicBlockEnvShape, PtrToRefBound, TypeEnv},
 };
 
 type Result<T = ()> = std::result::Result<T, CheckerError>;
@@ -77,6 +83,9 @@ pub(crate) struct Checker<'ck, 'genv, 'tcx, M> {
     visited: BitSet<BasicBlock>,
     queue: WorkQueue<'ck>,
     default_refiner: Refiner<'genv, 'tcx>,
+    /// The trace of the item being checked, only recorded in refinement mode when
+    /// `dump_checker_trace` is set.
+    trace: Option<FnTrace>,
 }
 
 /// Fields shared by the top-level function and its nested closure/generators
@@ -435,12 +444,14 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
 
         let body = genv.mir(def_id).with_span(span)?;
 
-        let fn_sig = poly_sig
-            .replace_bound_vars(|_| rty::ReErased, |sort, _| infcx.define_vars(sort))
-            .normalize_projections(infcx.genv, infcx.region_infcx, infcx.def_id)
-            .with_span(span)?;
-
-        let mut env = TypeEnv::new(&mut infcx, &body, &fn_sig, inherited.config.check_overflow);
+        let (fn_sig, env) = infcx.with_origin(Origin::FnInputs(span), |infcx| -> Result<_> {
+            let fn_sig = poly_sig
+                .replace_bound_vars(|_| rty::ReErased, |sort, _| infcx.define_vars(sort))
+                .normalize_projections(infcx.genv, infcx.region_infcx, infcx.def_id)
+                .with_span(span)?;
+            let env = TypeEnv::new(infcx, &body, &fn_sig, inherited.config.check_overflow);
+            Ok((fn_sig, env))
+        })?;
 
         // (NOTE:YIELD) per https://doc.rust-lang.org/beta/nightly-rustc/rustc_middle/mir/enum.TerminatorKind.html#variant.Yield
         //   "execution of THIS function continues at the `resume` basic block, with THE SECOND ARGUMENT WRITTEN
@@ -461,23 +472,40 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             snapshots: IndexVec::from_fn_n(|_| None, body.basic_blocks.len()),
             queue: WorkQueue::empty(body.basic_blocks.len(), &body.dominator_order_rank),
             default_refiner: Refiner::default_for_item(genv, def_id.to_def_id()).with_span(span)?,
+            trace: (config::dump_checker_trace() && M::NAME == RefineMode::NAME)
+                .then(|| FnTrace::new(genv.tcx(), def_id.to_def_id())),
         };
-        ck.check_ghost_statements_at(&mut infcx, &mut env, Point::FunEntry, body.span())?;
+        let result = ck.check_body(&mut infcx, env);
+
+        // The trace is dumped even if checking fails because that's when it's most useful.
+        if let Some(trace) = &ck.trace
+            && let Err(err) = trace::dump_fn_trace(genv.tcx(), def_id.to_def_id(), trace)
+        {
+            genv.sess()
+                .emit_err(crate::errors::CannotWriteTrace::new(genv.tcx().def_span(def_id), err));
+        }
 
-        ck.check_goto(infcx.branch(), env, body.span(), START_BLOCK)?;
+        result
+    }
+
+    fn check_body(&mut self, infcx: &mut InferCtxt<'_, 'genv, 'tcx>, mut env: TypeEnv) -> Result {
+        let span = self.body.span();
+        self.check_ghost_statements_at(infcx, &mut env, Point::FunEntry, span)?;
 
-        while let Some(bb) = ck.queue.pop() {
-            if ck.visited.contains(bb) {
-                let snapshot = ck.snapshot_at_dominator(bb);
+        self.check_goto(infcx.branch(), env, span, START_BLOCK)?;
+
+        while let Some(bb) = self.queue.pop() {
+            if self.visited.contains(bb) {
+                let snapshot = self.snapshot_at_dominator(bb);
                 infcx.clean_subtree(snapshot);
-                M::clear(&mut ck, bb);
+                M::clear(self, bb);
             }
 
-            let snapshot = ck.snapshot_at_dominator(bb);
+            let snapshot = self.snapshot_at_dominator(bb);
             let mut infcx = infcx.change_root(snapshot);
-            let mut env = M::enter_basic_block(&mut ck, &mut infcx, bb);
-            env.unpack(&mut infcx, ck.config().check_overflow);
-            ck.check_basic_block(infcx, env, bb)?;
+            let mut env = M::enter_basic_block(self, &mut infcx, bb);
+            env.unpack(&mut infcx, self.config().check_overflow);
+            self.check_basic_block(infcx, env, bb)?;
         }
 
         Ok(())
@@ -492,6 +520,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         dbg::basic_block_start!(bb, infcx, env);
 
         self.visited.insert(bb);
+        if let Some(trace) = &mut self.trace {
+            trace.enter_basic_block(bb.as_u32());
+        }
         let data = &self.body.basic_blocks[bb];
         let mut last_stmt_span = None;
         let mut location = Location { block: bb, statement_index: 0 };
@@ -504,9 +535,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 span,
             )?;
             bug::track_span(span, || {
-                dbg::statement!("start", stmt, &infcx, &env, span, &self);
+                dbg::statement!("start", stmt, &infcx, &env, span, self);
                 self.check_statement(&mut infcx, &mut env, stmt)?;
-                dbg::statement!("end", stmt, &infcx, &env, span, &self);
+                dbg::statement!("end", stmt, &infcx, &env, span, self);
                 Ok(())
             })?;
             if !stmt.is_nop() {
@@ -680,8 +711,15 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     }
                 };
 
-                let ret = infcx.unpack(&ret);
-                infcx.assume_invariants(&ret, self.check_overflow());
+                let callee = match kind {
+                    mir::CallKind::FnDef { resolved_id, .. } => Some(*resolved_id),
+                    mir::CallKind::FnPtr { .. } => None,
+                };
+                let ret = infcx.with_origin(Origin::Call(callee, terminator_span), |infcx| {
+                    let ret = infcx.unpack(&ret);
+                    infcx.assume_invariants(&ret, self.check_overflow());
+                    ret
+                });
 
                 env.assign(&mut infcx.at(terminator_span), destination, ret)
                     .with_span(terminator_span)?;
@@ -785,7 +823,6 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             .at(span)
             .check_non_closure_clauses(&clauses, ConstrReason::Call)
             .with_span(span)?;
-        self.check_closure_clauses(infcx, &fn_clauses, span)?;
 
         // Instantiate function signature and normalize it
         let fn_sig = fn_sig
@@ -811,18 +848,28 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         env.replace_evars(&evars_sol);
         infcx.replace_evars(&evars_sol);
 
-        let output = fn_sig
-            .output()
-            .replace_evars(&evars_sol)
-            .replace_bound_refts_with(|sort, _, _| infcx.define_vars(sort));
+        // Closures are checked after solving evars because `Fn` bounds may mention refinement
+        // parameters of the callee which are inferred from the arguments.
+        let fn_clauses = fn_clauses
+            .iter()
+            .map(|clause| clause.replace_evars(&evars_sol))
+            .collect_vec();
+        self.check_closure_clauses(infcx, &fn_clauses, span)?;
 
-        infcx.push_scope();
-        env.update_ensures(infcx, &output, self.check_overflow());
-        fold_local_ptrs(infcx, env, span).with_span(span)?;
-        let evars_sol = infcx.pop_scope().with_span(span)?;
-        infcx.replace_evars(&evars_sol);
+        infcx.with_origin(Origin::Call(callee_def_id, span), |infcx| {
+            let output = fn_sig
+                .output()
+                .replace_evars(&evars_sol)
+                .replace_bound_refts_with(|sort, _, _| infcx.define_vars(sort));
 
-        Ok(output.ret)
+            infcx.push_scope();
+            env.update_ensures(infcx, &output, self.check_overflow());
+            fold_local_ptrs(infcx, env, span).with_span(span)?;
+            let evars_sol = infcx.pop_scope().with_span(span)?;
+            infcx.replace_evars(&evars_sol);
+
+            Ok(output.ret)
+        })
     }
 
     fn check_coroutine_obligations(
@@ -1008,21 +1055,24 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         for (target, guard) in successors {
             let mut infcx = infcx.branch();
             let mut env = env.clone();
-            match guard {
-                Guard::None => {}
-                Guard::Pred(expr) => {
-                    infcx.assume_pred(&expr);
-                }
-                Guard::Match(place, variant_idx) => {
-                    env.downcast(
-                        &mut infcx.at(terminator_span),
-                        &place,
-                        variant_idx,
-                        self.config(),
-                    )
-                    .with_span(terminator_span)?;
+            infcx.with_origin(Origin::Guard(terminator_span), |infcx| -> Result {
+                match guard {
+                    Guard::None => {}
+                    Guard::Pred(expr) => {
+                        infcx.assume_pred(&expr);
+                    }
+                    Guard::Match(place, variant_idx) => {
+                        env.downcast(
+                            &mut infcx.at(terminator_span),
+                            &place,
+                            variant_idx,
+                            self.config(),
+                        )
+                        .with_span(terminator_span)?;
+                    }
                 }
-            }
+                Ok(())
+            })?;
             self.check_ghost_statements_at(
                 &mut infcx,
                 &mut env,
@@ -1401,12 +1451,31 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             src.clone()
         };
 
-        if let ty::TyKind::Ref(_, deref_ty, _) = dst.kind()
+        if let ty::TyKind::Ref(re, deref_ty, mutbl) = dst.kind()
             && let ty::TyKind::Dynamic(..) = deref_ty.kind()
         {
+            // `&T -> &dyn Trait` or `&mut T -> &mut dyn Trait`. As for `Box<T> -> Box<dyn Trait>`,
+            // the trait object keeps the values of the associated refinements of `T`. This is sound
+            // for mutable references because they are invariant: a method can only mutate the value
+            // through a `&mut dyn Trait` if it preserves its index.
+            if let TyKind::Indexed(BaseTy::Ref(_, src_deref, _), _) = infcx.unpack(&src).kind() {
+                let dyn_ty = self.trait_object_ty(infcx, src_deref, deref_ty)?;
+                return Ok(Ty::mk_ref(*re, dyn_ty, *mutbl));
+            }
             return Ok(self.refine_default(dst)?);
         }
 
+        // `Box<T> -> Box<dyn Trait>`
+        if let ty::TyKind::Adt(adt_def, args) = dst.kind()
+            && adt_def.is_box()
+            && let ty::TyKind::Dynamic(..) = args.box_args().0.kind()
+            && let TyKind::Indexed(BaseTy::Adt(_, src_args), _) = infcx.unpack(&src).kind()
+        {
+            let (src_deref, alloc_ty) = src_args.box_args();
+            let dyn_ty = self.trait_object_ty(infcx, src_deref, args.box_args().0)?;
+            return Ok(Ty::mk_box(self.genv, dyn_ty, alloc_ty.clone())?);
+        }
+
         // `&mut [T; n] -> &mut [T]` or `&[T; n] -> &[T]`
         if let TyKind::Indexed(BaseTy::Ref(_, deref_ty, _), _) = src.kind()
             && let TyKind::Indexed(BaseTy::Array(arr_ty, arr_len), _) = deref_ty.kind()
@@ -1432,6 +1501,63 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         }
     }
 
+    /// Returns the type of the trait object `dst` created from a value of type `src`. The trait
+    /// object is indexed by the values of the associated refinements of the principal trait for
+    /// `src`. If `src` is not indexed we return the default refinement of `dst`.
+    fn trait_object_ty(
+        &self,
+        infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
+        src: &Ty,
+        dst: &ty::Ty,
+    ) -> InferResult<Ty> {
+        let dyn_ty = self.refine_default(dst)?;
+        let Some(dyn_bty @ BaseTy::Dynamic(preds, _, Some(sort_def))) =
+            dyn_ty.as_bty_skipping_existentials()
+        else {
+            return Ok(dyn_ty);
+        };
+        let TyKind::Indexed(src_bty, src_idx) = src.kind() else { return Ok(dyn_ty) };
+
+        let trait_id = sort_def.did();
+        let self_arg = match self
+            .genv
+            .generics_of(trait_id)?
+            .param_at(0, self.genv)?
+            .kind
+        {
+            rty::GenericParamDefKind::Base { .. } => GenericArg::Base(src_bty.to_subset_ty_ctor()),
+            _ => GenericArg::Ty(src.clone()),
+        };
+        let principal_args = preds
+            .iter()
+            .find_map(|pred| {
+                if let rty::ExistentialPredicate::Trait(trait_ref) = pred.skip_binder_ref() {
+                    Some(trait_ref.args.clone())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let args: GenericArgs = iter::once(self_arg)
+            .chain(principal_args.iter().cloned())
+            .collect();
+
+        let flds = sort_def
+            .field_names()
+            .iter()
+            .map(|name| {
+                let alias_reft = rty::AliasReft { trait_id, name: *name, args: args.clone() };
+                Expr::alias(alias_reft, rty::List::singleton(src_idx.clone()))
+            })
+            .collect();
+        let idx = Expr::adt(trait_id, flds).normalize_projections(
+            self.genv,
+            infcx.region_infcx,
+            infcx.def_id,
+        )?;
+        Ok(Ty::indexed(dyn_bty.clone(), idx))
+    }
+
     fn check_operands(
         &mut self,
         infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
@@ -1476,11 +1602,24 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 let idx = Expr::constant(rty::Constant::from(*b));
                 Ok(Ty::indexed(BaseTy::Bool, idx))
             }
-            Constant::Float(_, float_ty) => Ok(Ty::float(*float_ty)),
+            Constant::Float(bits, float_ty) => {
+                // Infinities and NaN are not real numbers so we leave them unrefined
+                let Some(r) = rty::Real::from_float_bits(*bits, *float_ty) else {
+                    return Ok(Ty::float(*float_ty));
+                };
+                let idx = Expr::constant(rty::Constant::from(r));
+                Ok(Ty::indexed(BaseTy::Float(*float_ty), idx))
+            }
             Constant::Unit => Ok(Ty::unit()),
             Constant::Str(s) => {
-                let idx = Expr::constant(rty::Constant::from(*s));
-                Ok(Ty::mk_ref(ReStatic, Ty::indexed(BaseTy::Str, idx), Mutability::Not))
+                // Strings are refined by their bytes, but SMT strings are sequences of characters,
+                // so we only index literals whose characters are all single bytes.
+                let ty = if s.as_str().is_ascii() {
+                    Ty::indexed(BaseTy::Str, Expr::constant(rty::Constant::from(*s)))
+                } else {
+                    Ty::str()
+                };
+                Ok(Ty::mk_ref(ReStatic, ty, Mutability::Not))
             }
             Constant::Char(c) => {
                 let idx = Expr::constant(rty::Constant::from(*c));
@@ -1517,7 +1656,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         stmt: &GhostStatement,
         span: Span,
     ) -> Result {
-        dbg::statement!("start", stmt, infcx, env, span, &self);
+        dbg::statement!("start", stmt, infcx, env, span, self);
         match stmt {
             GhostStatement::Fold(place) => {
                 env.fold(&mut infcx.at(span), place).with_span(span)?;
@@ -1532,7 +1671,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     .with_span(span)?;
             }
         }
-        dbg::statement!("end", stmt, infcx, env, span, &self);
+        dbg::statement!("end", stmt, infcx, env, span, self);
         Ok(())
     }
 
@@ -1748,7 +1887,7 @@ impl Mode for ShapeMode {
 
     fn check_goto_join_point<'genv, 'tcx>(
         ck: &mut Checker<'_, 'genv, 'tcx, ShapeMode>,
-        _: InferCtxt<'_, 'genv, 'tcx>,
+        mut infcx: InferCtxt<'_, 'genv, 'tcx>,
         env: TypeEnv,
         terminator_span: Span,
         target: BasicBlock,
@@ -1758,7 +1897,12 @@ impl Mode for ShapeMode {
         dbg::shape_goto_enter!(target, env, target_bb_env);
 
         let modified = match bb_envs.entry(ck.def_id).or_default().entry(target) {
-            Entry::Occupied(mut entry) => entry.get_mut().join(env).with_span(terminator_span)?,
+            Entry::Occupied(mut entry) => {
+                entry
+                    .get_mut()
+                    .join(&mut infcx.at(terminator_span), env)
+                    .with_span(terminator_span)?
+            }
             Entry::Vacant(entry) => {
                 let scope = snapshot_at_dominator(ck.body, &ck.snapshots, target)
                     .scope()
@@ -1904,7 +2048,7 @@ fn snapshot_at_dominator<'a>(
 }
 
 pub(crate) mod errors {
-    use flux_errors::{ErrorGuaranteed, E0999};
+    use flux_errors::{ErrorGuaranteed, E9012, E9013};
     use flux_infer::infer::InferErr;
     use flux_middle::{def_id_to_string, global_env::GlobalEnv, queries::QueryErr, MaybeExternId};
     use rustc_errors::Diagnostic;
@@ -1930,7 +2074,7 @@ pub(crate) mod errors {
                 CheckerErrKind::Inference => {
                     let mut diag =
                         dcx.struct_span_err(self.span, fluent::refineck_param_inference_error);
-                    diag.code(E0999);
+                    diag.code(E9012);
                     diag.emit()
                 }
                 CheckerErrKind::OpaqueStruct(def_id) => {
@@ -1940,7 +2084,7 @@ pub(crate) mod errors {
                     diag.span_help(fn_span, fluent::refineck_opaque_struct_help);
                     diag.note(fluent::refineck_opaque_struct_note);
                     diag.arg("struct", def_id_to_string(def_id));
-                    diag.code(E0999);
+                    diag.code(E9013);
                     diag.emit()
                 }
                 CheckerErrKind::Query(err) => {
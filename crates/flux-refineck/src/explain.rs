@@ -0,0 +1,117 @@
+//! Explanations of refinement type errors enabled with `#[flux::explain]` or the `explain` setting.
+//! An explanation lists the path condition of the constraint that cannot be proven, i.e., the
+//! variables and assumptions in scope when checking it, grouped by where they come from: the inputs
+//! of the function, the guards of the branches taken and the outputs of the calls made along the
+//! way. The variables in the indices of the arguments of the function are printed with the names of
+//! the arguments.
+use flux_infer::{
+    infer::Tag,
+    refine_tree::{Origin, PathCondition, PathStep, RefineTree},
+};
+use flux_middle::{
+    global_env::GlobalEnv,
+    pretty::{PrettyCx, WithCx},
+    rty::{Sort, Var},
+};
+use itertools::Itertools;
+use rustc_errors::{Diag, EmissionGuarantee};
+use rustc_span::Span;
+
+pub(crate) struct Explanation {
+    notes: Vec<(Option<Span>, String)>,
+}
+
+impl Explanation {
+    pub(crate) fn new(genv: GlobalEnv, refine_tree: &RefineTree, tag: Tag) -> Explanation {
+        let paths = refine_tree.path_conditions(tag);
+        let mut notes = vec![];
+        for (i, path) in paths.iter().enumerate() {
+            if paths.len() > 1 {
+                notes.push((None, format!("along path {} of {}", i + 1, paths.len())));
+            }
+            explain_path(genv, path, &mut notes);
+        }
+        Explanation { notes }
+    }
+
+    pub(crate) fn add_to_diag<G: EmissionGuarantee>(self, diag: &mut Diag<G>) {
+        for (span, note) in self.notes {
+            match span {
+                Some(span) => diag.span_note(span, note),
+                None => diag.note(note),
+            };
+        }
+    }
+}
+
+fn explain_path(genv: GlobalEnv, path: &PathCondition, notes: &mut Vec<(Option<Span>, String)>) {
+    let free_var_names = path
+        .steps
+        .iter()
+        .filter_map(|(step, _)| {
+            match step {
+                PathStep::Bind(name, _, Some(symbol)) => Some((*name, *symbol)),
+                _ => None,
+            }
+        })
+        .collect();
+    let cx = PrettyCx::default_with_genv(genv).free_var_names(free_var_names);
+
+    let params = path
+        .params
+        .iter()
+        .filter(|(_, sort)| !sort.is_loc())
+        .map(|(var, sort)| format!("{:?}: {sort:?}", WithCx::new(&cx, var)))
+        .collect_vec();
+    if !params.is_empty() {
+        notes.push((None, format!("for the refinement parameters {}", params.join(", "))));
+    }
+
+    let groups = path.steps.iter().chunk_by(|(_, origin)| *origin);
+    for (origin, steps) in &groups {
+        let mut has_assumptions = false;
+        let items = steps
+            .flat_map(|(step, _)| {
+                match step {
+                    PathStep::Bind(_, Sort::Loc, _) => vec![],
+                    PathStep::Bind(name, sort, _) => {
+                        vec![format!("{:?}: {sort:?}", WithCx::new(&cx, Var::Free(*name)))]
+                    }
+                    PathStep::Assume(pred) => {
+                        pred.flatten_conjs()
+                            .into_iter()
+                            .filter(|pred| !pred.is_trivially_true())
+                            .map(|pred| {
+                                has_assumptions = true;
+                                format!("{:?}", WithCx::new(&cx, pred))
+                            })
+                            .collect()
+                    }
+                }
+            })
+            .collect_vec();
+        // Variables without assumptions are only worth mentioning if we know where they come from
+        if items.is_empty() || (origin.is_none() && !has_assumptions) {
+            continue;
+        }
+        let items = items.join(", ");
+        let note = match origin {
+            Some(Origin::FnInputs(span)) => {
+                (Some(span), format!("from the inputs of the function: {items}"))
+            }
+            Some(Origin::Guard(span)) => {
+                (Some(span), format!("from the branch taken here: {items}"))
+            }
+            Some(Origin::Call(Some(callee), span)) => {
+                let callee = genv.tcx().def_path_str(callee);
+                (Some(span), format!("from the output of the call to `{callee}`: {items}"))
+            }
+            Some(Origin::Call(None, span)) => {
+                (Some(span), format!("from the output of this call: {items}"))
+            }
+            None => (None, format!("assuming {items}")),
+        };
+        notes.push(note);
+    }
+    notes.push((None, format!("cannot prove {:?}", WithCx::new(&cx, &path.head))));
+}
@@ -1,4 +1,5 @@
 use flux_common::{iter::IterExt, result::ResultExt};
+use flux_config::Lint;
 use flux_errors::ErrorGuaranteed;
 use flux_infer::{
     fixpoint_encoding::{FixQueryCache, KVarGen},
@@ -8,7 +9,7 @@ use flux_infer::{
 use flux_middle::{fhir, global_env::GlobalEnv, rty, MaybeExternId};
 use rustc_span::{Span, DUMMY_SP};
 
-use crate::{invoke_fixpoint, CheckerConfig};
+use crate::{emit_lint, invoke_fixpoint, CheckerConfig};
 
 pub fn check_invariants(
     genv: GlobalEnv,
@@ -60,7 +61,7 @@ fn check_invariant(
         genv,
         cache,
         def_id,
-        refine_tree,
+        &mut refine_tree,
         KVarGen::dummy(),
         checker_config,
         "fluxc",
@@ -68,19 +69,20 @@ fn check_invariant(
     .emit(&genv)?;
 
     if errors.is_empty() {
-        Ok(())
-    } else {
-        Err(genv.sess().emit_err(errors::Invalid { span }))
+        return Ok(());
+    }
+    match emit_lint(genv, def_id.local_id(), Lint::Invariant, errors::Invalid { span }, None) {
+        Some(err) => Err(err),
+        None => Ok(()),
     }
 }
 
 mod errors {
-    use flux_errors::E0999;
     use flux_macros::Diagnostic;
     use rustc_span::Span;
 
     #[derive(Diagnostic)]
-    #[diag(refineck_invalid_invariant, code = E0999)]
+    #[diag(refineck_invalid_invariant)]
     pub struct Invalid {
         #[primary_span]
         pub span: Span,
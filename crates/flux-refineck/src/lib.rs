@@ -25,6 +25,7 @@ extern crate rustc_target;
 extern crate rustc_type_ir;
 
 mod checker;
+mod explain;
 mod ghost_statements;
 pub mod invariants;
 mod primops;
@@ -34,25 +35,23 @@ mod type_env;
 pub use checker::CheckerConfig;
 use checker::{trait_impl_subtyping, Checker};
 use flux_common::{dbg, result::ResultExt as _};
-use flux_config as config;
+use flux_config::{self as config, Lint, LintLevel};
+use flux_errors::{E9001, E9002, E9003, E9004, E9005, E9006, E9007, E9008, E9009, E9010};
 use flux_infer::{
     fixpoint_encoding::{FixQueryCache, FixpointCtxt, KVarGen},
     infer::{ConstrReason, SubtypeReason, Tag},
     refine_tree::RefineTree,
 };
 use flux_macros::fluent_messages;
-use flux_middle::{
-    global_env::GlobalEnv,
-    queries::QueryResult,
-    rty::{self, ESpan},
-    MaybeExternId,
-};
+use flux_middle::{global_env::GlobalEnv, queries::QueryResult, rty, MaybeExternId};
 use itertools::Itertools;
-use rustc_errors::ErrorGuaranteed;
+use rustc_errors::{Diagnostic, ErrCode, ErrorGuaranteed};
 use rustc_hir::def_id::LocalDefId;
-use rustc_span::Span;
 
-use crate::{checker::errors::ResultExt as _, ghost_statements::compute_ghost_statements};
+use crate::{
+    checker::errors::ResultExt as _, explain::Explanation,
+    ghost_statements::compute_ghost_statements,
+};
 
 fluent_messages! { "../locales/en-US.ftl" }
 
@@ -60,6 +59,7 @@ fn report_fixpoint_errors(
     genv: GlobalEnv,
     local_id: LocalDefId,
     errors: Vec<Tag>,
+    refine_tree: &RefineTree,
 ) -> Result<(), ErrorGuaranteed> {
     #[expect(clippy::collapsible_else_if, reason = "it looks better")]
     if genv.should_fail(local_id) {
@@ -72,7 +72,7 @@ fn report_fixpoint_errors(
         if errors.is_empty() {
             Ok(())
         } else {
-            report_errors(genv, errors)
+            report_errors(genv, local_id, errors, refine_tree)
         }
     }
 }
@@ -81,22 +81,22 @@ fn invoke_fixpoint(
     genv: GlobalEnv,
     cache: &mut FixQueryCache,
     def_id: MaybeExternId,
-    mut refine_tree: RefineTree,
+    refine_tree: &mut RefineTree,
     kvars: KVarGen,
     config: CheckerConfig,
     ext: &str,
 ) -> QueryResult<Vec<Tag>> {
     if config::dump_constraint() {
-        dbg::dump_item_info(genv.tcx(), def_id.resolved_id(), ext, &refine_tree).unwrap();
+        dbg::dump_item_info(genv.tcx(), def_id.resolved_id(), ext, &*refine_tree).unwrap();
     }
     refine_tree.simplify(genv.spec_func_defns()?);
     let simp_ext = format!("simp.{}", ext);
     if config::dump_constraint() {
-        dbg::dump_item_info(genv.tcx(), def_id.resolved_id(), simp_ext, &refine_tree).unwrap();
+        dbg::dump_item_info(genv.tcx(), def_id.resolved_id(), simp_ext, &*refine_tree).unwrap();
     }
 
     let mut fcx = FixpointCtxt::new(genv, def_id, kvars);
-    let cstr = refine_tree.into_fixpoint(&mut fcx)?;
+    let cstr = refine_tree.to_fixpoint(&mut fcx)?;
     fcx.check(cache, cstr, config.scrape_quals)
 }
 
@@ -108,14 +108,6 @@ pub fn check_fn(
 ) -> Result<(), ErrorGuaranteed> {
     let span = genv.tcx().def_span(def_id);
 
-    // HACK(nilehmann) this will ignore any code generated by a macro. This is a temporary
-    // workaround to deal with a `#[derive(..)]` that generates code that flux cannot handle.
-    // Note that this is required because code generated by a `#[derive(..)]` cannot be
-    // manually trusted or ignored.
-    if !genv.tcx().def_span(def_id).ctxt().is_root() {
-        return Ok(());
-    }
-
     // Make sure we run conversion and report errors even if we skip the function for any of
     // the reasons below
     force_conv(genv, def_id).emit(&genv)?;
@@ -133,6 +125,14 @@ pub fn check_fn(
         return Ok(());
     }
 
+    // Skip derived functions with a built-in signature. The generated body is trusted.
+    if rty::derive::derived_eq_enum(genv, local_id)
+        .emit(&genv)?
+        .is_some()
+    {
+        return Ok(());
+    }
+
     // Since we still want the global check overflow, just override it here if it's set
     if let Some(check_overflow) = genv.check_overflow(local_id) {
         if check_overflow {
@@ -155,29 +155,29 @@ pub fn check_fn(
         tracing::info!("check_fn::shape");
 
         // PHASE 2: generate refinement tree constraint
-        let (refine_tree, kvars) =
+        let (mut refine_tree, kvars) =
             Checker::run_in_refine_mode(genv, local_id, &ghost_stmts, shape_result, config)
                 .map_err(|err| err.emit(genv, def_id))?;
         tracing::info!("check_fn::refine");
 
         // PHASE 3: invoke fixpoint on the constraint
-        let errors = invoke_fixpoint(genv, cache, def_id, refine_tree, kvars, config, "fluxc")
+        let errors = invoke_fixpoint(genv, cache, def_id, &mut refine_tree, kvars, config, "fluxc")
             .emit(&genv)?;
         tracing::info!("check_fn::fixpoint");
-        report_fixpoint_errors(genv, local_id, errors)?;
+        report_fixpoint_errors(genv, local_id, errors, &refine_tree)?;
 
         // PHASE 4: subtyping check for trait-method implementations
-        if let Some((refine_tree, kvars)) =
+        if let Some((mut refine_tree, kvars)) =
             trait_impl_subtyping(genv, local_id, config.check_overflow, span)
                 .with_span(span)
                 .map_err(|err| err.emit(genv, def_id))?
         {
             tracing::info!("check_fn::refine-subtyping");
             let errors =
-                invoke_fixpoint(genv, cache, def_id, refine_tree, kvars, config, "sub.fluxc")
+                invoke_fixpoint(genv, cache, def_id, &mut refine_tree, kvars, config, "sub.fluxc")
                     .emit(&genv)?;
             tracing::info!("check_fn::fixpoint-subtyping");
-            report_fixpoint_errors(genv, local_id, errors)?;
+            report_fixpoint_errors(genv, local_id, errors, &refine_tree)?;
         }
         Ok(())
     })?;
@@ -193,40 +193,87 @@ fn force_conv(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult {
     Ok(())
 }
 
-fn call_error(genv: GlobalEnv, span: Span, dst_span: Option<ESpan>) -> ErrorGuaranteed {
-    genv.sess()
-        .emit_err(errors::RefineError::call(span, dst_span))
-}
-
-fn ret_error(genv: GlobalEnv, span: Span, dst_span: Option<ESpan>) -> ErrorGuaranteed {
-    genv.sess()
-        .emit_err(errors::RefineError::ret(span, dst_span))
-}
-
-fn report_errors(genv: GlobalEnv, errors: Vec<Tag>) -> Result<(), ErrorGuaranteed> {
+fn report_errors(
+    genv: GlobalEnv,
+    local_id: LocalDefId,
+    errors: Vec<Tag>,
+    refine_tree: &RefineTree,
+) -> Result<(), ErrorGuaranteed> {
     let mut e = None;
     for err in errors {
         let span = err.src_span;
-        e = Some(match err.reason {
+        let explanation = genv
+            .explain(local_id)
+            .then(|| Explanation::new(genv, refine_tree, err));
+        let reported = match err.reason {
             ConstrReason::Call
             | ConstrReason::Subtype(SubtypeReason::Input)
             | ConstrReason::Subtype(SubtypeReason::Requires) => {
-                call_error(genv, span, err.dst_span)
+                let err = errors::RefineError::call(span, err.dst_span);
+                emit_lint(genv, local_id, Lint::Precondition, err, explanation)
+            }
+            ConstrReason::Assign => {
+                emit_lint(
+                    genv,
+                    local_id,
+                    Lint::Assignment,
+                    errors::AssignError { span },
+                    explanation,
+                )
             }
-            ConstrReason::Assign => genv.sess().emit_err(errors::AssignError { span }),
             ConstrReason::Ret
             | ConstrReason::Subtype(SubtypeReason::Output)
-            | ConstrReason::Subtype(SubtypeReason::Ensures) => ret_error(genv, span, err.dst_span),
-            ConstrReason::Div => genv.sess().emit_err(errors::DivError { span }),
-            ConstrReason::Rem => genv.sess().emit_err(errors::RemError { span }),
-            ConstrReason::Goto(_) => genv.sess().emit_err(errors::GotoError { span }),
-            ConstrReason::Assert(msg) => genv.sess().emit_err(errors::AssertError { span, msg }),
+            | ConstrReason::Subtype(SubtypeReason::Ensures) => {
+                let err = errors::RefineError::ret(span, err.dst_span);
+                emit_lint(genv, local_id, Lint::Postcondition, err, explanation)
+            }
+            ConstrReason::Div => {
+                emit_lint(
+                    genv,
+                    local_id,
+                    Lint::DivisionByZero,
+                    errors::DivError { span },
+                    explanation,
+                )
+            }
+            ConstrReason::Rem => {
+                emit_lint(
+                    genv,
+                    local_id,
+                    Lint::DivisionByZero,
+                    errors::RemError { span },
+                    explanation,
+                )
+            }
+            ConstrReason::Goto(_) => {
+                emit_lint(genv, local_id, Lint::JoinPoint, errors::GotoError { span }, explanation)
+            }
+            ConstrReason::Assert(msg) => {
+                emit_lint(
+                    genv,
+                    local_id,
+                    Lint::Assertion,
+                    errors::AssertError { span, msg },
+                    explanation,
+                )
+            }
             ConstrReason::Fold | ConstrReason::FoldLocal => {
-                genv.sess().emit_err(errors::FoldError { span })
+                emit_lint(genv, local_id, Lint::Fold, errors::FoldError { span }, explanation)
             }
-            ConstrReason::Overflow => genv.sess().emit_err(errors::OverflowError { span }),
-            ConstrReason::Other => genv.sess().emit_err(errors::UnknownError { span }),
-        });
+            ConstrReason::Overflow => {
+                emit_lint(
+                    genv,
+                    local_id,
+                    Lint::Overflow,
+                    errors::OverflowError { span },
+                    explanation,
+                )
+            }
+            ConstrReason::Other => {
+                emit_lint(genv, local_id, Lint::Unknown, errors::UnknownError { span }, explanation)
+            }
+        };
+        e = reported.or(e);
     }
 
     if let Some(e) = e {
@@ -236,6 +283,59 @@ fn report_errors(genv: GlobalEnv, errors: Vec<Tag>) -> Result<(), ErrorGuarantee
     }
 }
 
+/// Emits a refinement type `err` according to the level of `lint` for `local_id`. Returns an
+/// [`ErrorGuaranteed`] if the error was emitted as an error, i.e., if the lint is denied. The notes
+/// of the `explanation` are attached to the error if given.
+fn emit_lint<'a, D>(
+    genv: GlobalEnv<'a, '_>,
+    local_id: LocalDefId,
+    lint: Lint,
+    err: D,
+    explanation: Option<Explanation>,
+) -> Option<ErrorGuaranteed>
+where
+    D: Diagnostic<'a> + Diagnostic<'a, ()>,
+{
+    let dcx = genv.sess().dcx().handle();
+    let code = lint_code(lint);
+    match genv.lint_level(local_id, lint) {
+        LintLevel::Allow => None,
+        LintLevel::Warn => {
+            let mut diag = dcx.create_warn(err);
+            diag.code(code);
+            diag.note(format!("the flux lint `{lint}` is set to `warn`"));
+            if let Some(explanation) = explanation {
+                explanation.add_to_diag(&mut diag);
+            }
+            diag.emit();
+            None
+        }
+        LintLevel::Deny => {
+            let mut diag = dcx.create_err(err);
+            diag.code(code);
+            if let Some(explanation) = explanation {
+                explanation.add_to_diag(&mut diag);
+            }
+            Some(diag.emit())
+        }
+    }
+}
+
+fn lint_code(lint: Lint) -> ErrCode {
+    match lint {
+        Lint::Precondition => E9001,
+        Lint::Postcondition => E9002,
+        Lint::Assignment => E9003,
+        Lint::DivisionByZero => E9004,
+        Lint::Assertion => E9005,
+        Lint::Fold => E9006,
+        Lint::JoinPoint => E9007,
+        Lint::Overflow => E9008,
+        Lint::Unknown => E9009,
+        Lint::Invariant => E9010,
+    }
+}
+
 fn report_expected_neg(genv: GlobalEnv, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
     Err(genv.sess().emit_err(errors::ExpectedNeg {
         span: genv.tcx().def_span(def_id),
@@ -243,21 +343,25 @@ fn report_expected_neg(genv: GlobalEnv, def_id: LocalDefId) -> Result<(), ErrorG
     }))
 }
 
+/// The code of refinement type errors is set when emitting them because it depends on the lint
+/// (see [`emit_lint`]).
 mod errors {
-    use flux_errors::E0999;
+    use std::{io, path::Path};
+
+    use flux_errors::{E9011, E9080};
     use flux_macros::{Diagnostic, Subdiagnostic};
     use flux_middle::rty::ESpan;
     use rustc_span::Span;
 
     #[derive(Diagnostic)]
-    #[diag(refineck_goto_error, code = E0999)]
+    #[diag(refineck_goto_error)]
     pub struct GotoError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_assign_error, code = E0999)]
+    #[diag(refineck_assign_error)]
     pub struct AssignError {
         #[primary_span]
         pub span: Span,
@@ -278,7 +382,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_refine_error, code = E0999)]
+    #[diag(refineck_refine_error)]
     pub struct RefineError {
         #[primary_span]
         #[label]
@@ -312,21 +416,21 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_div_error, code = E0999)]
+    #[diag(refineck_div_error)]
     pub struct DivError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_rem_error, code = E0999)]
+    #[diag(refineck_rem_error)]
     pub struct RemError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_assert_error, code = E0999)]
+    #[diag(refineck_assert_error)]
     pub struct AssertError {
         #[primary_span]
         pub span: Span,
@@ -334,31 +438,46 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_fold_error, code = E0999)]
+    #[diag(refineck_fold_error)]
     pub struct FoldError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_overflow_error, code = E0999)]
+    #[diag(refineck_overflow_error)]
     pub struct OverflowError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_unknown_error, code = E0999)]
+    #[diag(refineck_unknown_error)]
     pub struct UnknownError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_expected_neg, code = E0999)]
+    #[diag(refineck_expected_neg, code = E9011)]
     pub struct ExpectedNeg {
         #[primary_span]
         pub span: Span,
         pub def_descr: &'static str,
     }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_cannot_write_trace, code = E9080)]
+    pub struct CannotWriteTrace {
+        #[primary_span]
+        span: Span,
+        dir: &'static Path,
+        err: io::Error,
+    }
+
+    impl CannotWriteTrace {
+        pub fn new(span: Span, err: io::Error) -> Self {
+            Self { span, dir: flux_config::log_dir(), err }
+        }
+    }
 }
@@ -30,7 +30,10 @@ use flux_infer::infer::ConstrReason;
 use flux_macros::primop_rules;
 use flux_middle::rty::{self, BaseTy, Expr};
 use flux_rustc_bridge::mir;
-use rty::{BinOp::Mod, Expr as E};
+use rty::{
+    BinOp::{Ge, Gt, Le, Lt, Mod},
+    Expr as E, Sort,
+};
 use rustc_data_structures::unord::UnordMap;
 
 pub(crate) struct MatchedRule {
@@ -270,6 +273,9 @@ fn mk_rem_rules() -> RuleMatcher<2> {
                                    E::eq(v, E::binary_op(Mod, a, b))) }
         requires E::ne(b, 0) => ConstrReason::Rem
         if T.is_signed()
+
+        fn(a: T, b: T) -> T
+        if T.is_float()
     }
 }
 
@@ -307,6 +313,11 @@ fn mk_eq_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::eq(a, b)]
         if T.is_integral() || T.is_bool() || T.is_char() || T.is_str()
 
+        // A comparison involving NaN is false, so we only learn something about the indices of
+        // floats when the comparison holds
+        fn(a: T, b: T) -> bool{v: E::implies(v, E::eq(a, b))}
+        if T.is_float()
+
         fn(a: T, b: T) -> bool
     }
 }
@@ -317,6 +328,10 @@ fn mk_ne_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::ne(a, b)]
         if T.is_integral() || T.is_bool()
 
+        // `NaN != NaN` holds, so we only learn something when the comparison fails
+        fn(a: T, b: T) -> bool{v: E::implies(v.not(), E::eq(a, b))}
+        if T.is_float()
+
         fn(a: T, b: T) -> bool
     }
 }
@@ -327,6 +342,9 @@ fn mk_le_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::le(a, b)]
         if T.is_integral()
 
+        fn(a: T, b: T) -> bool{v: E::implies(v, E::binary_op(Le(Sort::Real), a, b))}
+        if T.is_float()
+
         fn(a: bool, b: bool) -> bool[E::implies(a, b)]
 
         fn(a: T, b: T) -> bool
@@ -339,6 +357,9 @@ fn mk_ge_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::ge(a, b)]
         if T.is_integral()
 
+        fn(a: T, b: T) -> bool{v: E::implies(v, E::binary_op(Ge(Sort::Real), a, b))}
+        if T.is_float()
+
         fn(a: bool, b: bool) -> bool[E::implies(b, a)]
 
         fn(a: T, b: T) -> bool
@@ -351,6 +372,9 @@ fn mk_lt_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::lt(a, b)]
         if T.is_integral()
 
+        fn(a: T, b: T) -> bool{v: E::implies(v, E::binary_op(Lt(Sort::Real), a, b))}
+        if T.is_float()
+
         fn(a: bool, b: bool) -> bool[E::and(a.not(), b)]
 
         fn(a: T, b: T) -> bool
@@ -363,6 +387,9 @@ fn mk_gt_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::gt(a, b)]
         if T.is_integral()
 
+        fn(a: T, b: T) -> bool{v: E::implies(v, E::binary_op(Gt(Sort::Real), a, b))}
+        if T.is_float()
+
         fn(a: bool, b: bool) -> bool[E::and(a, b.not())]
 
         fn(a: T, b: T) -> bool
@@ -399,10 +426,7 @@ fn mk_neg_rules(check_overflow: bool) -> RuleMatcher<1> {
     } else {
         primop_rules! {
             fn(a: T) -> T[a.neg()]
-            if T.is_integral()
-
-            fn(a: T) -> T
-            if T.is_float()
+            if T.is_numeric()
         }
     }
 }
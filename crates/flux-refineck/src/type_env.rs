@@ -2,13 +2,17 @@ mod place_ty;
 
 use std::{iter, ops::ControlFlow};
 
-use flux_common::{bug, dbg::debug_assert_eq3, tracked_span_bug, tracked_span_dbg_assert_eq};
+use flux_common::{
+    bug,
+    dbg::debug_assert_eq3,
+    trace::{LocInfo, TypeEnvBind, TypeEnvTrace},
+    tracked_span_bug, tracked_span_dbg_assert_eq,
+};
 use flux_infer::{
     fixpoint_encoding::{KVarEncoding, KVarGen},
     infer::{ConstrReason, InferCtxt, InferCtxtAt, InferResult},
     refine_tree::{AssumeInvariants, RefineCtxt, Scope},
 };
-use flux_macros::DebugAsJson;
 use flux_middle::{
     global_env::GlobalEnv,
     pretty::{PrettyCx, PrettyNested},
@@ -18,9 +22,9 @@ use flux_middle::{
         evars::EVarSol,
         fold::{FallibleTypeFolder, TypeFoldable, TypeVisitable, TypeVisitor},
         region_matching::{rty_match_regions, ty_match_regions},
-        BaseTy, Binder, BoundReftKind, Ensures, Expr, ExprKind, FnOutput, FnSig, GenericArg,
-        HoleKind, Lambda, List, Loc, Mutability, Path, PtrKind, Region, SortCtor, SubsetTy, Ty,
-        TyKind, VariantIdx, INNERMOST,
+        AggregateKind, BaseTy, Binder, BoundReftKind, Ensures, Expr, ExprKind, FnOutput, FnSig,
+        GenericArg, HoleKind, Lambda, List, Loc, Mutability, Path, PtrKind, Ref, Region, SortCtor,
+        SubsetTy, Ty, TyKind, Var, VariantIdx, INNERMOST,
     },
     PlaceExt as _,
 };
@@ -35,7 +39,6 @@ use rustc_index::IndexSlice;
 use rustc_middle::{mir::RETURN_PLACE, ty::TyCtxt};
 use rustc_span::Symbol;
 use rustc_type_ir::BoundVar;
-use serde::Serialize;
 
 use self::place_ty::{LocKind, PlacesTree};
 use super::rty::Sort;
@@ -80,6 +83,11 @@ impl<'a> TypeEnv<'a> {
 
         for (local, ty) in body.args_iter().zip(fn_sig.inputs()) {
             let ty = infcx.unpack(ty);
+            if let TyKind::Indexed(_, idx) = ty.kind()
+                && let Some(symbol) = body.local_names.get(&local)
+            {
+                name_index_vars(infcx, idx, symbol.as_str());
+            }
             infcx.assume_invariants(&ty, check_overflow);
             env.alloc_with_ty(local, ty);
         }
@@ -287,7 +295,7 @@ impl<'a> TypeEnv<'a> {
     }
 
     pub(crate) fn check_goto(
-        self,
+        mut self,
         infcx: &mut InferCtxtAt,
         bb_env: &BasicBlockEnv,
         target: BasicBlock,
@@ -303,8 +311,31 @@ impl<'a> TypeEnv<'a> {
             infcx.check_pred(constr, ConstrReason::Goto(target));
         }
 
-        // Check subtyping
         let bb_env = bb_env.bindings.flatten();
+
+        // Weaken pointers and close locations to match the shape of the target environment. See
+        // [`BasicBlockEnvShape::join`].
+        for (path, _, ty2) in &bb_env {
+            if let TyKind::Ptr(PtrKind::Mut(re), ptr_path) = self.bindings.get(path).kind()
+                && let Ref!(_, bound, Mutability::Mut) = ty2.kind()
+            {
+                let ref_ty = self.ptr_to_ref(
+                    infcx,
+                    ConstrReason::Goto(target),
+                    *re,
+                    ptr_path,
+                    PtrToRefBound::Ty(bound.clone()),
+                )?;
+                self.update_path(path, ref_ty);
+            }
+        }
+        for (path, _, ty2) in &bb_env {
+            if is_unfolded(&self.bindings.get(path)) && !is_unfolded(&ty2.unblocked()) {
+                self.bindings.lookup(path).fold(infcx)?;
+            }
+        }
+
+        // Check subtyping
         for (path, _, ty2) in bb_env {
             let ty1 = self.bindings.get(&path);
             infcx.subtyping(&ty1.unblocked(), &ty2.unblocked(), ConstrReason::Goto(target))?;
@@ -408,7 +439,7 @@ impl<'a> TypeEnv<'a> {
         for constraint in &output.ensures {
             match constraint {
                 Ensures::Type(path, ty) => {
-                    let actual_ty = self.get(path);
+                    let actual_ty = self.get(path).unblocked();
                     at.subtyping(&actual_ty, ty, reason)?;
                 }
                 Ensures::Pred(e) => {
@@ -557,11 +588,54 @@ impl BasicBlockEnvShape {
     /// join(self, genv, other) consumes the bindings in other, to "update"
     /// `self` in place, and returns `true` if there was an actual change
     /// or `false` indicating no change (i.e., a fixpoint was reached).
-    pub(crate) fn join(&mut self, other: TypeEnv) -> Result<bool> {
+    pub(crate) fn join(&mut self, infcx: &mut InferCtxtAt, mut other: TypeEnv) -> Result<bool> {
         let paths = self.bindings.paths();
+        let mut modified = false;
+
+        // Weaken pointers that disagree on the location they point to. For example, when looping
+        // over a list, a cursor may start as `ptr(mut, ℓ₁)` and come back through the back edge as
+        // `ptr(mut, ℓ₂)` where `ℓ₂` is a box inside `ℓ₁`. The only thing we can say at the join is
+        // that the cursor is a `&mut T`.
+        for path in &paths {
+            let ty1 = self.bindings.get(path);
+            let ty2 = other.bindings.get(path);
+            if let TyKind::Ptr(PtrKind::Mut(_), path1) = ty1.kind()
+                && let TyKind::Ptr(PtrKind::Mut(_), path2) = ty2.kind()
+                && path1 == path2
+            {
+                continue;
+            }
+            if let TyKind::Ptr(PtrKind::Mut(re), ptr_path) = ty1.kind()
+                && is_mut_ptr_or_ref(&ty2)
+            {
+                let bound = self.bindings.get(ptr_path).unblocked().with_holes();
+                self.update(ptr_path, Ty::blocked(bound.clone()));
+                self.update(path, Ty::mk_ref(*re, bound, Mutability::Mut));
+                modified = true;
+            }
+            if let TyKind::Ptr(PtrKind::Mut(re), ptr_path) = ty2.kind()
+                && is_mut_ptr_or_ref(&ty1)
+            {
+                let ref_ty = other.ptr_to_ref(
+                    infcx,
+                    ConstrReason::Other,
+                    *re,
+                    ptr_path,
+                    PtrToRefBound::Infer,
+                )?;
+                other.update_path(path, ref_ty);
+            }
+        }
+
+        // Close the locations (including boxes) that are folded in `self` but were left unfolded
+        // in `other`.
+        for path in &paths {
+            if !is_unfolded(&self.bindings.get(path)) && is_unfolded(&other.bindings.get(path)) {
+                other.bindings.lookup(path).fold(infcx)?;
+            }
+        }
 
         // Join types
-        let mut modified = false;
         for path in &paths {
             let ty1 = self.bindings.get(path);
             let ty2 = other.bindings.get(path);
@@ -874,22 +948,14 @@ mod pretty {
     }
 }
 
-/// A very explicit representation of [`TypeEnv`] for debugging/tracing/serialization ONLY.
-#[derive(Serialize, DebugAsJson)]
-pub struct TypeEnvTrace(Vec<TypeEnvBind>);
-
-#[derive(Serialize)]
-struct TypeEnvBind {
-    local: LocInfo,
-    name: Option<String>,
-    kind: String,
-    ty: String,
+/// Whether `ty` is the type of a location that has been unfolded, i.e., a downcasted adt or an
+/// open box.
+fn is_unfolded(ty: &Ty) -> bool {
+    matches!(ty.kind(), TyKind::Downcast(..) | TyKind::Ptr(PtrKind::Box, _))
 }
 
-#[derive(Serialize)]
-enum LocInfo {
-    Local(String),
-    Var(String),
+fn is_mut_ptr_or_ref(ty: &Ty) -> bool {
+    matches!(ty.kind(), TyKind::Ptr(PtrKind::Mut(_), _) | Ref!(.., Mutability::Mut))
 }
 
 fn loc_info(loc: &Loc) -> LocInfo {
@@ -899,6 +965,27 @@ fn loc_info(loc: &Loc) -> LocInfo {
     }
 }
 
+/// Names the variables in the index of an argument after the argument to explain errors. The fields
+/// of an aggregate index are named with projections, e.g., the variables in the index `(a0, a1)` of
+/// an argument `x` are named `x.0` and `x.1`.
+fn name_index_vars(infcx: &mut InferCtxt, idx: &Expr, name: &str) {
+    match idx.kind() {
+        ExprKind::Var(Var::Free(var)) => infcx.name_var(*var, Symbol::intern(name)),
+        ExprKind::Aggregate(AggregateKind::Tuple(_), flds) => {
+            for (i, fld) in flds.iter().enumerate() {
+                name_index_vars(infcx, fld, &format!("{name}.{i}"));
+            }
+        }
+        ExprKind::Aggregate(AggregateKind::Adt(def_id), flds) => {
+            let Ok(sort_def) = infcx.genv.adt_sort_def_of(*def_id) else { return };
+            for (fld, field_name) in iter::zip(flds, sort_def.field_names()) {
+                name_index_vars(infcx, fld, &format!("{name}.{field_name}"));
+            }
+        }
+        _ => {}
+    }
+}
+
 fn loc_name(local_names: &UnordMap<Local, Symbol>, loc: &Loc) -> Option<String> {
     if let Loc::Local(local) = loc {
         let name = local_names.get(local)?;
@@ -907,11 +994,12 @@ fn loc_name(local_names: &UnordMap<Local, Symbol>, loc: &Loc) -> Option<String>
     None
 }
 
-impl TypeEnvTrace {
-    pub fn new(genv: GlobalEnv, local_names: &UnordMap<Local, Symbol>, env: &TypeEnv) -> Self {
+impl TypeEnv<'_> {
+    /// Returns a very explicit representation of the environment for debugging/tracing/serialization
+    pub fn trace(&self, genv: GlobalEnv, local_names: &UnordMap<Local, Symbol>) -> TypeEnvTrace {
         let mut bindings = vec![];
         let cx = PrettyCx::default_with_genv(genv).hide_regions(true);
-        env.bindings
+        self.bindings
             .iter()
             .filter(|(_, binding)| !binding.ty.is_uninit())
             .sorted_by(|(loc1, _), (loc2, _)| loc1.cmp(loc2))
@@ -919,7 +1007,7 @@ impl TypeEnvTrace {
                 let name = loc_name(local_names, loc);
                 let local = loc_info(loc);
                 let kind = format!("{:?}", binding.kind);
-                let ty = binding.ty.nested_string(&cx);
+                let ty = binding.ty.fmt_nested(&cx).unwrap();
                 bindings.push(TypeEnvBind { name, local, kind, ty });
             });
 
@@ -157,7 +157,16 @@ impl PlacesTree {
                     };
                 }
                 PlaceElem::Index(_) | PlaceElem::ConstantIndex { .. } => return,
-                PlaceElem::Downcast(..) => {}
+                PlaceElem::Downcast(..) => {
+                    // A strong update through a cursor (e.g., `*cur = other` after looping with
+                    // `while let Cons(_, tl) = cur`) folds the enum back, so the blocked field
+                    // of the variant no longer exists.
+                    if let TyKind::Indexed(BaseTy::Adt(adt_def, _), _) = ty.kind()
+                        && adt_def.is_enum()
+                    {
+                        return;
+                    }
+                }
             }
         }
         cursor.reset();
@@ -885,7 +894,13 @@ fn fold(
             let LocKind::Box(alloc) = binding.kind else {
                 tracked_span_bug!("box pointer to non-box loc");
             };
-            let deref_ty = fold(bindings, infcx, &binding.ty, is_strg)?;
+            // The box may be closed while its contents are still borrowed, in which case it holds
+            // the (unpacked) type of the borrow.
+            let deref_ty = match binding.ty.kind() {
+                TyKind::Blocked(ty) => infcx.unpack(ty),
+                _ => binding.ty.clone(),
+            };
+            let deref_ty = fold(bindings, infcx, &deref_ty, is_strg)?;
             Ok(Ty::mk_box(infcx.genv, deref_ty, alloc)?)
         }
         Ref!(re, deref_ty, mutbl) => {
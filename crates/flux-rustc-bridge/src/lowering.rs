@@ -1117,7 +1117,7 @@ impl<'tcx> Lower<'tcx> for rustc_ty::TypeOutlivesPredicate<'tcx> {
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::E9033;
     use flux_macros::Diagnostic;
     use rustc_middle::mir as rustc_mir;
     use rustc_span::Span;
@@ -1125,7 +1125,7 @@ mod errors {
     use super::UnsupportedReason;
 
     #[derive(Diagnostic)]
-    #[diag(rustc_bridge_unsupported_local_decl, code = E0999)]
+    #[diag(rustc_bridge_unsupported_local_decl, code = E9033)]
     pub(super) struct UnsupportedLocalDecl<'tcx> {
         #[primary_span]
         #[label]
@@ -1143,7 +1143,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(rustc_bridge_unsupported_mir, code = E0999)]
+    #[diag(rustc_bridge_unsupported_mir, code = E9033)]
     #[note]
     pub(super) struct UnsupportedMir {
         #[primary_span]
@@ -41,10 +41,14 @@ pub enum Token {
     Where,
     Forall,
     Impl,
+    Struct,
+    Use,
+    Dyn,
     Requires,
     Ensures,
     Literal(Lit),
     Ident(Symbol),
+    Lifetime(Symbol),
     OpenDelim(Delimiter),
     CloseDelim(Delimiter),
     Invalid,
@@ -149,6 +153,7 @@ impl<'t> Cursor<'t> {
             TokenKind::OpenDelim(delim) => Token::OpenDelim(delim),
             TokenKind::CloseDelim(delim) => Token::CloseDelim(delim),
             TokenKind::Literal(lit) => Token::Literal(lit),
+            TokenKind::Lifetime(name, _) => Token::Lifetime(name),
             TokenKind::Ident(symb, _) if symb == kw::True || symb == kw::False => {
                 Token::Literal(Lit { kind: LitKind::Bool, symbol: symb, suffix: None })
             }
@@ -168,6 +173,9 @@ impl<'t> Cursor<'t> {
             TokenKind::Ident(symb, _) if symb == kw::Mut => Token::Mut,
             TokenKind::Ident(symb, _) if symb == kw::Where => Token::Where,
             TokenKind::Ident(symb, _) if symb == kw::Impl => Token::Impl,
+            TokenKind::Ident(symb, _) if symb == kw::Struct => Token::Struct,
+            TokenKind::Ident(symb, _) if symb == kw::Use => Token::Use,
+            TokenKind::Ident(symb, _) if symb == kw::Dyn => Token::Dyn,
             TokenKind::Ident(symb, _) if symb == kw::Type => Token::Type,
             TokenKind::Ident(symb, _) if symb == kw::If => Token::If,
             TokenKind::Ident(symb, _) if symb == kw::Else => Token::Else,
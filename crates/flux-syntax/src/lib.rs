@@ -97,6 +97,14 @@ impl ParseSess {
         parse!(self, grammar::ItemsParser, tokens, span)
     }
 
+    pub fn parse_spec_file(
+        &mut self,
+        tokens: &TokenStream,
+        span: Span,
+    ) -> ParseResult<Vec<surface::SpecFileItem>> {
+        parse!(self, grammar::SpecFileParser, tokens, span)
+    }
+
     pub fn parse_type(&mut self, tokens: &TokenStream, span: Span) -> ParseResult<surface::Ty> {
         parse!(self, grammar::TyParser, tokens, span)
     }
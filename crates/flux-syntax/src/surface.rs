@@ -55,6 +55,36 @@ pub struct SpecFunc {
     pub body: Option<Expr>,
 }
 
+/// An item in a standalone spec file with specs for items defined in other crates
+#[derive(Debug)]
+pub enum SpecFileItem {
+    /// `use path::to::item;` brings an item into scope so the other items can refer to it
+    Use(Path),
+    /// `fn name(x: i32{x > 0}) -> i32;` attaches the signature to the function `name`
+    Fn(FnSig),
+    /// `opaque struct Name<T>[len: int];`
+    Struct(ExternStruct),
+    /// `impl<T> Name<T> { fn new() -> Name<T>[0]; }`
+    Impl(ExternImpl),
+}
+
+#[derive(Debug)]
+pub struct ExternStruct {
+    pub ident: Ident,
+    pub generics: Generics,
+    /// The refinement parameters and their span in the file
+    pub refined_by: Option<(RefineParams, Span)>,
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub struct ExternImpl {
+    pub generics: Generics,
+    pub self_ty: Path,
+    pub items: Vec<FnSig>,
+    pub span: Span,
+}
+
 #[derive(Debug)]
 pub struct Generics {
     pub params: Vec<GenericParam>,
@@ -73,6 +103,9 @@ pub struct GenericParam {
 pub enum GenericParamKind {
     Type,
     Base,
+    /// A lifetime parameter, e.g., `'a`. Lifetimes are only used to write the unrefined signature
+    /// of items in spec files, flux signatures take them from the Rust signature they refine.
+    Lifetime,
 }
 
 #[derive(Debug)]
@@ -139,7 +172,7 @@ pub type RefineParams = Vec<RefineParam>;
 
 #[derive(Debug, Default)]
 pub struct QualNames {
-    pub names: Vec<Ident>,
+    pub names: Vec<ExprPath>,
 }
 
 #[derive(Debug)]
@@ -332,8 +365,9 @@ pub enum TyKind {
         ty: Box<Ty>,
         pred: Option<Expr>,
     },
-    /// Mutable or shared reference
-    Ref(Mutability, Box<Ty>),
+    /// Mutable or shared reference with an optional lifetime, which is ignored like
+    /// [`GenericParamKind::Lifetime`]
+    Ref(Option<Ident>, Mutability, Box<Ty>),
     /// Constrained type: an exists without binder
     Constr(Expr, Box<Ty>),
     Tuple(Vec<Ty>),
@@ -383,17 +417,22 @@ pub struct BaseTy {
 pub enum BaseTyKind {
     Path(Option<Box<Ty>>, Path),
     Slice(Box<Ty>),
+    /// A trait object `dyn Trait + Send`
+    TraitObject(GenericBounds),
 }
 
-#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+#[derive(Debug)]
 pub struct ConstArg {
     pub kind: ConstArgKind,
     pub span: Span,
 }
 
-#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+#[derive(Debug)]
 pub enum ConstArgKind {
     Lit(usize),
+    /// An expression over const generic parameters, const items and integer literals, e.g.,
+    /// `N`, `BUFLEN` or `N + 1`.
+    Expr(Expr),
     Infer,
 }
 
@@ -450,6 +489,8 @@ pub struct GenericArg {
 pub enum GenericArgKind {
     Type(Ty),
     Constraint(Ident, Ty),
+    /// A lifetime argument, ignored like [`GenericParamKind::Lifetime`]
+    Lifetime(Ident),
 }
 
 #[derive(Debug)]
@@ -487,7 +528,7 @@ pub enum ExprKind {
     Literal(Lit),
     BinaryOp(BinOp, Box<[Expr; 2]>),
     UnaryOp(UnOp, Box<Expr>),
-    App(Ident, Vec<Expr>),
+    App(ExprPath, Vec<Expr>),
     Alias(AliasReft, Vec<Expr>),
     IfThenElse(Box<[Expr; 3]>),
     Constructor(Option<ExprPath>, Vec<ConstructorArg>),
@@ -1,11 +1,12 @@
 use rustc_span::symbol::Ident;
 
 use super::{
-    AliasReft, Async, BaseSort, BaseTy, BaseTyKind, ConstArg, ConstructorArg, Ensures, EnumDef,
-    Expr, ExprKind, ExprPath, ExprPathSegment, FieldExpr, FnInput, FnOutput, FnRetTy, FnSig,
-    GenericArg, GenericArgKind, GenericParam, Generics, Impl, ImplAssocReft, Indices, Lit, Path,
-    PathSegment, Qualifier, RefineArg, RefineParam, Sort, SortPath, SpecFunc, StructDef, Trait,
-    TraitAssocReft, TraitRef, Ty, TyAlias, TyKind, VariantDef, VariantRet, WhereBoundPredicate,
+    AliasReft, Async, BaseSort, BaseTy, BaseTyKind, ConstArg, ConstArgKind, ConstructorArg,
+    Ensures, EnumDef, Expr, ExprKind, ExprPath, ExprPathSegment, FieldExpr, FnInput, FnOutput,
+    FnRetTy, FnSig, GenericArg, GenericArgKind, GenericParam, Generics, Impl, ImplAssocReft,
+    Indices, Lit, Path, PathSegment, Qualifier, RefineArg, RefineParam, Sort, SortPath, SpecFunc,
+    StructDef, Trait, TraitAssocReft, TraitRef, Ty, TyAlias, TyKind, VariantDef, VariantRet,
+    WhereBoundPredicate,
 };
 
 #[macro_export]
@@ -135,7 +136,9 @@ pub trait Visitor: Sized {
         walk_ty(self, ty);
     }
 
-    fn visit_const_arg(&mut self, _const_arg: &ConstArg) {}
+    fn visit_const_arg(&mut self, const_arg: &ConstArg) {
+        walk_const_arg(self, const_arg);
+    }
 
     fn visit_bty(&mut self, bty: &BaseTy) {
         walk_bty(self, bty);
@@ -308,8 +311,9 @@ pub fn walk_variant_ret<V: Visitor>(vis: &mut V, ret: &VariantRet) {
 
 pub fn walk_fn_sig<V: Visitor>(vis: &mut V, fn_sig: &FnSig) {
     vis.visit_async(&fn_sig.asyncness);
-    vis.visit_generics(&fn_sig.generics);
+    // Refinement parameters are visited first because they are in scope in the where clauses
     walk_list!(vis, visit_refine_param, &fn_sig.params);
+    vis.visit_generics(&fn_sig.generics);
     for requires in &fn_sig.requires {
         walk_list!(vis, visit_refine_param, &requires.params);
         vis.visit_expr(&requires.pred);
@@ -381,6 +385,7 @@ pub fn walk_generic_arg<V: Visitor>(vis: &mut V, arg: &GenericArg) {
             vis.visit_ident(*ident);
             vis.visit_ty(ty);
         }
+        GenericArgKind::Lifetime(_) => {}
     }
 }
 
@@ -422,7 +427,7 @@ pub fn walk_ty<V: Visitor>(vis: &mut V, ty: &Ty) {
                 vis.visit_expr(pred);
             }
         }
-        TyKind::Ref(_mutbl, ty) => {
+        TyKind::Ref(_lft, _mutbl, ty) => {
             vis.visit_ty(ty);
         }
         TyKind::Constr(pred, ty) => {
@@ -443,6 +448,13 @@ pub fn walk_ty<V: Visitor>(vis: &mut V, ty: &Ty) {
     }
 }
 
+pub fn walk_const_arg<V: Visitor>(vis: &mut V, const_arg: &ConstArg) {
+    match &const_arg.kind {
+        ConstArgKind::Expr(expr) => vis.visit_expr(expr),
+        ConstArgKind::Lit(_) | ConstArgKind::Infer => {}
+    }
+}
+
 pub fn walk_bty<V: Visitor>(vis: &mut V, bty: &BaseTy) {
     match &bty.kind {
         BaseTyKind::Path(qself, path) => {
@@ -452,6 +464,9 @@ pub fn walk_bty<V: Visitor>(vis: &mut V, bty: &BaseTy) {
             vis.visit_path(path);
         }
         BaseTyKind::Slice(ty) => vis.visit_ty(ty),
+        BaseTyKind::TraitObject(bounds) => {
+            walk_list!(vis, visit_trait_ref, bounds);
+        }
     }
 }
 
@@ -493,7 +508,7 @@ pub fn walk_expr<V: Visitor>(vis: &mut V, expr: &Expr) {
             vis.visit_expr(e);
         }
         ExprKind::App(fun, exprs) => {
-            vis.visit_ident(*fun);
+            vis.visit_path_expr(fun);
             walk_list!(vis, visit_expr, exprs);
         }
         ExprKind::Alias(alias_pred, args) => {
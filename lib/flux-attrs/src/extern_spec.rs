@@ -403,7 +403,13 @@ impl ExternFn {
             FnCtxt::Trait { trait_ } => quote!(#trait_ :: #ident),
             FnCtxt::Free => quote!(#ident),
         };
-        let generic_args = generic_params_to_args(&self.sig.generics.params);
+        // Lifetime arguments are left to inference because they cannot be passed explicitly if the
+        // function has late-bound lifetimes
+        let generic_args: Punctuated<GenericArgument, Token!(,)> =
+            generic_params_to_args(&self.sig.generics.params)
+                .into_iter()
+                .filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)))
+                .collect();
         let fn_args = fn_params_to_args(&self.sig.inputs);
         self.block = Some(quote!({ #fn_path :: <#generic_args> ( #fn_args ) }));
     }
@@ -15,8 +15,11 @@ pub const FLUX_ATTRS: &[&str] = &[
     "sig",
     "trusted",
     "trusted_impl",
+    "trusted_derive",
+    "ignore_derive",
     "variant",
     "should_fail",
+    "explain",
     "check_overflow",
 ];
 
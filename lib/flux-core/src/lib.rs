@@ -0,0 +1,489 @@
+//! Refined specifications for `core` (and the string types in `alloc`) shipped with the flux
+//! sysroot.
+//!
+//! This crate is compiled with flux and the resulting `.fluxmeta` is loaded automatically when
+//! checking any crate, so the specs here apply without having to include them. Specs declared in
+//! the crate being checked take precedence over the ones in this crate.
+//!
+//! Iterators are modeled with three associated refinements on [`Iterator`]:
+//!
+//! * `done(s)` holds if calling `next` on an iterator with index `s` definitely returns `None`.
+//! * `ready(s)` holds if calling `next` on an iterator with index `s` definitely returns `Some`.
+//! * `step(s, t)` relates the index of an iterator before and after `next` returns `Some`.
+//!
+//! The defaults say nothing, which is what we assume for iterators without a spec.
+//!
+//! Strings (`str` and `String`) are refined by their contents as a sequence of bytes, i.e., a value
+//! of sort `str`, which we reason about with the theory functions `str_len`, `str_concat`, etc.
+#![feature(step_trait)]
+#![allow(unused)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use core::{
+    iter::{Enumerate, Rev, Step, StepBy, Zip},
+    ops::{Range, RangeInclusive},
+};
+
+use flux_rs::extern_spec;
+
+// -------------------------------------------------------------------------------------------------
+// Option
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(core::option)]
+#[flux_rs::refined_by(b: bool)]
+enum Option<T> {
+    #[flux_rs::variant(Option<T>[false])]
+    None,
+    #[flux_rs::variant({T} -> Option<T>[true])]
+    Some(T),
+}
+
+#[extern_spec(core::option)]
+impl<T> Option<T> {
+    #[flux_rs::sig(fn(&Option<T>[@b]) -> bool[b])]
+    const fn is_some(&self) -> bool;
+
+    #[flux_rs::sig(fn(&Option<T>[@b]) -> bool[!b])]
+    const fn is_none(&self) -> bool;
+
+    #[flux_rs::sig(fn(Option<T>[true], &str) -> T)]
+    const fn expect(self, msg: &str) -> T;
+
+    #[flux_rs::sig(fn(Option<T>[true]) -> T)]
+    const fn unwrap(self) -> T;
+
+    #[flux_rs::sig(fn(Option<T>, T) -> T)]
+    fn unwrap_or(self, default: T) -> T;
+
+    #[flux_rs::sig(fn(Option<T>, F) -> T where F: FnOnce() -> T)]
+    fn unwrap_or_else<F>(self, f: F) -> T
+    where
+        F: FnOnce() -> T;
+
+    #[flux_rs::sig(fn(Option<T>[@b], F) -> Option<U>[b] where F: FnOnce(T) -> U)]
+    fn map<U, F>(self, f: F) -> Option<U>
+    where
+        F: FnOnce(T) -> U;
+
+    #[flux_rs::sig(fn(Option<T>, U, F) -> U where F: FnOnce(T) -> U)]
+    fn map_or<U, F>(self, default: U, f: F) -> U
+    where
+        F: FnOnce(T) -> U;
+
+    #[flux_rs::sig(fn(Option<T>[@b], E) -> Result<T, E>[b])]
+    fn ok_or<E>(self, err: E) -> Result<T, E>;
+
+    #[flux_rs::sig(fn(Option<T>[@b], F) -> Result<T, E>[b] where F: FnOnce() -> E)]
+    fn ok_or_else<E, F>(self, err: F) -> Result<T, E>
+    where
+        F: FnOnce() -> E;
+
+    #[flux_rs::sig(fn(Option<T>[@b], F) -> Option<U>{v: v => b} where F: FnOnce(T) -> Option<U>)]
+    fn and_then<U, F>(self, f: F) -> Option<U>
+    where
+        F: FnOnce(T) -> Option<U>;
+
+    #[flux_rs::sig(fn(Option<T>[@b], P) -> Option<T>{v: v => b} where P: FnOnce(&T) -> bool)]
+    fn filter<P>(self, predicate: P) -> Option<T>
+    where
+        P: FnOnce(&T) -> bool;
+
+    #[flux_rs::sig(fn(Option<T>[@b1], Option<T>[@b2]) -> Option<T>[b1 || b2])]
+    fn or(self, optb: Option<T>) -> Option<T>;
+
+    #[flux_rs::sig(fn(self: &strg Option<T>[@b]) -> Option<T>[b] ensures self: Option<T>[false])]
+    const fn take(&mut self) -> Option<T>;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Result
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(core::result)]
+#[flux_rs::refined_by(ok: bool)]
+enum Result<T, E> {
+    #[flux_rs::variant({T} -> Result<T, E>[true])]
+    Ok(T),
+    #[flux_rs::variant({E} -> Result<T, E>[false])]
+    Err(E),
+}
+
+#[extern_spec(core::result)]
+impl<T, E> Result<T, E> {
+    #[flux_rs::sig(fn(&Result<T, E>[@ok]) -> bool[ok])]
+    const fn is_ok(&self) -> bool;
+
+    #[flux_rs::sig(fn(&Result<T, E>[@ok]) -> bool[!ok])]
+    const fn is_err(&self) -> bool;
+
+    #[flux_rs::sig(fn(Result<T, E>[@ok]) -> Option<T>[ok])]
+    fn ok(self) -> Option<T>;
+
+    #[flux_rs::sig(fn(Result<T, E>[@ok]) -> Option<E>[!ok])]
+    fn err(self) -> Option<E>;
+
+    #[flux_rs::sig(fn(Result<T, E>[@ok], F) -> Result<U, E>[ok] where F: FnOnce(T) -> U)]
+    fn map<U, F>(self, op: F) -> Result<U, E>
+    where
+        F: FnOnce(T) -> U;
+
+    #[flux_rs::sig(fn(Result<T, E>, U, F) -> U where F: FnOnce(T) -> U)]
+    fn map_or<U, F>(self, default: U, f: F) -> U
+    where
+        F: FnOnce(T) -> U;
+
+    #[flux_rs::sig(fn(Result<T, E>[@ok], O) -> Result<T, F>[ok] where O: FnOnce(E) -> F)]
+    fn map_err<F, O>(self, op: O) -> Result<T, F>
+    where
+        O: FnOnce(E) -> F;
+
+    #[flux_rs::sig(fn(Result<T, E>[@ok], F) -> Result<U, E>{v: v => ok} where F: FnOnce(T) -> Result<U, E>)]
+    fn and_then<U, F>(self, op: F) -> Result<U, E>
+    where
+        F: FnOnce(T) -> Result<U, E>;
+
+    #[flux_rs::sig(fn(Result<T, E>, T) -> T)]
+    fn unwrap_or(self, default: T) -> T;
+
+    #[flux_rs::sig(fn(Result<T, E>, F) -> T where F: FnOnce(E) -> T)]
+    fn unwrap_or_else<F>(self, op: F) -> T
+    where
+        F: FnOnce(E) -> T;
+
+    #[flux_rs::sig(fn(Result<T, E>[true], &str) -> T)]
+    fn expect(self, msg: &str) -> T
+    where
+        E: core::fmt::Debug;
+
+    #[flux_rs::sig(fn(Result<T, E>[true]) -> T)]
+    fn unwrap(self) -> T
+    where
+        E: core::fmt::Debug;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Step
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(core::iter)]
+#[flux_rs::generics(Self as base)]
+#[flux_rs::assoc(fn step_forward(start: Self, count: int) -> Self)]
+#[flux_rs::assoc(fn step_backward(start: Self, count: int) -> Self)]
+trait Step {}
+
+macro_rules! step_int {
+    ($($ty:ident)*) => {$(
+        #[extern_spec(core::iter)]
+        #[flux_rs::assoc(fn step_forward(start: int, count: int) -> int { start + count })]
+        #[flux_rs::assoc(fn step_backward(start: int, count: int) -> int { start - count })]
+        impl Step for $ty {}
+    )*};
+}
+
+step_int! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
+// -------------------------------------------------------------------------------------------------
+// Iterator
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(core::iter)]
+#[flux_rs::generics(Self as base)]
+#[flux_rs::assoc(fn done(s: Self) -> bool { false })]
+#[flux_rs::assoc(fn ready(s: Self) -> bool { false })]
+#[flux_rs::assoc(fn step(s: Self, t: Self) -> bool { true })]
+trait Iterator {
+    #[flux_rs::sig(fn(Self[@s]) -> Enumerate<Self>[0, s])]
+    fn enumerate(self) -> Enumerate<Self>
+    where
+        Self: Sized;
+
+    #[flux_rs::sig(fn(Self[@s], U) -> Zip<Self, U::IntoIter>{z: z.a == s})]
+    fn zip<U>(self, other: U) -> Zip<Self, U::IntoIter>
+    where
+        Self: Sized,
+        U: IntoIterator;
+
+    #[flux_rs::sig(fn(Self[@s]) -> Rev<Self>[s])]
+    fn rev(self) -> Rev<Self>
+    where
+        Self: Sized + DoubleEndedIterator;
+
+    #[flux_rs::sig(fn(Self, usize[@n]) -> StepBy<Self>[n] requires n > 0)]
+    fn step_by(self, step: usize) -> StepBy<Self>
+    where
+        Self: Sized;
+}
+
+#[extern_spec(core::iter)]
+#[flux_rs::generics(Self as base)]
+#[flux_rs::assoc(fn step_back(s: Self, t: Self) -> bool { true })]
+trait DoubleEndedIterator {}
+
+#[extern_spec(core::iter)]
+#[flux_rs::generics(Self as base)]
+trait IntoIterator {
+    #[flux_rs::sig(fn(Self) -> Self::IntoIter)]
+    fn into_iter(self) -> Self::IntoIter
+    where
+        Self: Sized;
+}
+
+#[extern_spec(core::iter)]
+#[flux_rs::generics(I as base)]
+impl<I: Iterator> IntoIterator for I {
+    #[flux_rs::sig(fn(I[@s]) -> I[s])]
+    fn into_iter(self) -> I;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Range
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(core::ops)]
+#[flux_rs::refined_by(start: Idx, end: Idx)]
+struct Range<Idx> {
+    #[flux_rs::field(Idx[start])]
+    start: Idx,
+    #[flux_rs::field(Idx[end])]
+    end: Idx,
+}
+
+#[extern_spec(core::ops)]
+#[flux_rs::generics(A as base)]
+#[flux_rs::assoc(fn done(r: Range<A>) -> bool { !(r.start < r.end) })]
+#[flux_rs::assoc(fn ready(r: Range<A>) -> bool { r.start < r.end })]
+#[flux_rs::assoc(fn step(r: Range<A>, s: Range<A>) -> bool {
+    s.start == <A as Step>::step_forward(r.start, 1) && s.end == r.end
+})]
+impl<A: Step> Iterator for Range<A> {
+    #[flux_rs::sig(
+        fn(self: &strg Range<A>[@r]) -> Option<A[r.start]>[r.start < r.end]
+            ensures self: Range<A>{s:
+                s.end == r.end
+                && (r.start < r.end => s.start == <A as Step>::step_forward(r.start, 1))
+                && (!(r.start < r.end) => s.start == r.start)
+            }
+    )]
+    fn next(&mut self) -> Option<A>;
+}
+
+#[extern_spec(core::ops)]
+#[flux_rs::generics(A as base)]
+#[flux_rs::assoc(fn step_back(r: Range<A>, s: Range<A>) -> bool {
+    s.start == r.start && s.end == <A as Step>::step_backward(r.end, 1)
+})]
+impl<A: Step> DoubleEndedIterator for Range<A> {
+    #[flux_rs::sig(
+        fn(self: &strg Range<A>[@r]) -> Option<A[<A as Step>::step_backward(r.end, 1)]>[r.start < r.end]
+            ensures self: Range<A>{s:
+                s.start == r.start
+                && (r.start < r.end => s.end == <A as Step>::step_backward(r.end, 1))
+                && (!(r.start < r.end) => s.end == r.end)
+            }
+    )]
+    fn next_back(&mut self) -> Option<A>;
+}
+
+// -------------------------------------------------------------------------------------------------
+// RangeInclusive
+// -------------------------------------------------------------------------------------------------
+
+/// The fields of `RangeInclusive` are private. It is refined by its bounds and whether it has been
+/// exhausted, which happens after yielding `end`.
+#[extern_spec(core::ops)]
+#[flux_rs::refined_by(start: Idx, end: Idx, exhausted: bool)]
+struct RangeInclusive<Idx>;
+
+#[extern_spec(core::ops)]
+#[flux_rs::generics(Idx as base)]
+impl<Idx> RangeInclusive<Idx> {
+    #[flux_rs::sig(fn(Idx[@start], Idx[@end]) -> RangeInclusive<Idx>[start, end, false])]
+    const fn new(start: Idx, end: Idx) -> RangeInclusive<Idx>;
+}
+
+#[extern_spec(core::ops)]
+#[flux_rs::generics(A as base)]
+#[flux_rs::assoc(fn done(r: RangeInclusive<A>) -> bool { r.exhausted || !(r.start <= r.end) })]
+#[flux_rs::assoc(fn ready(r: RangeInclusive<A>) -> bool { !r.exhausted && r.start <= r.end })]
+#[flux_rs::assoc(fn step(r: RangeInclusive<A>, s: RangeInclusive<A>) -> bool {
+    s.end == r.end
+        && (r.start < r.end => s.start == <A as Step>::step_forward(r.start, 1) && !s.exhausted)
+        && (!(r.start < r.end) => s.start == r.start && s.exhausted)
+})]
+impl<A: Step> Iterator for RangeInclusive<A> {
+    #[flux_rs::sig(
+        fn(self: &strg RangeInclusive<A>[@r]) -> Option<A[r.start]>[!r.exhausted && r.start <= r.end]
+            ensures self: RangeInclusive<A>{s:
+                s.end == r.end
+                && (!r.exhausted && r.start < r.end => s.start == <A as Step>::step_forward(r.start, 1) && !s.exhausted)
+                && (!r.exhausted && r.start == r.end => s.start == r.start && s.exhausted)
+                && (r.exhausted || !(r.start <= r.end) => s.start == r.start && s.exhausted == r.exhausted)
+            }
+    )]
+    fn next(&mut self) -> Option<A>;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Adaptors
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(core::iter)]
+#[flux_rs::refined_by(idx: int, inner: I)]
+struct Enumerate<I>;
+
+#[extern_spec(core::iter)]
+#[flux_rs::generics(I as base)]
+#[flux_rs::assoc(fn done(e: Enumerate<I>) -> bool { <I as Iterator>::done(e.inner) })]
+#[flux_rs::assoc(fn ready(e: Enumerate<I>) -> bool { <I as Iterator>::ready(e.inner) })]
+#[flux_rs::assoc(fn step(e: Enumerate<I>, f: Enumerate<I>) -> bool {
+    f.idx == e.idx + 1 && <I as Iterator>::step(e.inner, f.inner)
+})]
+impl<I: Iterator> Iterator for Enumerate<I> {
+    #[flux_rs::sig(
+        fn(self: &strg Enumerate<I>[@e]) -> Option<(usize[e.idx], I::Item)>{v:
+            (<I as Iterator>::done(e.inner) => !v) && (<I as Iterator>::ready(e.inner) => v)
+        }
+            ensures self: Enumerate<I>{f:
+                (<I as Iterator>::ready(e.inner) => f.idx == e.idx + 1 && <I as Iterator>::step(e.inner, f.inner))
+                && (<I as Iterator>::done(e.inner) => f.idx == e.idx)
+            }
+    )]
+    fn next(&mut self) -> Option<(usize, I::Item)>;
+}
+
+#[extern_spec(core::iter)]
+#[flux_rs::refined_by(a: A, b: B)]
+struct Zip<A, B>;
+
+#[extern_spec(core::iter)]
+#[flux_rs::generics(A as base, B as base)]
+#[flux_rs::assoc(fn done(z: Zip<A, B>) -> bool {
+    <A as Iterator>::done(z.a) || <B as Iterator>::done(z.b)
+})]
+#[flux_rs::assoc(fn ready(z: Zip<A, B>) -> bool {
+    <A as Iterator>::ready(z.a) && <B as Iterator>::ready(z.b)
+})]
+#[flux_rs::assoc(fn step(z: Zip<A, B>, y: Zip<A, B>) -> bool {
+    <A as Iterator>::step(z.a, y.a) && <B as Iterator>::step(z.b, y.b)
+})]
+impl<A: Iterator, B: Iterator> Iterator for Zip<A, B> {
+    #[flux_rs::sig(
+        fn(self: &strg Zip<A, B>[@z]) -> Option<_>{v:
+            (<A as Iterator>::done(z.a) || <B as Iterator>::done(z.b) => !v)
+            && (<A as Iterator>::ready(z.a) && <B as Iterator>::ready(z.b) => v)
+        }
+            ensures self: Zip<A, B>{y:
+                <A as Iterator>::ready(z.a) && <B as Iterator>::ready(z.b)
+                    => <A as Iterator>::step(z.a, y.a) && <B as Iterator>::step(z.b, y.b)
+            }
+    )]
+    fn next(&mut self) -> Option<<Zip<A, B> as Iterator>::Item>;
+}
+
+#[extern_spec(core::iter)]
+#[flux_rs::refined_by(inner: T)]
+struct Rev<T>;
+
+#[extern_spec(core::iter)]
+#[flux_rs::generics(I as base)]
+#[flux_rs::assoc(fn done(r: Rev<I>) -> bool { <I as Iterator>::done(r.inner) })]
+#[flux_rs::assoc(fn ready(r: Rev<I>) -> bool { <I as Iterator>::ready(r.inner) })]
+#[flux_rs::assoc(fn step(r: Rev<I>, s: Rev<I>) -> bool {
+    <I as DoubleEndedIterator>::step_back(r.inner, s.inner)
+})]
+impl<I: DoubleEndedIterator> Iterator for Rev<I> {
+    #[flux_rs::sig(
+        fn(self: &strg Rev<I>[@r]) -> Option<I::Item>{v:
+            (<I as Iterator>::done(r.inner) => !v) && (<I as Iterator>::ready(r.inner) => v)
+        }
+            ensures self: Rev<I>{s:
+                <I as Iterator>::ready(r.inner) => <I as DoubleEndedIterator>::step_back(r.inner, s.inner)
+            }
+    )]
+    fn next(&mut self) -> Option<I::Item>;
+}
+
+/// `StepBy` is refined by its step, which is always positive. We do not track the state of the
+/// inner iterator because it advances by a variable number of elements on each call to `next`.
+#[extern_spec(core::iter)]
+#[flux_rs::refined_by(step: int)]
+#[flux_rs::invariant(step > 0)]
+struct StepBy<I>;
+
+// -------------------------------------------------------------------------------------------------
+// str
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec]
+impl str {
+    #[flux_rs::sig(fn(&str[@s]) -> usize[str_len(s)])]
+    const fn len(&self) -> usize;
+
+    #[flux_rs::sig(fn(&str[@s]) -> bool[str_len(s) == 0])]
+    const fn is_empty(&self) -> bool;
+
+    #[flux_rs::sig(fn(&str[@s]) -> &[u8][str_len(s)])]
+    const fn as_bytes(&self) -> &[u8];
+}
+
+#[extern_spec(core::cmp)]
+impl PartialEq for str {
+    #[flux_rs::sig(fn(&str[@a], &str[@b]) -> bool[a == b])]
+    fn eq(&self, other: &str) -> bool;
+}
+
+// -------------------------------------------------------------------------------------------------
+// String
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(alloc::string)]
+#[flux_rs::refined_by(s: str)]
+struct String;
+
+#[extern_spec(alloc::string)]
+impl String {
+    #[flux_rs::sig(fn() -> String[""])]
+    const fn new() -> String;
+
+    #[flux_rs::sig(fn(&String[@s]) -> usize[str_len(s)])]
+    fn len(&self) -> usize;
+
+    #[flux_rs::sig(fn(&String[@s]) -> bool[str_len(s) == 0])]
+    fn is_empty(&self) -> bool;
+
+    #[flux_rs::sig(fn(self: &strg String[@s], &str[@t]) ensures self: String[str_concat(s, t)])]
+    fn push_str(&mut self, string: &str);
+
+    #[flux_rs::sig(fn(&String[@s]) -> &str[s])]
+    fn as_str(&self) -> &str;
+
+    #[flux_rs::sig(fn(&String[@s]) -> &[u8][str_len(s)])]
+    fn as_bytes(&self) -> &[u8];
+}
+
+#[extern_spec(alloc::str)]
+impl ToOwned for str {
+    #[flux_rs::sig(fn(&str[@s]) -> String[s])]
+    fn to_owned(&self) -> String;
+}
+
+#[extern_spec(alloc::string)]
+impl PartialEq for String {
+    #[flux_rs::sig(fn(&String[@a], &String[@b]) -> bool[a == b])]
+    fn eq(&self, other: &String) -> bool;
+}
+
+#[extern_spec(alloc::string)]
+impl<'a, 'b> PartialEq<str> for String {
+    #[flux_rs::sig(fn(&String[@a], &str[@b]) -> bool[a == b])]
+    fn eq(&self, other: &str) -> bool;
+}
+
+#[extern_spec(alloc::string)]
+impl<'a, 'b> PartialEq<&'a str> for String {
+    #[flux_rs::sig(fn(&String[@a], & &str[@b]) -> bool[a == b])]
+    fn eq(&self, other: &&'a str) -> bool;
+}
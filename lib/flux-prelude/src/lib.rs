@@ -0,0 +1,428 @@
+//! Refined specifications for the standard collections shipped with the flux sysroot.
+//!
+//! This crate is compiled with flux along with `flux-core`, but its specs are only loaded when a
+//! crate enables them with `#![flux::cfg(prelude = true)]` or when one of its dependencies was
+//! checked with them. The collections are refined as follows:
+//!
+//! * `Vec<T>`, `VecDeque<T>` and slices are refined by their length.
+//! * `HashMap<K, V>` is refined by the set of its keys and `HashSet<T>` by the set of its elements.
+//!   Keys are tracked by their index, so lookups only tell whether a key is in the map when the
+//!   index of a key determines it, i.e., for integers, booleans and `char` (see `exact_index`).
+//! * `String` is refined by its contents (see `flux-core`), here we only add the conversions from
+//!   and to `Vec<u8>`.
+//!
+//! Indexing a `Vec` or a slice with a `usize` or a `Range<usize>` requires the index to be in
+//! bounds.
+#![feature(allocator_api)]
+#![allow(unused)]
+
+use std::{
+    alloc::{Allocator, Global},
+    borrow::Borrow,
+    collections::{hash_map::RandomState, HashMap, HashSet, VecDeque},
+    hash::{BuildHasher, Hash},
+    ops::{Index, IndexMut, Range},
+    slice::SliceIndex,
+};
+
+use flux_rs::extern_spec;
+
+// -------------------------------------------------------------------------------------------------
+// Slices
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(std::slice)]
+#[flux_rs::generics(Self as base, T as base)]
+#[flux_rs::assoc(fn in_bounds(idx: Self, v: T) -> bool)]
+trait SliceIndex<T: ?Sized> {}
+
+#[extern_spec(std::slice)]
+#[flux_rs::assoc(fn in_bounds(idx: int, len: int) -> bool { idx < len })]
+impl<T> SliceIndex<[T]> for usize {}
+
+#[extern_spec(std::slice)]
+#[flux_rs::assoc(fn in_bounds(r: Range<int>, len: int) -> bool { r.start <= r.end && r.end <= len })]
+impl<T> SliceIndex<[T]> for Range<usize> {}
+
+#[extern_spec]
+impl<T> [T] {
+    #[flux_rs::sig(fn(&[T][@n]) -> usize[n])]
+    const fn len(&self) -> usize;
+
+    #[flux_rs::sig(fn(&[T][@n]) -> bool[n == 0])]
+    const fn is_empty(&self) -> bool;
+
+    #[flux_rs::generics(I as base)]
+    #[flux_rs::sig(
+        fn(&[T][@n], I[@idx]) -> Option<&I::Output>[<I as SliceIndex<[T]>>::in_bounds(idx, n)]
+    )]
+    fn get<I: SliceIndex<[T]>>(&self, index: I) -> Option<&I::Output>;
+
+    #[flux_rs::generics(I as base)]
+    #[flux_rs::sig(
+        fn(&mut [T][@n], I[@idx]) -> Option<&mut I::Output>[<I as SliceIndex<[T]>>::in_bounds(idx, n)]
+    )]
+    fn get_mut<I: SliceIndex<[T]>>(&mut self, index: I) -> Option<&mut I::Output>;
+
+    #[flux_rs::sig(fn(&[T][@n]) -> Option<&T>[n > 0])]
+    const fn first(&self) -> Option<&T>;
+
+    #[flux_rs::sig(fn(&[T][@n]) -> Option<&T>[n > 0])]
+    const fn last(&self) -> Option<&T>;
+
+    #[flux_rs::sig(fn(&mut [T][@n], usize{v: v < n}, usize{v: v < n}))]
+    fn swap(&mut self, a: usize, b: usize);
+
+    #[flux_rs::sig(fn(&[T][@n], usize[@mid]) -> (&[T][mid], &[T][n - mid]) requires mid <= n)]
+    fn split_at(&self, mid: usize) -> (&[T], &[T]);
+}
+
+// The methods of slices that allocate are defined in a separate impl block in `alloc`
+#[extern_spec]
+impl<T> [T] {
+    #[flux_rs::sig(fn(self: Box<[T][@n], A>) -> Vec<T, A>[n])]
+    fn into_vec<A: Allocator>(self: Box<[T], A>) -> Vec<T, A>;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Vec
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(std::vec)]
+#[flux_rs::refined_by(len: int)]
+#[flux_rs::invariant(len >= 0)]
+struct Vec<T, A: Allocator = Global>;
+
+#[extern_spec(std::vec)]
+impl<T> Vec<T> {
+    #[flux_rs::sig(fn() -> Vec<T>[0])]
+    const fn new() -> Vec<T>;
+
+    #[flux_rs::sig(fn(usize) -> Vec<T>[0])]
+    fn with_capacity(capacity: usize) -> Vec<T>;
+}
+
+#[extern_spec(std::vec)]
+impl<T, A: Allocator> Vec<T, A> {
+    #[flux_rs::sig(fn(&Vec<T, A>[@n]) -> usize[n])]
+    fn len(&self) -> usize;
+
+    #[flux_rs::sig(fn(&Vec<T, A>[@n]) -> bool[n == 0])]
+    fn is_empty(&self) -> bool;
+
+    #[flux_rs::sig(fn(self: &strg Vec<T, A>[@n], T) ensures self: Vec<T, A>[n + 1])]
+    fn push(&mut self, value: T);
+
+    #[flux_rs::sig(
+        fn(self: &strg Vec<T, A>[@n]) -> Option<T>[n > 0]
+        ensures self: Vec<T, A>[if n > 0 { n - 1 } else { 0 }]
+    )]
+    fn pop(&mut self) -> Option<T>;
+
+    #[flux_rs::sig(
+        fn(self: &strg Vec<T, A>[@n], usize{v: v <= n}, T)
+        ensures self: Vec<T, A>[n + 1]
+    )]
+    fn insert(&mut self, index: usize, element: T);
+
+    #[flux_rs::sig(
+        fn(self: &strg Vec<T, A>[@n], usize{v: v < n}) -> T
+        ensures self: Vec<T, A>[n - 1]
+    )]
+    fn remove(&mut self, index: usize) -> T;
+
+    #[flux_rs::sig(
+        fn(self: &strg Vec<T, A>[@n], usize{v: v < n}) -> T
+        ensures self: Vec<T, A>[n - 1]
+    )]
+    fn swap_remove(&mut self, index: usize) -> T;
+
+    #[flux_rs::sig(
+        fn(self: &strg Vec<T, A>[@n], usize[@m])
+        ensures self: Vec<T, A>[if m < n { m } else { n }]
+    )]
+    fn truncate(&mut self, len: usize);
+
+    #[flux_rs::sig(fn(self: &strg Vec<T, A>) ensures self: Vec<T, A>[0])]
+    fn clear(&mut self);
+
+    #[flux_rs::sig(fn(&Vec<T, A>[@n]) -> &[T][n])]
+    fn as_slice(&self) -> &[T];
+
+    #[flux_rs::sig(fn(&mut Vec<T, A>[@n]) -> &mut [T][n])]
+    fn as_mut_slice(&mut self) -> &mut [T];
+}
+
+// Used by the expansion of `vec![elem; n]`
+#[extern_spec(std::vec)]
+#[flux_rs::sig(fn(T, usize[@n]) -> Vec<T>[n])]
+fn from_elem<T: Clone>(elem: T, n: usize) -> Vec<T>;
+
+#[extern_spec(std::vec)]
+#[flux_rs::generics(I as base)]
+impl<T, I: SliceIndex<[T]>, A: Allocator> Index<I> for Vec<T, A> {
+    #[flux_rs::sig(fn(&Vec<T, A>[@n], {I[@idx] | <I as SliceIndex<[T]>>::in_bounds(idx, n)}) -> _)]
+    fn index(&self, index: I) -> &<I as SliceIndex<[T]>>::Output;
+}
+
+#[extern_spec(std::vec)]
+#[flux_rs::generics(I as base)]
+impl<T, I: SliceIndex<[T]>, A: Allocator> IndexMut<I> for Vec<T, A> {
+    #[flux_rs::sig(fn(&mut Vec<T, A>[@n], {I[@idx] | <I as SliceIndex<[T]>>::in_bounds(idx, n)}) -> _)]
+    fn index_mut(&mut self, index: I) -> &mut <I as SliceIndex<[T]>>::Output;
+}
+
+// -------------------------------------------------------------------------------------------------
+// VecDeque
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(std::collections)]
+#[flux_rs::refined_by(len: int)]
+#[flux_rs::invariant(len >= 0)]
+struct VecDeque<T, A: Allocator = Global>;
+
+#[extern_spec(std::collections)]
+impl<T> VecDeque<T> {
+    #[flux_rs::sig(fn() -> VecDeque<T>[0])]
+    const fn new() -> VecDeque<T>;
+
+    #[flux_rs::sig(fn(usize) -> VecDeque<T>[0])]
+    fn with_capacity(capacity: usize) -> VecDeque<T>;
+}
+
+#[extern_spec(std::collections)]
+impl<T, A: Allocator> VecDeque<T, A> {
+    #[flux_rs::sig(fn(&VecDeque<T, A>[@n]) -> usize[n])]
+    fn len(&self) -> usize;
+
+    #[flux_rs::sig(fn(&VecDeque<T, A>[@n]) -> bool[n == 0])]
+    fn is_empty(&self) -> bool;
+
+    #[flux_rs::sig(fn(&VecDeque<T, A>[@n], usize[@i]) -> Option<&T>[i < n])]
+    fn get(&self, index: usize) -> Option<&T>;
+
+    #[flux_rs::sig(fn(&VecDeque<T, A>[@n]) -> Option<&T>[n > 0])]
+    fn front(&self) -> Option<&T>;
+
+    #[flux_rs::sig(fn(&VecDeque<T, A>[@n]) -> Option<&T>[n > 0])]
+    fn back(&self) -> Option<&T>;
+
+    #[flux_rs::sig(fn(self: &strg VecDeque<T, A>[@n], T) ensures self: VecDeque<T, A>[n + 1])]
+    fn push_back(&mut self, value: T);
+
+    #[flux_rs::sig(fn(self: &strg VecDeque<T, A>[@n], T) ensures self: VecDeque<T, A>[n + 1])]
+    fn push_front(&mut self, value: T);
+
+    #[flux_rs::sig(
+        fn(self: &strg VecDeque<T, A>[@n]) -> Option<T>[n > 0]
+        ensures self: VecDeque<T, A>[if n > 0 { n - 1 } else { 0 }]
+    )]
+    fn pop_back(&mut self) -> Option<T>;
+
+    #[flux_rs::sig(
+        fn(self: &strg VecDeque<T, A>[@n]) -> Option<T>[n > 0]
+        ensures self: VecDeque<T, A>[if n > 0 { n - 1 } else { 0 }]
+    )]
+    fn pop_front(&mut self) -> Option<T>;
+
+    #[flux_rs::sig(fn(self: &strg VecDeque<T, A>) ensures self: VecDeque<T, A>[0])]
+    fn clear(&mut self);
+}
+
+// -------------------------------------------------------------------------------------------------
+// Borrow
+// -------------------------------------------------------------------------------------------------
+
+// Maps and sets are queried with a borrowed form of their keys. The associated refinement `owned`
+// maps the index of the borrowed form back to the index of the key.
+#[extern_spec(std::borrow)]
+#[flux_rs::generics(Self as base, Borrowed as base)]
+#[flux_rs::assoc(fn owned(x: Borrowed) -> Self)]
+trait Borrow<Borrowed: ?Sized> {}
+
+#[extern_spec(std::borrow)]
+#[flux_rs::assoc(fn owned(x: T) -> T { x })]
+impl<T> Borrow<T> for T {}
+
+#[extern_spec(std::borrow)]
+#[flux_rs::assoc(fn owned(x: str) -> String { String { s: x } })]
+impl Borrow<str> for String {}
+
+// -------------------------------------------------------------------------------------------------
+// Hash
+// -------------------------------------------------------------------------------------------------
+
+// The associated refinement `exact_index` holds for keys whose index determines their value, i.e.,
+// two keys are equal iff their indices are. Any other key type, e.g., one refined by nothing, has
+// many keys with the same index, so the membership of its index in the set of keys says nothing
+// about the membership of the key.
+#[extern_spec(core::hash)]
+#[flux_rs::generics(Self as base)]
+#[flux_rs::assoc(fn exact_index(x: Self) -> bool { false })]
+trait Hash {}
+
+macro_rules! hash_exact {
+    ($($ty:ident)*) => {$(
+        #[extern_spec(core::hash)]
+        #[flux_rs::assoc(fn exact_index(x: Self) -> bool { true })]
+        impl Hash for $ty {}
+    )*};
+}
+
+hash_exact! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize bool char }
+
+// -------------------------------------------------------------------------------------------------
+// HashMap
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(std::collections)]
+#[flux_rs::refined_by(keys: Set<K>)]
+struct HashMap<K, V, S = RandomState>;
+
+#[extern_spec(std::collections)]
+#[flux_rs::generics(K as base)]
+impl<K, V> HashMap<K, V> {
+    #[flux_rs::sig(fn() -> HashMap<K, V>[set_empty(0)])]
+    fn new() -> HashMap<K, V>;
+
+    #[flux_rs::sig(fn(usize) -> HashMap<K, V>[set_empty(0)])]
+    fn with_capacity(capacity: usize) -> HashMap<K, V>;
+}
+
+#[extern_spec(std::collections)]
+#[flux_rs::generics(K as base)]
+impl<K, V, S> HashMap<K, V, S> {
+    #[flux_rs::sig(fn(self: &strg HashMap<K, V, S>) ensures self: HashMap<K, V, S>[set_empty(0)])]
+    fn clear(&mut self);
+}
+
+#[extern_spec(std::collections)]
+#[flux_rs::generics(K as base)]
+impl<K: Eq + Hash, V, S: BuildHasher> HashMap<K, V, S> {
+    #[flux_rs::sig(
+        fn(&HashMap<K, V, S>[@m], &Q[@k]) -> bool{b:
+            <K as Hash>::exact_index(<K as Borrow<Q>>::owned(k))
+                => b == set_is_in(<K as Borrow<Q>>::owned(k), m.keys)
+        }
+    )]
+    fn contains_key<Q: ?Sized + Hash + Eq>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>;
+
+    #[flux_rs::sig(
+        fn(&HashMap<K, V, S>[@m], &Q[@k]) -> Option<&V>{b:
+            <K as Hash>::exact_index(<K as Borrow<Q>>::owned(k))
+                => b == set_is_in(<K as Borrow<Q>>::owned(k), m.keys)
+        }
+    )]
+    fn get<'a, Q: ?Sized + Hash + Eq>(&'a self, k: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>;
+
+    #[flux_rs::sig(
+        fn(&mut HashMap<K, V, S>[@m], &Q[@k]) -> Option<&mut V>{b:
+            <K as Hash>::exact_index(<K as Borrow<Q>>::owned(k))
+                => b == set_is_in(<K as Borrow<Q>>::owned(k), m.keys)
+        }
+    )]
+    fn get_mut<'a, Q: ?Sized + Hash + Eq>(&'a mut self, k: &Q) -> Option<&'a mut V>
+    where
+        K: Borrow<Q>;
+
+    #[flux_rs::sig(
+        fn(self: &strg HashMap<K, V, S>[@m], K[@k], V)
+            -> Option<V>{b: <K as Hash>::exact_index(k) => b == set_is_in(k, m.keys)}
+        ensures self: HashMap<K, V, S>[set_union(set_singleton(k), m.keys)]
+    )]
+    fn insert(&mut self, k: K, v: V) -> Option<V>;
+
+    #[flux_rs::sig(
+        fn(self: &strg HashMap<K, V, S>[@m], &Q[@k]) -> Option<V>{b:
+            <K as Hash>::exact_index(<K as Borrow<Q>>::owned(k))
+                => b == set_is_in(<K as Borrow<Q>>::owned(k), m.keys)
+        }
+        ensures self: HashMap<K, V, S>{r:
+            <K as Hash>::exact_index(<K as Borrow<Q>>::owned(k))
+                => !set_is_in(<K as Borrow<Q>>::owned(k), r.keys)
+        }
+    )]
+    fn remove<Q: ?Sized + Hash + Eq>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>;
+}
+
+// -------------------------------------------------------------------------------------------------
+// HashSet
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(std::collections)]
+#[flux_rs::refined_by(elems: Set<T>)]
+struct HashSet<T, S = RandomState>;
+
+#[extern_spec(std::collections)]
+#[flux_rs::generics(T as base)]
+impl<T> HashSet<T> {
+    #[flux_rs::sig(fn() -> HashSet<T>[set_empty(0)])]
+    fn new() -> HashSet<T>;
+
+    #[flux_rs::sig(fn(usize) -> HashSet<T>[set_empty(0)])]
+    fn with_capacity(capacity: usize) -> HashSet<T>;
+}
+
+#[extern_spec(std::collections)]
+#[flux_rs::generics(T as base)]
+impl<T, S> HashSet<T, S> {
+    #[flux_rs::sig(fn(self: &strg HashSet<T, S>) ensures self: HashSet<T, S>[set_empty(0)])]
+    fn clear(&mut self);
+}
+
+#[extern_spec(std::collections)]
+#[flux_rs::generics(T as base)]
+impl<T: Eq + Hash, S: BuildHasher> HashSet<T, S> {
+    #[flux_rs::sig(
+        fn(&HashSet<T, S>[@s], &Q[@v]) -> bool{b:
+            <T as Hash>::exact_index(<T as Borrow<Q>>::owned(v))
+                => b == set_is_in(<T as Borrow<Q>>::owned(v), s.elems)
+        }
+    )]
+    fn contains<Q: ?Sized + Hash + Eq>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>;
+
+    #[flux_rs::sig(
+        fn(self: &strg HashSet<T, S>[@s], T[@v])
+            -> bool{b: <T as Hash>::exact_index(v) => b == !set_is_in(v, s.elems)}
+        ensures self: HashSet<T, S>[set_union(set_singleton(v), s.elems)]
+    )]
+    fn insert(&mut self, value: T) -> bool;
+
+    #[flux_rs::sig(
+        fn(self: &strg HashSet<T, S>[@s], &Q[@v]) -> bool{b:
+            <T as Hash>::exact_index(<T as Borrow<Q>>::owned(v))
+                => b == set_is_in(<T as Borrow<Q>>::owned(v), s.elems)
+        }
+        ensures self: HashSet<T, S>{r:
+            <T as Hash>::exact_index(<T as Borrow<Q>>::owned(v))
+                => !set_is_in(<T as Borrow<Q>>::owned(v), r.elems)
+        }
+    )]
+    fn remove<Q: ?Sized + Hash + Eq>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>;
+}
+
+// -------------------------------------------------------------------------------------------------
+// String
+// -------------------------------------------------------------------------------------------------
+
+#[extern_spec(std::string)]
+impl String {
+    #[flux_rs::sig(fn(usize) -> String[""])]
+    fn with_capacity(capacity: usize) -> String;
+
+    #[flux_rs::sig(fn(self: &strg String) ensures self: String[""])]
+    fn clear(&mut self);
+
+    #[flux_rs::sig(fn(String[@s]) -> Vec<u8>[str_len(s)])]
+    fn into_bytes(self) -> Vec<u8>;
+}
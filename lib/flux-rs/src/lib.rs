@@ -59,6 +59,16 @@ pub fn trusted_impl(attr: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::trusted_impl(attr, tokens)
 }
 
+#[proc_macro_attribute]
+pub fn trusted_derive(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::trusted_derive(attr, tokens)
+}
+
+#[proc_macro_attribute]
+pub fn ignore_derive(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::ignore_derive(attr, tokens)
+}
+
 #[proc_macro_attribute]
 pub fn generics(attr: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::generics(attr, tokens)
@@ -94,6 +104,11 @@ pub fn should_fail(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::should_fail(attrs, tokens)
 }
 
+#[proc_macro_attribute]
+pub fn explain(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::explain(attrs, tokens)
+}
+
 #[cfg(flux_sysroot)]
 mod attr_sysroot {
     use super::*;
@@ -131,10 +146,13 @@ mod attr_sysroot {
         check_overflow,
         trusted,
         trusted_impl,
+        trusted_derive,
+        ignore_derive,
         generics,
         assoc,
         ignore,
-        should_fail
+        should_fail,
+        explain
     );
 }
 
@@ -175,9 +193,12 @@ mod attr_dummy {
         check_overflow,
         trusted,
         trusted_impl,
+        trusted_derive,
+        ignore_derive,
         generics,
         assoc,
         ignore,
         should_fail,
+        explain,
     );
 }
@@ -12,36 +12,36 @@ pub(crate) static DEFAULT_QUALIFIERS: LazyLock<[Qualifier<DefaultTypes>; 11]> =
 
         // (qualif EqZero ((v int)) (v == 0))
         let eqzero = Qualifier {
-            args: vec![("v", Sort::Int)],
-            body: Expr::Atom(BinRel::Eq, Box::new([Expr::Var("v"), Expr::int(0)])),
+            args: vec![("v".to_string(), Sort::Int)],
+            body: Expr::Atom(BinRel::Eq, Box::new([Expr::Var("v".to_string()), Expr::int(0)])),
             name: String::from("EqZero"),
         };
 
         // (qualif GtZero ((v int)) (v > 0))
         let gtzero = Qualifier {
-            args: vec![("v", Sort::Int)],
-            body: Expr::Atom(BinRel::Gt, Box::new([Expr::Var("v"), Expr::int(0)])),
+            args: vec![("v".to_string(), Sort::Int)],
+            body: Expr::Atom(BinRel::Gt, Box::new([Expr::Var("v".to_string()), Expr::int(0)])),
             name: String::from("GtZero"),
         };
 
         // (qualif GeZero ((v int)) (v >= 0))
         let gezero = Qualifier {
-            args: vec![("v", Sort::Int)],
-            body: Expr::Atom(BinRel::Ge, Box::new([Expr::Var("v"), Expr::int(0)])),
+            args: vec![("v".to_string(), Sort::Int)],
+            body: Expr::Atom(BinRel::Ge, Box::new([Expr::Var("v".to_string()), Expr::int(0)])),
             name: String::from("GeZero"),
         };
 
         // (qualif LtZero ((v int)) (v < 0))
         let ltzero = Qualifier {
-            args: vec![("v", Sort::Int)],
-            body: Expr::Atom(BinRel::Lt, Box::new([Expr::Var("v"), Expr::int(0)])),
+            args: vec![("v".to_string(), Sort::Int)],
+            body: Expr::Atom(BinRel::Lt, Box::new([Expr::Var("v".to_string()), Expr::int(0)])),
             name: String::from("LtZero"),
         };
 
         // (qualif LeZero ((v int)) (v <= 0))
         let lezero = Qualifier {
-            args: vec![("v", Sort::Int)],
-            body: Expr::Atom(BinRel::Le, Box::new([Expr::Var("v"), Expr::int(0)])),
+            args: vec![("v".to_string(), Sort::Int)],
+            body: Expr::Atom(BinRel::Le, Box::new([Expr::Var("v".to_string()), Expr::int(0)])),
             name: String::from("LeZero"),
         };
 
@@ -51,47 +51,65 @@ pub(crate) static DEFAULT_QUALIFIERS: LazyLock<[Qualifier<DefaultTypes>; 11]> =
 
         // (qualif Eq ((a int) (b int)) (a == b))
         let eq = Qualifier {
-            args: vec![("a", Sort::Int), ("b", Sort::Int)],
-            body: Expr::Atom(BinRel::Eq, Box::new([Expr::Var("a"), Expr::Var("b")])),
+            args: vec![("a".to_string(), Sort::Int), ("b".to_string(), Sort::Int)],
+            body: Expr::Atom(
+                BinRel::Eq,
+                Box::new([Expr::Var("a".to_string()), Expr::Var("b".to_string())]),
+            ),
             name: String::from("Eq"),
         };
 
         // (qualif Gt ((a int) (b int)) (a > b))
         let gt = Qualifier {
-            args: vec![("a", Sort::Int), ("b", Sort::Int)],
-            body: Expr::Atom(BinRel::Gt, Box::new([Expr::Var("a"), Expr::Var("b")])),
+            args: vec![("a".to_string(), Sort::Int), ("b".to_string(), Sort::Int)],
+            body: Expr::Atom(
+                BinRel::Gt,
+                Box::new([Expr::Var("a".to_string()), Expr::Var("b".to_string())]),
+            ),
             name: String::from("Gt"),
         };
 
         // (qualif Lt ((a int) (b int)) (a < b))
         let ge = Qualifier {
-            args: vec![("a", Sort::Int), ("b", Sort::Int)],
-            body: Expr::Atom(BinRel::Ge, Box::new([Expr::Var("a"), Expr::Var("b")])),
+            args: vec![("a".to_string(), Sort::Int), ("b".to_string(), Sort::Int)],
+            body: Expr::Atom(
+                BinRel::Ge,
+                Box::new([Expr::Var("a".to_string()), Expr::Var("b".to_string())]),
+            ),
             name: String::from("Ge"),
         };
 
         // (qualif Ge ((a int) (b int)) (a >= b))
         let lt = Qualifier {
-            args: vec![("a", Sort::Int), ("b", Sort::Int)],
-            body: Expr::Atom(BinRel::Lt, Box::new([Expr::Var("a"), Expr::Var("b")])),
+            args: vec![("a".to_string(), Sort::Int), ("b".to_string(), Sort::Int)],
+            body: Expr::Atom(
+                BinRel::Lt,
+                Box::new([Expr::Var("a".to_string()), Expr::Var("b".to_string())]),
+            ),
             name: String::from("Lt"),
         };
 
         // (qualif Le ((a int) (b int)) (a <= b))
         let le = Qualifier {
-            args: vec![("a", Sort::Int), ("b", Sort::Int)],
-            body: Expr::Atom(BinRel::Le, Box::new([Expr::Var("a"), Expr::Var("b")])),
+            args: vec![("a".to_string(), Sort::Int), ("b".to_string(), Sort::Int)],
+            body: Expr::Atom(
+                BinRel::Le,
+                Box::new([Expr::Var("a".to_string()), Expr::Var("b".to_string())]),
+            ),
             name: String::from("Le"),
         };
 
         // (qualif Le1 ((a int) (b int)) (a < b - 1))
         let le1 = Qualifier {
-            args: vec![("a", Sort::Int), ("b", Sort::Int)],
+            args: vec![("a".to_string(), Sort::Int), ("b".to_string(), Sort::Int)],
             body: Expr::Atom(
                 BinRel::Le,
                 Box::new([
-                    Expr::Var("a"),
-                    Expr::BinaryOp(BinOp::Sub, Box::new([Expr::Var("b"), Expr::int(1)])),
+                    Expr::Var("a".to_string()),
+                    Expr::BinaryOp(
+                        BinOp::Sub,
+                        Box::new([Expr::Var("b".to_string()), Expr::int(1)]),
+                    ),
                 ]),
             ),
             name: String::from("Le1"),
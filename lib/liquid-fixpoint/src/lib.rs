@@ -4,6 +4,7 @@
 
 mod constraint;
 mod format;
+mod parser;
 
 use std::{
     collections::hash_map::DefaultHasher,
@@ -19,6 +20,7 @@ pub use constraint::{
     Qualifier, Sort, SortCtor,
 };
 use derive_where::derive_where;
+pub use parser::{ParseError, Span};
 use serde::{de, Deserialize, Serialize};
 
 pub trait Types {
@@ -63,24 +65,40 @@ pub trait Identifier: Sized {
     }
 }
 
-struct DefaultTypes;
+/// A set of [`Types`] using plain strings for names. This is the instantiation produced by
+/// [`Task::parse`].
+pub struct DefaultTypes;
 
 impl Types for DefaultTypes {
-    type Sort = &'static str;
-    type KVar = &'static str;
-    type Var = &'static str;
+    type Sort = String;
+    type KVar = String;
+    type Var = String;
     type Tag = String;
     type Numeral = i128;
-    type Decimal = i128;
+    type Decimal = Real;
     type String = String;
 }
 
+/// A decimal constant denoting `mantissa * 10^-scale`, e.g., `1.25` is represented with mantissa
+/// `125` and scale `2`. The scale is kept as written so a parsed literal is printed back unchanged.
+#[derive(Hash, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Real {
+    pub mantissa: i128,
+    pub scale: u32,
+}
+
 impl Identifier for &str {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{self}")
     }
 }
 
+impl Identifier for String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
 impl FixpointFmt for i128 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if *self < 0 {
@@ -91,9 +109,41 @@ impl FixpointFmt for i128 {
     }
 }
 
+impl FixpointFmt for Real {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if self.mantissa < 0 {
+            write!(f, "(- ")?;
+        }
+        if scale == 0 {
+            write!(f, "{digits}.0")?;
+        } else if digits.len() > scale {
+            let (int, frac) = digits.split_at(digits.len() - scale);
+            write!(f, "{int}.{frac}")?;
+        } else {
+            write!(f, "0.{digits:0>scale$}")?;
+        }
+        if self.mantissa < 0 {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
 impl FixpointFmt for String {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\"{self}\"")
+        // Same escapes as the ones read by the parser, i.e., Haskell's escapes for quotes and
+        // backslashes, and a decimal code followed by the empty escape `\&` for other characters.
+        write!(f, "\"")?;
+        for c in self.chars() {
+            match c {
+                '"' | '\\' => write!(f, "\\{c}")?,
+                ' '..='~' => write!(f, "{c}")?,
+                _ => write!(f, "\\{}\\&", c as u32)?,
+            }
+        }
+        write!(f, "\"")
     }
 }
 
@@ -0,0 +1,656 @@
+//! A parser for the textual task format printed by [`Task`]'s [`fmt::Display`] implementation.
+//!
+//! The parser works in two stages: the input is first read into a tree of s-expressions which is
+//! then converted into a [`Task<DefaultTypes>`]. Parsing the output of printing a task and printing
+//! it again yields the same text.
+
+use std::fmt;
+
+use crate::{
+    constraint::DEFAULT_QUALIFIERS, BinOp, BinRel, Bind, ConstDecl, Constant, Constraint, DataCtor,
+    DataDecl, DataField, DefaultTypes, Expr, KVarDecl, Pred, Qualifier, Real, Sort, SortCtor, Task,
+};
+
+type Result<T = ()> = std::result::Result<T, ParseError>;
+
+/// A range of byte offsets in the parsed source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub lo: usize,
+    pub hi: usize,
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub msg: String,
+    pub span: Span,
+}
+
+impl ParseError {
+    fn new(msg: impl Into<String>, span: Span) -> Self {
+        Self { msg: msg.into(), span }
+    }
+
+    /// Returns the (1-based) line and column where the error starts in `src`.
+    pub fn line_col(&self, src: &str) -> (usize, usize) {
+        let before = &src[..self.span.lo.min(src.len())];
+        let line = before.matches('\n').count() + 1;
+        let col = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        (line, col)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.msg, self.span.lo, self.span.hi)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Task<DefaultTypes> {
+    /// Parses a task in the format produced by printing a [`Task`].
+    ///
+    /// The default qualifiers are always printed as part of a task, thus, qualifiers identical to
+    /// one of the defaults are skipped. A different qualifier with the name of a default one is an
+    /// error because it cannot be printed without clashing with the default.
+    pub fn parse(src: &str) -> Result<Self> {
+        let items = Reader::new(src).read_items()?;
+
+        let mut comments = vec![];
+        let mut constants = vec![];
+        let mut data_decls = vec![];
+        let mut kvars = vec![];
+        let mut qualifiers = vec![];
+        let mut constraint = None;
+        let mut scrape_quals = false;
+        let mut seen_item = false;
+
+        let mut items = items.into_iter().peekable();
+        while let Some(item) = items.next() {
+            let sexp = match item {
+                Item::Comment(text, _) => {
+                    if !seen_item {
+                        comments.push(text);
+                    }
+                    continue;
+                }
+                Item::Sexp(sexp) => sexp,
+            };
+            // A comment starting in the same line where an item ends is attached to the item
+            let end_line = src[..sexp.span.hi].matches('\n').count();
+            let mut trailing_comment = || {
+                match items
+                    .next_if(|item| matches!(item, Item::Comment(_, line) if *line == end_line))
+                {
+                    Some(Item::Comment(text, _)) => Some(text),
+                    _ => None,
+                }
+            };
+
+            let (head, args) = sexp.expect_app()?;
+            match head {
+                "fixpoint" => {
+                    let [opt] = expect_args(&sexp, args)?;
+                    if opt.expect_str()? != "--scrape=both" {
+                        return Err(ParseError::new("unsupported fixpoint option", opt.span));
+                    }
+                    scrape_quals = true;
+                    continue;
+                }
+                "datatype" => data_decls.push(parse_data_decl(&sexp, args)?),
+                "qualif" => {
+                    let qualif = parse_qualifier(&sexp, args)?;
+                    match DEFAULT_QUALIFIERS.iter().find(|q| q.name == qualif.name) {
+                        Some(default) if default.to_string() == qualif.to_string() => {}
+                        Some(_) => {
+                            return Err(ParseError::new(
+                                format!(
+                                    "qualifier `{}` clashes with a default qualifier",
+                                    qualif.name
+                                ),
+                                sexp.span,
+                            ));
+                        }
+                        None => qualifiers.push(qualif),
+                    }
+                }
+                "constant" => {
+                    let [name, sort] = expect_args(&sexp, args)?;
+                    constants.push(ConstDecl {
+                        name: name.expect_atom()?.to_string(),
+                        sort: parse_sort(sort)?,
+                        comment: trailing_comment(),
+                    });
+                }
+                "var" => {
+                    let [kvid, sorts] = expect_args(&sexp, args)?;
+                    let kvid = expect_kvar(kvid)?;
+                    let sorts = sorts
+                        .expect_list()?
+                        .iter()
+                        .map(parse_sort)
+                        .collect::<Result<_>>()?;
+                    kvars.push(KVarDecl::new(kvid, sorts, trailing_comment().unwrap_or_default()));
+                }
+                "constraint" => {
+                    if constraint.is_some() {
+                        return Err(ParseError::new("duplicate constraint", sexp.span));
+                    }
+                    let [cstr] = expect_args(&sexp, args)?;
+                    constraint = Some(parse_constraint(cstr)?);
+                }
+                _ => return Err(ParseError::new(format!("unexpected item `{head}`"), sexp.span)),
+            }
+            seen_item = true;
+        }
+
+        let constraint = constraint.ok_or_else(|| {
+            ParseError::new("missing constraint", Span { lo: src.len(), hi: src.len() })
+        })?;
+        Ok(Task { comments, constants, data_decls, kvars, constraint, qualifiers, scrape_quals })
+    }
+}
+
+fn parse_data_decl(sexp: &Sexp, args: &[Sexp]) -> Result<DataDecl<DefaultTypes>> {
+    let [name_and_vars, ctors] = expect_args(sexp, args)?;
+    let [name, vars] = expect_list_of(name_and_vars)?;
+    let ctors = ctors
+        .expect_list()?
+        .iter()
+        .map(|ctor| {
+            let [name, fields] = expect_list_of(ctor)?;
+            let fields = fields
+                .expect_list()?
+                .iter()
+                .map(|field| {
+                    let [name, sort] = expect_list_of(field)?;
+                    Ok(DataField { name: name.expect_atom()?.to_string(), sort: parse_sort(sort)? })
+                })
+                .collect::<Result<_>>()?;
+            Ok(DataCtor { name: name.expect_atom()?.to_string(), fields })
+        })
+        .collect::<Result<_>>()?;
+    Ok(DataDecl { name: name.expect_atom()?.to_string(), vars: parse_usize(vars)?, ctors })
+}
+
+fn parse_qualifier(sexp: &Sexp, args: &[Sexp]) -> Result<Qualifier<DefaultTypes>> {
+    let [name, params, body] = expect_args(sexp, args)?;
+    let args = params
+        .expect_list()?
+        .iter()
+        .map(|param| {
+            let [name, sort] = expect_list_of(param)?;
+            Ok((name.expect_atom()?.to_string(), parse_sort(sort)?))
+        })
+        .collect::<Result<_>>()?;
+    let [body] = expect_list_of(body)?;
+    Ok(Qualifier { name: name.expect_atom()?.to_string(), args, body: parse_expr(body)? })
+}
+
+fn parse_constraint(sexp: &Sexp) -> Result<Constraint<DefaultTypes>> {
+    if let Ok((head, args)) = sexp.expect_app() {
+        match head {
+            "forall" => {
+                let [bind, body] = expect_args(sexp, args)?;
+                let [name_and_sort, pred] = expect_list_of(bind)?;
+                let [name, sort] = expect_list_of(name_and_sort)?;
+                let bind = Bind {
+                    name: name.expect_atom()?.to_string(),
+                    sort: parse_sort(sort)?,
+                    pred: parse_pred(pred)?,
+                };
+                return Ok(Constraint::ForAll(bind, Box::new(parse_constraint(body)?)));
+            }
+            "and" => {
+                let cstrs = args.iter().map(parse_constraint).collect::<Result<_>>()?;
+                return Ok(Constraint::Conj(cstrs));
+            }
+            "tag" => {
+                let [pred, tag] = expect_args(sexp, args)?;
+                return Ok(Constraint::Pred(
+                    parse_pred(pred)?,
+                    Some(tag.expect_str()?.to_string()),
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(Constraint::Pred(parse_pred(sexp)?, None))
+}
+
+fn parse_pred(sexp: &Sexp) -> Result<Pred<DefaultTypes>> {
+    let list = sexp.expect_list()?;
+    match list {
+        [Sexp { kind: SexpKind::Atom(head), .. }, args @ ..] if head == "and" => {
+            Ok(Pred::And(args.iter().map(parse_pred).collect::<Result<_>>()?))
+        }
+        [kvid @ Sexp { kind: SexpKind::Atom(head), .. }, args @ ..] if head.starts_with('$') => {
+            let args = args
+                .iter()
+                .map(|arg| Ok(arg.expect_atom()?.to_string()))
+                .collect::<Result<_>>()?;
+            Ok(Pred::KVar(expect_kvar(kvid)?, args))
+        }
+        [e] => Ok(Pred::Expr(parse_expr(e)?)),
+        _ => Err(ParseError::new("expected predicate", sexp.span)),
+    }
+}
+
+fn parse_expr(sexp: &Sexp) -> Result<Expr<DefaultTypes>> {
+    let list = match &sexp.kind {
+        SexpKind::Atom(atom) => return parse_atom(atom, sexp.span),
+        SexpKind::Str(s) => return Ok(Expr::Constant(Constant::String(s.clone()))),
+        SexpKind::List(list) => list,
+    };
+    let [head, args @ ..] = &list[..] else {
+        return Err(ParseError::new("expected expression", sexp.span));
+    };
+    if let SexpKind::Atom(op) = &head.kind {
+        match (op.as_str(), args) {
+            ("-", [e]) => {
+                return match parse_expr(e)? {
+                    Expr::Constant(Constant::Numeral(n)) => Ok(Expr::int(-n)),
+                    Expr::Constant(Constant::Decimal(r)) => {
+                        Ok(Expr::Constant(Constant::Decimal(Real { mantissa: -r.mantissa, ..r })))
+                    }
+                    e => Ok(Expr::Neg(Box::new(e))),
+                };
+            }
+            ("if", [p, e1, e2]) => {
+                return Ok(Expr::IfThenElse(Box::new([
+                    parse_expr(p)?,
+                    parse_expr(e1)?,
+                    parse_expr(e2)?,
+                ])));
+            }
+            ("and", _) => {
+                return Ok(Expr::And(args.iter().map(parse_expr).collect::<Result<_>>()?))
+            }
+            ("or", _) => return Ok(Expr::Or(args.iter().map(parse_expr).collect::<Result<_>>()?)),
+            ("not", [e]) => return Ok(Expr::Not(Box::new(parse_expr(e)?))),
+            ("=>", [e1, e2]) => return Ok(Expr::Imp(Box::new([parse_expr(e1)?, parse_expr(e2)?]))),
+            ("<=>", [e1, e2]) => {
+                return Ok(Expr::Iff(Box::new([parse_expr(e1)?, parse_expr(e2)?])));
+            }
+            (op, [e1, e2]) => {
+                if let Some(op) = parse_bin_op(op) {
+                    return Ok(Expr::BinaryOp(op, Box::new([parse_expr(e1)?, parse_expr(e2)?])));
+                }
+                if let Some(rel) = parse_bin_rel(op) {
+                    return Ok(Expr::Atom(rel, Box::new([parse_expr(e1)?, parse_expr(e2)?])));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(Expr::App(Box::new(parse_expr(head)?), args.iter().map(parse_expr).collect::<Result<_>>()?))
+}
+
+fn parse_atom(atom: &str, span: Span) -> Result<Expr<DefaultTypes>> {
+    match atom {
+        "true" => return Ok(Expr::Constant(Constant::Boolean(true))),
+        "false" => return Ok(Expr::Constant(Constant::Boolean(false))),
+        _ => {}
+    }
+    if !atom.starts_with(|c: char| c.is_ascii_digit()) {
+        return Ok(Expr::Var(atom.to_string()));
+    }
+    let invalid = || ParseError::new(format!("invalid numeric literal `{atom}`"), span);
+    if let Some((int, frac)) = atom.split_once('.') {
+        if frac.is_empty() || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        // A literal of the form `n.0` is printed back with a scale of zero
+        let (mantissa, scale) = if frac.chars().all(|c| c == '0') {
+            (int.parse().map_err(|_| invalid())?, 0)
+        } else {
+            let mantissa = format!("{int}{frac}").parse().map_err(|_| invalid())?;
+            (mantissa, u32::try_from(frac.len()).map_err(|_| invalid())?)
+        };
+        Ok(Expr::Constant(Constant::Decimal(Real { mantissa, scale })))
+    } else {
+        Ok(Expr::int(atom.parse().map_err(|_| invalid())?))
+    }
+}
+
+fn parse_bin_op(op: &str) -> Option<BinOp> {
+    match op {
+        "+" => Some(BinOp::Add),
+        "-" => Some(BinOp::Sub),
+        "*" => Some(BinOp::Mul),
+        "/" => Some(BinOp::Div),
+        "mod" => Some(BinOp::Mod),
+        _ => None,
+    }
+}
+
+fn parse_bin_rel(rel: &str) -> Option<BinRel> {
+    match rel {
+        "=" => Some(BinRel::Eq),
+        "!=" => Some(BinRel::Ne),
+        ">" => Some(BinRel::Gt),
+        ">=" => Some(BinRel::Ge),
+        "<" => Some(BinRel::Lt),
+        "<=" => Some(BinRel::Le),
+        _ => None,
+    }
+}
+
+fn parse_sort(sexp: &Sexp) -> Result<Sort<DefaultTypes>> {
+    match &sexp.kind {
+        SexpKind::Atom(atom) => {
+            match atom.as_str() {
+                "int" => Ok(Sort::Int),
+                "bool" => Ok(Sort::Bool),
+                "real" => Ok(Sort::Real),
+                "Str" => Ok(Sort::Str),
+                _ => {
+                    if let Some(idx) = atom.strip_prefix("@(").and_then(|s| s.strip_suffix(')')) {
+                        let idx = idx
+                            .parse()
+                            .map_err(|_| ParseError::new("invalid sort variable", sexp.span))?;
+                        Ok(Sort::Var(idx))
+                    } else if let Some(Ok(size)) = atom.strip_prefix("Size").map(str::parse) {
+                        Ok(Sort::BvSize(size))
+                    } else {
+                        Ok(Sort::App(SortCtor::Data(atom.clone()), vec![]))
+                    }
+                }
+            }
+        }
+        SexpKind::Str(_) => Err(ParseError::new("expected sort", sexp.span)),
+        SexpKind::List(_) => {
+            let (head, args) = sexp.expect_app()?;
+            match head {
+                "BitVec" => {
+                    let [size] = expect_args(sexp, args)?;
+                    Ok(Sort::BitVec(Box::new(parse_sort(size)?)))
+                }
+                "func" => {
+                    let [params, inputs, output] = expect_args(sexp, args)?;
+                    let inputs: Vec<_> = inputs
+                        .expect_list()?
+                        .iter()
+                        .map(parse_sort)
+                        .collect::<Result<_>>()?;
+                    Ok(Sort::mk_func(parse_usize(params)?, inputs, parse_sort(output)?))
+                }
+                _ => {
+                    let ctor = match head {
+                        "Set_Set" => SortCtor::Set,
+                        "Map_t" => SortCtor::Map,
+                        _ => SortCtor::Data(head.to_string()),
+                    };
+                    Ok(Sort::App(ctor, args.iter().map(parse_sort).collect::<Result<_>>()?))
+                }
+            }
+        }
+    }
+}
+
+fn parse_usize(sexp: &Sexp) -> Result<usize> {
+    sexp.expect_atom()?
+        .parse()
+        .map_err(|_| ParseError::new("expected a natural number", sexp.span))
+}
+
+fn expect_kvar(sexp: &Sexp) -> Result<String> {
+    sexp.expect_atom()?
+        .strip_prefix('$')
+        .map(str::to_string)
+        .ok_or_else(|| ParseError::new("expected a kvar", sexp.span))
+}
+
+fn expect_args<'a, const N: usize>(sexp: &Sexp, args: &'a [Sexp]) -> Result<&'a [Sexp; N]> {
+    args.try_into().map_err(|_| {
+        ParseError::new(format!("expected {N} arguments but found {}", args.len()), sexp.span)
+    })
+}
+
+fn expect_list_of<const N: usize>(sexp: &Sexp) -> Result<&[Sexp; N]> {
+    let list = sexp.expect_list()?;
+    list.try_into().map_err(|_| {
+        ParseError::new(
+            format!("expected a list of length {N} but found {}", list.len()),
+            sexp.span,
+        )
+    })
+}
+
+struct Sexp {
+    kind: SexpKind,
+    span: Span,
+}
+
+enum SexpKind {
+    Atom(String),
+    Str(String),
+    List(Vec<Sexp>),
+}
+
+impl Sexp {
+    fn expect_atom(&self) -> Result<&str> {
+        if let SexpKind::Atom(atom) = &self.kind {
+            Ok(atom)
+        } else {
+            Err(ParseError::new("expected an atom", self.span))
+        }
+    }
+
+    fn expect_str(&self) -> Result<&str> {
+        if let SexpKind::Str(s) = &self.kind {
+            Ok(s)
+        } else {
+            Err(ParseError::new("expected a string", self.span))
+        }
+    }
+
+    fn expect_list(&self) -> Result<&[Sexp]> {
+        if let SexpKind::List(list) = &self.kind {
+            Ok(list)
+        } else {
+            Err(ParseError::new("expected a list", self.span))
+        }
+    }
+
+    /// Expects a list whose first element is an atom
+    fn expect_app(&self) -> Result<(&str, &[Sexp])> {
+        if let [head, args @ ..] = self.expect_list()? {
+            Ok((head.expect_atom()?, args))
+        } else {
+            Err(ParseError::new("unexpected empty list", self.span))
+        }
+    }
+}
+
+/// A top level item. Comments are only preserved at the top level together with the (0-based)
+/// line they appear in.
+enum Item {
+    Sexp(Sexp),
+    Comment(String, usize),
+}
+
+struct Reader<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0, line: 0 }
+    }
+
+    fn read_items(mut self) -> Result<Vec<Item>> {
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => return Ok(items),
+                Some(';') => {
+                    let line = self.line;
+                    let text = self.read_comment();
+                    items.push(Item::Comment(text, line));
+                }
+                Some(_) => items.push(Item::Sexp(self.read_sexp()?)),
+            }
+        }
+    }
+
+    fn read_sexp(&mut self) -> Result<Sexp> {
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(';') {
+                self.read_comment();
+            } else {
+                break;
+            }
+        }
+        let lo = self.pos;
+        match self.peek() {
+            None => Err(ParseError::new("unexpected end of input", Span { lo, hi: lo })),
+            Some('(') => {
+                self.bump();
+                let mut list = vec![];
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(')') => {
+                            self.bump();
+                            break;
+                        }
+                        Some(';') => {
+                            self.read_comment();
+                        }
+                        Some(_) => list.push(self.read_sexp()?),
+                        None => {
+                            return Err(ParseError::new(
+                                "unclosed delimiter",
+                                Span { lo, hi: self.pos },
+                            ));
+                        }
+                    }
+                }
+                Ok(Sexp { kind: SexpKind::List(list), span: Span { lo, hi: self.pos } })
+            }
+            Some(')') => {
+                Err(ParseError::new("unexpected closing delimiter", Span { lo, hi: lo + 1 }))
+            }
+            Some('"') => {
+                self.bump();
+                let mut s = String::new();
+                while let Some(c) = self.peek() {
+                    self.bump();
+                    match c {
+                        '"' => {
+                            return Ok(Sexp {
+                                kind: SexpKind::Str(s),
+                                span: Span { lo, hi: self.pos },
+                            });
+                        }
+                        '\\' => self.read_escape(&mut s)?,
+                        _ => s.push(c),
+                    }
+                }
+                Err(ParseError::new("unterminated string", Span { lo, hi: self.pos }))
+            }
+            Some(_) => {
+                // Sort variables are printed as `@(n)` so we read them as a single atom
+                if self.src[self.pos..].starts_with("@(") {
+                    self.bump();
+                    self.bump();
+                    while self.peek().is_some_and(|c| c != ')') {
+                        self.bump();
+                    }
+                    self.bump();
+                } else {
+                    while self
+                        .peek()
+                        .is_some_and(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | '"' | ';'))
+                    {
+                        self.bump();
+                    }
+                }
+                let atom = self.src[lo..self.pos].to_string();
+                Ok(Sexp { kind: SexpKind::Atom(atom), span: Span { lo, hi: self.pos } })
+            }
+        }
+    }
+
+    /// Reads an escape in a string literal after the backslash and pushes the character it denotes,
+    /// if any, to `s`. These are the escapes of Haskell's string literals used by fixpoint.
+    fn read_escape(&mut self, s: &mut String) -> Result {
+        let lo = self.pos - 1;
+        match self.peek() {
+            Some(c @ ('"' | '\\')) => {
+                self.bump();
+                s.push(c);
+            }
+            Some('n') => {
+                self.bump();
+                s.push('\n');
+            }
+            Some('t') => {
+                self.bump();
+                s.push('\t');
+            }
+            // The empty escape separates a numeric escape from a subsequent digit
+            Some('&') => self.bump(),
+            Some(c) if c.is_ascii_digit() => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.bump();
+                }
+                let c = self.src[start..self.pos]
+                    .parse()
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        ParseError::new("invalid character code", Span { lo, hi: self.pos })
+                    })?;
+                s.push(c);
+            }
+            _ => {
+                return Err(ParseError::new(
+                    "unknown escape in string",
+                    Span { lo, hi: self.pos + 1 },
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a comment until the end of the line returning its contents without the leading `;`
+    /// and the first space.
+    fn read_comment(&mut self) -> String {
+        while self.peek() == Some(';') {
+            self.bump();
+        }
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.bump();
+        }
+        let text = &self.src[start..self.pos];
+        text.strip_prefix(' ').unwrap_or(text).to_string()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+}
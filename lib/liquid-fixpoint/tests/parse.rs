@@ -0,0 +1,95 @@
+use liquid_fixpoint::{DefaultTypes, Task};
+
+fn parse(src: &str) -> Task<DefaultTypes> {
+    match Task::parse(src) {
+        Ok(task) => task,
+        Err(err) => {
+            let (line, col) = err.line_col(src);
+            panic!("{line}:{col}: {}", err.msg)
+        }
+    }
+}
+
+/// Checks that printing the parsed task and parsing it again yields the same text.
+fn assert_round_trips(src: &str) {
+    let printed = parse(src).to_string();
+    assert_eq!(printed, parse(&printed).to_string());
+}
+
+#[test]
+fn round_trip_task() {
+    assert_round_trips(
+        r#"
+(fixpoint "--scrape=both")
+;; a comment
+;; another comment
+
+(datatype (Tuple2 2) ((mktuple2 ((tuple2$0 @(0)) (tuple2$1 @(1))))))
+(qualif EqZero ((v int)) ((= v 0)))
+(qualif MyQual ((a int) (b real)) ((<= a (+ b 1.0))))
+(constant c0 (func 1 (@(0) int ) bool))  ;; some function
+(constant c1 (Set_Set int))
+(constant c2 (BitVec Size32))
+(var $k0 (int (Map_t int bool))) ;; kvar comment
+(var $k1 ()) ;;
+
+(constraint
+ (forall ((a0 int) ((true)))
+  (forall ((a1 int) ($k0 a0 a1))
+   (and
+    (forall ((a2 bool) (and ((= a1 (- 1))) ((not (c0 a0)))))
+     (tag ((=> a2 (< a0 (if a2 a1 (* a1 2))))) "0"))
+    (tag ($k1 ) "1")
+    ((or (!= (mod a0 2) 0) (<=> (>= a1 a0) (> a0 (- a1)))))))))
+"#,
+    );
+}
+
+#[test]
+fn default_qualifiers_are_not_duplicated() {
+    let task = parse("(constraint ((true)))");
+    assert!(task.qualifiers.is_empty());
+    let printed = task.to_string();
+    assert!(parse(&printed).qualifiers.is_empty());
+    assert_eq!(printed, parse(&printed).to_string());
+}
+
+#[test]
+fn error_has_span() {
+    let src = "(constraint\n (forall ((a0 int) ((true))) ((= a0 0)))";
+    let err = Task::parse(src).err().unwrap();
+    assert_eq!(err.line_col(src), (1, 1));
+    let src = "(constraint ((true)))\n(bogus)";
+    let err = Task::parse(src).err().unwrap();
+    assert_eq!(err.line_col(src), (2, 1));
+}
+
+#[test]
+fn round_trip_decimals() {
+    let src = "(constraint ((and (= 1.25 (/ 15.0 1000.0)) (< (- 2.5) 0.005))))";
+    let printed = parse(src).to_string();
+    assert!(printed.contains("(= 1.25 (/ 15.0 1000.0))"));
+    assert!(printed.contains("(< (- 2.5) 0.005)"));
+    assert_round_trips(src);
+}
+
+#[test]
+fn round_trip_string_escapes() {
+    let src = r#"(constraint ((= (strLen "a\"b\\c\955\&1") 6)))"#;
+    let task = parse(src);
+    let printed = task.to_string();
+    assert!(printed.contains(r#""a\"b\\c\955\&1""#));
+    assert_round_trips(src);
+
+    let src = r#"(constraint ((= "\q" "")))"#;
+    let err = Task::parse(src).err().unwrap();
+    assert_eq!(err.line_col(src), (1, 18));
+}
+
+#[test]
+fn qualifier_clashing_with_default_is_rejected() {
+    let src = "(qualif EqZero ((v int)) ((= v 1)))\n(constraint ((true)))";
+    let err = Task::parse(src).err().unwrap();
+    assert_eq!(err.line_col(src), (1, 1));
+    assert!(err.msg.contains("EqZero"));
+}
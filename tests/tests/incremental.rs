@@ -0,0 +1,77 @@
+//! Tests checking a crate again with incremental compilation, where rustc reuses the results of the
+//! queries of the previous session instead of running them, including the borrow checker.
+use std::{
+    env, fs,
+    path::Path,
+    process::{self, Command, Output},
+};
+
+use tests::{default_rustc_flags, find_flux_path, FLUX_FULL_COMPILATION, FLUX_SYSROOT};
+
+/// A crate with const functions, whose bodies rustc steals while checking the constants using them,
+/// and one of them with promoted constants.
+const SRC: &str = "\
+#[flux::sig(fn(x: i32) -> i32[x])]
+pub const fn id(x: i32) -> i32 {
+    x
+}
+
+pub const fn zero() -> &'static i32 {
+    &0
+}
+
+pub const ONE: i32 = id(1);
+
+pub const ZERO: i32 = *zero();
+
+#[flux::sig(fn() -> i32[1])]
+pub fn one() -> i32 {
+    ONE
+}
+";
+
+fn compile(src: &Path, dir: &Path) -> Output {
+    let flux = find_flux_path();
+    Command::new(&flux)
+        .args(default_rustc_flags())
+        .arg("--emit=metadata")
+        .arg("--out-dir")
+        .arg(dir)
+        .arg("-C")
+        .arg(format!("incremental={}", dir.join("incremental").display()))
+        .arg(src)
+        .env(FLUX_FULL_COMPILATION, "1")
+        .env(FLUX_SYSROOT, flux.parent().unwrap())
+        .env("FLUX_DUMP_CONSTRAINT", "1")
+        .env("FLUX_LOG_DIR", dir.join("log"))
+        .output()
+        .unwrap()
+}
+
+/// Compiles `src` and checks that flux checked the body of `id`
+fn check(src: &Path, dir: &Path) {
+    let log_dir = dir.join("log");
+    if log_dir.exists() {
+        fs::remove_dir_all(&log_dir).unwrap();
+    }
+    let output = compile(src, dir);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(log_dir.join("inc00.id.fluxc").exists());
+}
+
+#[test]
+fn reuse_borrowck_results() {
+    let dir = env::temp_dir().join(format!("flux-incremental-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let src = dir.join("inc00.rs");
+    fs::write(&src, SRC).unwrap();
+
+    check(&src, &dir);
+    // Nothing changed, so rustc reuses the results of the borrow checker for every body
+    check(&src, &dir);
+    // Only the body of `one` changed
+    fs::write(&src, SRC.replace("    ONE\n", "    ONE + ZERO\n")).unwrap();
+    check(&src, &dir);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,85 @@
+//! Tests for the `.fluxmeta` files of a crate, which cannot be expressed as compiletest tests
+//! because they inspect or tamper with the files produced for a dependency.
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command, Output},
+};
+
+use tests::{
+    default_rustc_flags, find_flux_meta_path, find_flux_path, FLUX_FULL_COMPILATION, FLUX_SYSROOT,
+};
+
+/// Compiles `src` with flux into `out_dir` emitting its `.fluxmeta` file
+fn compile(src: &Path, out_dir: &Path, extern_crate: Option<&str>) -> Output {
+    let flux = find_flux_path();
+    let mut cmd = Command::new(&flux);
+    cmd.args(default_rustc_flags())
+        .arg("--emit=metadata")
+        .arg("--out-dir")
+        .arg(out_dir)
+        .arg(src)
+        .env(FLUX_FULL_COMPILATION, "1")
+        .env(FLUX_SYSROOT, flux.parent().unwrap());
+    if let Some(krate) = extern_crate {
+        cmd.arg("--extern")
+            .arg(format!("{krate}={}", out_dir.join(format!("lib{krate}.rmeta")).display()));
+    }
+    cmd.output().unwrap()
+}
+
+fn out_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("flux-metadata-{}-{name}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn compile_meta00(out_dir: &Path) -> PathBuf {
+    let src: PathBuf = ["tests", "metadata", "meta00.rs"].iter().collect();
+    let output = compile(&src, out_dir, None);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    out_dir.join("libmeta00.fluxmeta")
+}
+
+#[test]
+fn dump() {
+    let out_dir = out_dir("dump");
+    let fluxmeta = compile_meta00(&out_dir);
+
+    let flux_meta = find_flux_meta_path();
+    let output = Command::new(&flux_meta)
+        .arg("dump")
+        .arg(&fluxmeta)
+        .env(FLUX_SYSROOT, flux_meta.parent().unwrap())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("// flux metadata for crate `meta00`"), "{stdout}");
+    assert!(stdout.contains("opaque sort Key"), "{stdout}");
+    assert!(stdout.contains("spec fn in_range"), "{stdout}");
+    assert!(stdout.contains("fn meta00::clamp"), "{stdout}");
+
+    fs::remove_dir_all(out_dir).unwrap();
+}
+
+#[test]
+fn incompatible_version() {
+    let out_dir = out_dir("version");
+    let fluxmeta = compile_meta00(&out_dir);
+
+    // The version of the header is stored right after the magic bytes `flux\0\0\0`
+    let mut buf = fs::read(&fluxmeta).unwrap();
+    buf[7] = buf[7].wrapping_add(1);
+    fs::write(&fluxmeta, buf).unwrap();
+
+    let src = out_dir.join("dependent.rs");
+    fs::write(&src, "extern crate meta00;\n").unwrap();
+    let output = compile(&src, &out_dir, Some("meta00"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("error[E9061]"), "{stderr}");
+    assert!(stderr.contains("recompile `meta00` with this version of flux"), "{stderr}");
+
+    fs::remove_dir_all(out_dir).unwrap();
+}
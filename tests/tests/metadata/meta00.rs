@@ -0,0 +1,10 @@
+#![flux::defs {
+    fn in_range(x: int, lo: int, hi: int) -> bool { lo <= x && x < hi }
+    opaque sort Key;
+}]
+
+#[flux::trusted]
+#[flux::sig(fn(i32) -> i32{v: in_range(v, 0, 10)})]
+pub fn clamp(x: i32) -> i32 {
+    x.clamp(0, 9)
+}
@@ -0,0 +1,2 @@
+#[flux::allow(precondition, not_a_lint)] //~ ERROR unknown flux lint `not_a_lint`
+pub fn test() {}
@@ -0,0 +1,25 @@
+pub fn incr(x: &i32) -> i32 {
+    *x + 1
+}
+
+pub fn items(v: &Vec<i32>) -> &Vec<i32> {
+    v
+}
+
+pub fn pick<'a, 'b>(_x: &'a i32, y: &'b i32) -> &'b i32 {
+    y
+}
+
+pub struct Counter {
+    count: u64,
+}
+
+impl Counter {
+    pub fn get(&self) -> u64 {
+        self.count
+    }
+}
+
+pub trait Shape {
+    fn area(&self) -> u32;
+}
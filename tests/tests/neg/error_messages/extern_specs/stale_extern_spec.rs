@@ -0,0 +1,28 @@
+//@aux-build:stale_extern_spec_aux.rs
+
+extern crate stale_extern_spec_aux;
+
+use flux_rs::*;
+use stale_extern_spec_aux::{incr, items, pick, Counter, Shape};
+
+#[extern_spec]
+#[flux::sig(fn(x: &mut i32[@n]) -> i32[n + 1])]
+fn incr(x: &mut i32) -> i32; //~ERROR invalid extern spec for `stale_extern_spec_aux::incr`
+
+#[extern_spec]
+#[flux::sig(fn(v: &Vec<i32>) -> &[i32])]
+fn items(v: &Vec<i32>) -> &[i32]; //~ERROR invalid extern spec for `stale_extern_spec_aux::items`
+
+#[extern_spec]
+fn pick<'a>(x: &'a i32, y: &'a i32) -> &'a i32; //~ERROR invalid extern spec for `stale_extern_spec_aux::pick`
+
+#[extern_spec]
+impl Counter {
+    #[flux::sig(fn(&mut Counter) -> u64)]
+    fn get(&mut self) -> u64; //~ERROR invalid extern spec for `stale_extern_spec_aux::Counter::get`
+}
+
+#[extern_spec]
+trait Shape {
+    fn area(&mut self) -> u32; //~ERROR invalid extern spec for `stale_extern_spec_aux::Shape::area`
+}
@@ -0,0 +1,3 @@
+#![flux::defs {
+    qualifier Bound10(x: int, y: int, z: int) { x + y <= z + 10 }
+}]
@@ -0,0 +1,9 @@
+//@aux-build:extern_qual00_aux.rs
+
+extern crate extern_qual00_aux;
+
+#[flux::qualifiers(extern_qual00_aux::Bound20)] //~ ERROR unknown qualifier
+pub fn test00() {}
+
+#[flux::qualifiers(Bound10)] //~ ERROR unknown qualifier
+pub fn test01() {}
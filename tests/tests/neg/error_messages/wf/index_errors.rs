@@ -27,7 +27,7 @@ pub fn myint1(x: i32) -> i32 {
     x
 }
 
-#[flux::sig(fn(f: f32) -> i32[f.x])] //~ ERROR no field `x` on sort `()`
+#[flux::sig(fn(f: f32) -> i32[f.x])] //~ ERROR `real` is a primitive sort
 fn ris(f: f32) -> i32 {
     0
 }
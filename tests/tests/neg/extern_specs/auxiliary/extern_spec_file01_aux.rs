@@ -0,0 +1,57 @@
+pub struct VecMap<K, V> {
+    keys: Vec<K>,
+    vals: Vec<V>,
+}
+
+impl<K, V> VecMap<K, V> {
+    pub fn new() -> VecMap<K, V> {
+        VecMap { keys: Vec::new(), vals: Vec::new() }
+    }
+
+    pub fn insert(&mut self, k: K, v: V) {
+        self.keys.push(k);
+        self.vals.push(v);
+    }
+
+    pub fn get(&self, k: &K) -> Option<&V>
+    where
+        K: PartialEq,
+    {
+        let i = self.keys.iter().position(|key| key == k)?;
+        self.vals.get(i)
+    }
+
+    pub fn get_mut(&mut self, k: &K) -> Option<&mut V>
+    where
+        K: PartialEq,
+    {
+        let i = self.keys.iter().position(|key| key == k)?;
+        self.vals.get_mut(i)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn into_len(self) -> usize {
+        self.keys.len()
+    }
+}
+
+pub struct Slot<'a> {
+    val: &'a i32,
+}
+
+impl<'a> Slot<'a> {
+    pub fn new(val: &'a i32) -> Slot<'a> {
+        Slot { val }
+    }
+
+    pub fn get(&self) -> &'a i32 {
+        self.val
+    }
+}
+
+pub fn pick<'a>(x: &'a i32, _y: &i32) -> &'a i32 {
+    x
+}
@@ -0,0 +1,18 @@
+//@aux-build:extern_spec_file00_aux.rs
+//@rustc-env:FLUX_EXTERN_SPECS=tests/neg/extern_specs/specs/extern_spec_file00.flux
+
+extern crate extern_spec_file00_aux;
+
+use extern_spec_file00_aux::{checked_half, Buffer};
+
+#[flux::sig(fn() -> usize[3])]
+pub fn test00() -> usize {
+    let mut buf = Buffer::new();
+    buf.push(1);
+    buf.push(2);
+    buf.len()
+} //~ ERROR refinement type
+
+pub fn test01(x: i32) -> i32 {
+    checked_half(x) //~ ERROR refinement type
+}
@@ -0,0 +1,31 @@
+//@aux-build:extern_spec_file01_aux.rs
+//@rustc-env:FLUX_EXTERN_SPECS=tests/neg/extern_specs/specs/extern_spec_file01.flux
+
+extern crate extern_spec_file01_aux;
+
+use extern_spec_file01_aux::{pick, Slot, VecMap};
+
+#[flux::sig(fn() -> usize[1])]
+pub fn test00() -> usize {
+    let mut map = VecMap::new();
+    map.insert(1, 10);
+    map.insert(2, 20);
+    map.len()
+} //~ ERROR refinement type
+
+#[flux::sig(fn() -> usize[0])]
+pub fn test01() -> usize {
+    let mut map = VecMap::new();
+    map.insert(1, 10);
+    map.into_len()
+} //~ ERROR refinement type
+
+#[flux::sig(fn(x: &i32[@n]) -> &i32[n + 1])]
+pub fn test02(x: &i32) -> &i32 {
+    let slot = Slot::new(x);
+    slot.get()
+} //~ ERROR refinement type
+
+pub fn test03<'a>(x: &'a i32, y: &i32) -> &'a i32 {
+    pick(x, y) //~ ERROR refinement type
+}
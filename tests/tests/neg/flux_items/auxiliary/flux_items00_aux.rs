@@ -0,0 +1,32 @@
+#![flux::defs {
+    fn in_range(x: int, lo: int, hi: int) -> bool { lo <= x && x < hi }
+    fn percent(x: int) -> bool { in_range(x, 0, 101) }
+    fn hash(x: int) -> int;
+    opaque sort TokenId;
+    qualifier Percent(x: int) { percent(x) }
+}]
+
+#[flux::sig(fn(i32) -> i32{v: percent(v)})]
+pub fn clamp(x: i32) -> i32 {
+    if x < 0 {
+        0
+    } else if x > 100 {
+        100
+    } else {
+        x
+    }
+}
+
+#[flux::opaque]
+#[flux::refined_by(id: TokenId)]
+pub struct Token {
+    _private: (),
+}
+
+impl Token {
+    #[flux::trusted]
+    #[flux::sig(fn(&Token[@id]) -> Token[id])]
+    pub fn dup(&self) -> Token {
+        Token { _private: () }
+    }
+}
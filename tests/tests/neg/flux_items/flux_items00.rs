@@ -0,0 +1,20 @@
+//@aux-build:flux_items00_aux.rs
+
+extern crate flux_items00_aux;
+
+use flux_items00_aux::clamp;
+
+#[flux::sig(fn(i32{v: flux_items00_aux::percent(v)}) -> i32{v: flux_items00_aux::in_range(v, 0, 100)})]
+pub fn test00(x: i32) -> i32 {
+    x //~ ERROR refinement type
+}
+
+#[flux::sig(fn(i32) -> i32{v: v < 100})]
+pub fn test01(x: i32) -> i32 {
+    clamp(x) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: flux_items00_aux::hash(v) == flux_items00_aux::hash(x)})]
+pub fn test02(x: i32) -> i32 {
+    x + 1 //~ ERROR refinement type
+}
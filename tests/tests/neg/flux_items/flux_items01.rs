@@ -0,0 +1,38 @@
+//@aux-build:flux_items01_aux.rs
+
+#![flux::defs {
+    fn is_pos(x: int) -> bool { x >= 0 }
+}]
+
+extern crate flux_items01_aux;
+
+#[path = "../../lib/rvec.rs"]
+pub mod rvec;
+
+use rvec::RVec;
+
+#[flux::sig(fn(i32{v: is_pos(v)}) -> i32{v: flux_items01_aux::is_pos(v)})]
+pub fn test00(x: i32) -> i32 {
+    x //~ ERROR refinement type
+}
+
+#[flux::refined_by(x: int, y: int)]
+pub struct Pair {
+    #[flux::field(i32[x])]
+    pub x: i32,
+    #[flux::field(i32[y])]
+    pub y: i32,
+}
+
+// The qualifier exported by the dependency is not used because it is not named.
+#[flux::sig(fn (a: i32) -> RVec<Pair{v : v.x + v.y <= a + 10 }>)]
+pub fn test01(a: i32) -> RVec<Pair> {
+    let mut i = 0;
+    let mut res = RVec::new();
+    while i < a {
+        let p = Pair { x: i + 10, y: a - i };
+        res.push(p);
+        i += 1;
+    }
+    return res; //~ ERROR refinement type
+}
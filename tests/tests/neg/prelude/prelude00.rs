@@ -0,0 +1,45 @@
+#![flux::cfg(prelude = true)]
+
+use std::collections::VecDeque;
+
+#[flux::sig(fn() -> Vec<i32>[2])]
+pub fn test_vec_macro() -> Vec<i32> {
+    let v = vec![10, 20, 30];
+    v //~ ERROR refinement type
+}
+
+pub fn test_push_pop() {
+    let mut v = Vec::new();
+    v.push(1);
+    v.pop().unwrap();
+    v.pop().unwrap(); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&Vec<i32>[@n]) -> i32)]
+pub fn test_index(v: &Vec<i32>) -> i32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i <= v.len() {
+        sum += v[i]; //~ ERROR refinement type
+        i += 1;
+    }
+    sum
+}
+
+#[flux::sig(fn(Vec<i32>{n: n > 0}))]
+pub fn test_remove(mut v: Vec<i32>) {
+    v.remove(0);
+    v.remove(0); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&[i32][@n]) -> Option<&i32>[true])]
+pub fn test_slice(s: &[i32]) -> Option<&i32> {
+    s.get(0) //~ ERROR refinement type
+}
+
+pub fn test_vec_deque() {
+    let mut q = VecDeque::new();
+    q.push_back(1);
+    q.pop_front().unwrap();
+    q.pop_back().unwrap(); //~ ERROR refinement type
+}
@@ -0,0 +1,29 @@
+#![flux::cfg(prelude = true)]
+
+use std::collections::{HashMap, HashSet};
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+pub fn test_map() {
+    let (one, two) = (1, 2);
+    let mut m = HashMap::new();
+    m.insert(one, "one");
+    m.get(&two).unwrap(); //~ ERROR refinement type
+    m.remove(&one);
+    assert(m.contains_key(&one)); //~ ERROR refinement type
+}
+
+pub fn test_set() {
+    let x = 1;
+    let mut s = HashSet::new();
+    s.insert(x);
+    assert(s.insert(x)); //~ ERROR refinement type
+    s.clear();
+    assert(s.contains(&x)); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(String[@s]) -> Vec<u8>[str_len(s) + 1])]
+pub fn test_into_bytes(s: String) -> Vec<u8> {
+    s.into_bytes()
+} //~ ERROR refinement type
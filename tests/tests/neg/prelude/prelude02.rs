@@ -0,0 +1,27 @@
+#![flux::cfg(prelude = true)]
+
+use std::collections::{HashMap, HashSet};
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+// Keys are not refined, so all of them have the same index and the set of keys says nothing about
+// which keys are in the map. Each of the assertions below fails at runtime.
+#[derive(PartialEq, Eq, Hash)]
+pub struct Key(i32);
+
+pub fn test_map() {
+    let mut m = HashMap::new();
+    m.insert(Key(1), "one");
+    assert(m.contains_key(&Key(2))); //~ ERROR refinement type
+    m.remove(&Key(2));
+    assert(!m.contains_key(&Key(1))); //~ ERROR refinement type
+}
+
+pub fn test_set() {
+    let mut s = HashSet::new();
+    s.insert(Key(1));
+    assert(!s.insert(Key(2))); //~ ERROR refinement type
+    s.remove(&Key(2));
+    assert(!s.contains(&Key(1))); //~ ERROR refinement type
+}
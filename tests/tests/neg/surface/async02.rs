@@ -0,0 +1,39 @@
+use std::future::Future;
+
+#[flux::sig(async fn(x: &i32{v: v > 0}) -> i32{v: v > 0})]
+pub async fn by_ref(x: &i32) -> i32 {
+    *x - 1 //~ ERROR refinement type
+}
+
+#[flux::sig(fn() -> impl Future<Output = i32{v: v > 0}>)]
+pub fn zero() -> impl Future<Output = i32> {
+    async { 0 } //~ ERROR refinement type
+}
+
+pub trait Counter {
+    #[flux::sig(async fn(&Self) -> i32{v: v >= 0})]
+    async fn count(&self) -> i32;
+}
+
+pub struct Neg;
+
+impl Counter for Neg {
+    #[flux::sig(async fn(&Self) -> i32{v: v >= 0})]
+    async fn count(&self) -> i32 {
+        -1 //~ ERROR refinement type
+    }
+}
+
+pub struct Any;
+
+impl Counter for Any {
+    #[flux::sig(async fn(&Self) -> i32)]
+    async fn count(&self) -> i32 { //~ ERROR refinement type
+        0
+    }
+}
+
+#[flux::sig(async fn(&T) -> i32{v: v > 0})]
+pub async fn use_counter<T: Counter>(c: &T) -> i32 {
+    c.count().await
+} //~ ERROR refinement type
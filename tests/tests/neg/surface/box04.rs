@@ -0,0 +1,22 @@
+pub enum List<T> {
+    Nil,
+    Cons(T, Box<List<T>>),
+}
+
+#[flux::sig(fn(list: &strg List<i32{v: v > 0}>, List<i32>) ensures list: List<i32{v: v > 0}>)]
+pub fn append_pos(list: &mut List<i32>, other: List<i32>) { //~ ERROR refinement type
+    let mut cur = list;
+    while let List::Cons(_, tl) = cur {
+        cur = tl;
+    }
+    *cur = other;
+}
+
+#[flux::sig(fn(list: &strg List<i32{v: v > 0}>) ensures list: List<i32{v: v > 0}>)]
+pub fn push_back_pos(list: &mut List<i32>) { //~ ERROR refinement type
+    let mut cur = list;
+    while let List::Cons(_, tl) = cur {
+        cur = &mut **tl;
+    }
+    *cur = List::Cons(0, Box::new(List::Nil));
+}
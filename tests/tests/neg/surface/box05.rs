@@ -0,0 +1,16 @@
+#[flux::refined_by(len: int)]
+pub enum List<T> {
+    #[flux::variant(List<T>[0])]
+    Nil,
+    #[flux::variant((T, Box<List<T>[@n]>) -> List<T>[n + 1])]
+    Cons(T, Box<List<T>>),
+}
+
+#[flux::sig(fn(list: &strg List<i32{v: v > 0}>[@n]) ensures list: List<i32{v: v > 0}>)]
+pub fn push_back_pos(list: &mut List<i32>) { //~ ERROR refinement type
+    let mut cur = list;
+    while let List::Cons(_, tl) = cur {
+        cur = &mut **tl;
+    }
+    *cur = List::Cons(0, Box::new(List::Nil));
+}
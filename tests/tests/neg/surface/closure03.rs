@@ -0,0 +1,36 @@
+// Test refinements in `Fn` bounds mentioning refinement parameters of the signature
+
+#[flux::trusted]
+#[flux::sig(fn[hrn p: int -> bool](x: i32{v: p(v)}, f: F) -> i32{v: p(v)} where F: FnOnce(i32{v: p(v)}) -> i32{v: p(v)})]
+fn apply<F: FnOnce(i32) -> i32>(x: i32, f: F) -> i32 {
+    f(x)
+}
+
+#[flux::sig(fn() -> i32{v: v > 0})]
+pub fn test_hrn() -> i32 {
+    apply(5, |x| x - 1) //~ ERROR refinement type
+}
+
+#[flux::trusted]
+#[flux::sig(fn(x: i32, f: F) -> i32{v: v > x} where F: Fn(i32[x]) -> i32{v: v > x})]
+fn bump<F: Fn(i32) -> i32>(x: i32, f: F) -> i32 {
+    f(x)
+}
+
+pub fn test_param(n: i32) -> i32 {
+    bump(n, |y| y) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(i32{v: v > 0}))]
+fn need_pos(_x: i32) {}
+
+// The closure must be checked even if the signature doesn't mention the `Fn` bound
+#[flux::trusted]
+#[flux::sig(fn(f: F))]
+fn call<F: FnOnce(i32)>(f: F) {
+    f(1)
+}
+
+pub fn test_unchecked() {
+    call(|_| need_pos(0)); //~ ERROR refinement type
+}
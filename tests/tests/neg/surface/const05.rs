@@ -0,0 +1,23 @@
+// Test const generics and const items as array lengths and refinement variables
+
+pub const BUFLEN: usize = 100;
+
+#[flux::sig(fn(&[i32; N]) -> i32 requires N > 0)]
+pub fn first<const N: usize>(arr: &[i32; N]) -> i32 {
+    arr[0]
+}
+
+#[flux::sig(fn(&[i32; N]) -> i32)]
+pub fn last<const N: usize>(arr: &[i32; N]) -> i32 {
+    arr[N] //~ ERROR assertion might fail
+}
+
+#[flux::sig(fn(&[i32; BUFLEN], i: usize{i <= BUFLEN}) -> i32)]
+pub fn get(buf: &[i32; BUFLEN], i: usize) -> i32 {
+    buf[i] //~ ERROR assertion might fail
+}
+
+pub fn test() -> i32 {
+    let arr: [i32; 0] = [];
+    first(&arr) //~ ERROR refinement type
+}
@@ -0,0 +1,13 @@
+// Test arithmetic over const generics in array lengths
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+#[flux::sig(fn(&[i32; N + 1]) -> i32)]
+pub fn past_end<const N: usize>(arr: &[i32; N + 1]) -> i32 {
+    arr[N + 1] //~ ERROR assertion might fail
+}
+
+#[flux::sig(fn(&[i32; 2 * N]) -> i32)]
+pub fn second_half<const N: usize>(arr: &[i32; 2 * N]) -> i32 {
+    arr[N] //~ ERROR assertion might fail
+}
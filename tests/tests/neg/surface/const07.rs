@@ -0,0 +1,4 @@
+// Test that array lengths cannot mention refinement parameters
+
+#[flux::sig(fn(n: usize, &[i32; n]))] //~ ERROR invalid array length
+pub fn invalid<const N: usize>(_n: usize, _arr: &[i32; N]) {}
@@ -0,0 +1,13 @@
+// Test that arithmetic over const generics in array lengths must match the Rust type
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+#[flux::sig(fn(&[i32; N + 2]) -> i32)] //~ ERROR incompatible refinement
+pub fn wrong_len<const N: usize>(arr: &[i32; N + 1]) -> i32 {
+    arr[N]
+}
+
+#[flux::sig(fn(&[i32; N]) -> i32)] //~ ERROR incompatible refinement
+pub fn missing_arith<const N: usize>(arr: &[i32; N - 1]) -> i32 {
+    arr[0]
+}
@@ -0,0 +1,15 @@
+#[flux::sig(fn(x: i32{x > 0}) -> i32)]
+fn pos(x: i32) -> i32 {
+    x
+}
+
+macro_rules! call_pos {
+    ($name:ident) => {
+        pub fn $name() -> i32 {
+            pos(0) //~ ERROR refinement type
+        }
+    };
+}
+
+call_pos!(test00);
+
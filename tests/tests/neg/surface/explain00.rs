@@ -0,0 +1,35 @@
+// Explanations are attached to the error as notes, so they don't change which errors are reported
+
+#[flux::sig(fn(n: i32) -> i32{v: v >= 0})]
+fn abs(n: i32) -> i32 {
+    if n < 0 { -n } else { n }
+}
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})] //~ NOTE this is the condition
+fn pos(x: i32) -> i32 {
+    x
+}
+
+#[flux::explain]
+#[flux::sig(fn(b: bool, n: i32) -> i32{v: v > 0})]
+fn test00(b: bool, n: i32) -> i32 { //~ NOTE from the inputs of the function: b: bool, n: int
+    let m = abs(n); //~ NOTE from the output of the call to `abs`: a2: int, a2 ≥ 0
+    if b { //~ NOTE from the branch taken here: b
+        pos(m) //~ ERROR refinement type error
+               //~| NOTE a precondition cannot be proved
+               //~| NOTE cannot prove a2 > 0
+    } else {
+        1
+    }
+}
+
+#[flux::explain]
+#[flux::sig(fn(o: Option<i32>) -> i32{v: v > 0})] //~ NOTE this is the condition
+fn test01(o: Option<i32>) -> i32 { //~ NOTE from the inputs of the function: o.b: bool
+    match o { //~ NOTE from the branch taken here: o.b = true, a1: int
+        Some(x) => x, //~ ERROR refinement type error
+                      //~| NOTE a postcondition cannot be proved
+                      //~| NOTE cannot prove a1 > 0
+        None => 1,
+    }
+}
@@ -0,0 +1,41 @@
+// Test refinements on floats
+
+#[flux::sig(fn(x: f64{0.0 <= x && x <= 1.0}) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn double(x: f64) -> f64 {
+    x * 2.0 //~ ERROR refinement type
+}
+
+// NaN fails both comparisons so it must be handled explicitly
+#[flux::sig(fn(f64) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn clamp(x: f64) -> f64 {
+    if x < 0.0 {
+        0.0
+    } else if x > 1.0 {
+        1.0
+    } else {
+        x //~ ERROR refinement type
+    }
+}
+
+// Float arithmetic rounds, so the result is not the exact real midpoint
+#[flux::sig(fn(x: f32, y: f32{x < y}) -> f32{v: x < v && v < y})]
+pub fn midpoint(x: f32, y: f32) -> f32 {
+    (x + y) / 2.0 //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: f64, y: f64) -> f64[x / y])]
+pub fn div(x: f64, y: f64) -> f64 {
+    x / y //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: f64, y: f64) -> bool[x == y])]
+pub fn eq(x: f64, y: f64) -> bool {
+    x == y //~ ERROR refinement type
+}
+
+pub fn test() {
+    to_unit(f64::NAN); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(f64{v: 0.0 <= v && v <= 1.0}))]
+pub fn to_unit(_: f64) {}
@@ -0,0 +1,20 @@
+#[flux_rs::sig(fn(bool[true]))]
+pub fn assert(_b: bool) {}
+
+pub fn test_lit() {
+    for i in 10..20 {
+        assert(10 < i); //~ ERROR refinement type
+    }
+}
+
+#[flux_rs::sig(fn(n: usize))]
+pub fn test_inclusive(n: usize) {
+    for i in 0..=n {
+        assert(i < n); //~ ERROR refinement type
+    }
+}
+
+#[flux_rs::sig(fn(n: usize))]
+pub fn test_step_by(n: usize) {
+    for _ in (0..n).step_by(0) {} //~ ERROR refinement type
+}
@@ -0,0 +1,18 @@
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+fn pos(x: i32) -> i32 {
+    x
+}
+
+#[flux::warn(precondition)]
+pub fn test00() -> i32 {
+    pos(0) //~ WARNING refinement type
+}
+
+#[flux::allow(all)]
+pub mod foo {
+    #[flux::deny(postcondition)]
+    #[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+    pub fn test01(x: i32) -> i32 {
+        x //~ ERROR refinement type
+    }
+}
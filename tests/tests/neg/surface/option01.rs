@@ -0,0 +1,31 @@
+// Test the built-in specs for `Option` combinators
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_b: bool) {}
+
+#[flux::sig(fn(Option<i32{v: v > 0}>) -> i32{v: v > 1})]
+pub fn test_map(x: Option<i32>) -> i32 {
+    x.map(|n| n + 1).unwrap() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Option<i32{v: v >= 0}>) -> i32{v: v > 0})]
+pub fn test_map_or(x: Option<i32>) -> i32 {
+    x.map_or(0, |n| n + 1) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Option<i32>) -> Option<i32>[true])]
+pub fn test_and_then(x: Option<i32>) -> Option<i32> {
+    x.and_then(|n| Some(n)) //~ ERROR refinement type
+}
+
+pub fn test_filter() {
+    let x = Some(1);
+    let y = x.filter(|n| *n > 0);
+    assert(y.is_some()); //~ ERROR refinement type
+}
+
+pub fn test_take() {
+    let mut x = Some(1);
+    x.take();
+    assert(x.is_some()); //~ ERROR refinement type
+}
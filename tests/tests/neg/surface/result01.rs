@@ -0,0 +1,29 @@
+// Test the built-in specs for `Result` combinators
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_b: bool) {}
+
+#[flux::sig(fn(i32) -> Result<i32{v: v >= 0}, i32{v: v < 0}>)]
+fn check(n: i32) -> Result<i32, i32> {
+    if n >= 0 { Ok(n) } else { Err(n) }
+}
+
+#[flux::sig(fn(i32) -> Result<i32{v: v > 0}, bool[false]>)]
+pub fn test_map_err(n: i32) -> Result<i32, bool> {
+    let m = check(n).map_err(|e| e < 0)?; //~ ERROR refinement type
+    Ok(m + 1)
+}
+
+#[flux::sig(fn(i32) -> i32{v: v > 0})]
+pub fn test_unwrap_or_else(n: i32) -> i32 {
+    check(n).unwrap_or_else(|e| -e) //~ ERROR refinement type
+}
+
+pub fn test_unwrap(n: i32) -> i32 {
+    check(n).unwrap() //~ ERROR refinement type
+}
+
+pub fn test_err() {
+    let r: Result<i32, ()> = Ok(1);
+    assert(r.err().is_some()); //~ ERROR refinement type
+}
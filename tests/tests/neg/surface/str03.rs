@@ -0,0 +1,32 @@
+// Test the string theory functions and the built-in specs for `str` and `String`
+
+#[flux::sig(fn(&str{s: str_prefix_of("GET ", s)}) -> &str)]
+fn get_request(s: &str) -> &str {
+    s
+}
+
+pub fn test_get() {
+    get_request("POST /index.html"); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&str[@s]) -> String[str_concat("\r\n", s)])]
+pub fn line(s: &str) -> String {
+    let mut line = String::new();
+    line.push_str(s);
+    line.push_str("\r\n");
+    line //~ ERROR refinement type
+}
+
+#[flux::sig(fn() -> bool[true])]
+pub fn test_eq() -> bool {
+    let s = "cat".to_owned();
+    let t = "dog";
+    s == t
+} //~ ERROR refinement type
+
+// Non-ASCII literals are not indexed because their length in bytes differs from the number of
+// characters
+#[flux::sig(fn() -> usize[1])]
+pub fn test_len() -> usize {
+    "é".len() //~ ERROR refinement type
+}
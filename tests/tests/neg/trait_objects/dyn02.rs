@@ -0,0 +1,58 @@
+#[flux::generics(Self as base)]
+#[flux::assoc(fn size(self: Self) -> int)]
+pub trait Shape {
+    #[flux::sig(fn(self: &Self[@s]) -> i32[<Self as Shape>::size(s)])]
+    fn size(&self) -> i32;
+}
+
+#[flux::refined_by(side: int)]
+pub struct Square {
+    #[flux::field(i32[side])]
+    pub side: i32,
+}
+
+#[flux::assoc(fn size(sq: Square) -> int { sq.side })]
+impl Shape for Square {
+    #[flux::sig(fn(self: &Square[@sq]) -> i32[sq.side])]
+    fn size(&self) -> i32 {
+        self.side
+    }
+}
+
+#[flux::sig(fn(shape: &dyn Shape{s: s.size > 0}) -> i32{v: v > 0})]
+pub fn size(shape: &dyn Shape) -> i32 {
+    shape.size()
+}
+
+#[flux::sig(fn(shape: &dyn Shape{s: s.size > 0}) -> i32{v: v > 1})]
+pub fn size_bad(shape: &dyn Shape) -> i32 {
+    shape.size() //~ ERROR refinement type
+}
+
+pub fn test00() {
+    let sq = Square { side: 0 };
+    size(&sq); //~ ERROR refinement type
+}
+
+pub fn test01() {
+    let b: Box<dyn Shape> = Box::new(Square { side: 0 });
+    size(&*b); //~ ERROR refinement type
+}
+
+pub fn test02(shape: &mut dyn Shape) {
+    size(shape); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(shape: &mut dyn Shape{s: s.size > 0}) -> i32{v: v > 0})]
+pub fn size_mut(shape: &mut dyn Shape) -> i32 {
+    shape.size()
+}
+
+pub fn test03() {
+    let mut sq = Square { side: 0 };
+    size_mut(&mut sq); //~ ERROR refinement type
+}
+
+pub fn test04(shape: &mut dyn Shape) {
+    size_mut(shape); //~ ERROR refinement type
+}
@@ -0,0 +1,21 @@
+pub struct Buffer {
+    data: Vec<u8>,
+}
+
+impl Buffer {
+    pub fn new() -> Buffer {
+        Buffer { data: Vec::new() }
+    }
+
+    pub fn push(&mut self, byte: u8) {
+        self.data.push(byte);
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+pub fn checked_half(x: i32) -> i32 {
+    x / 2
+}
@@ -0,0 +1,42 @@
+//@aux-build:extern_spec_file01_aux.rs
+//@rustc-env:FLUX_EXTERN_SPECS=tests/pos/extern_specs/specs/extern_spec_file01.flux
+
+extern crate extern_spec_file01_aux;
+
+use extern_spec_file01_aux::{pick, Slot, VecMap};
+
+#[flux::sig(fn() -> usize[2])]
+pub fn test00() -> usize {
+    let mut map = VecMap::new();
+    map.insert(1, 10);
+    map.insert(2, 20);
+    map.len()
+}
+
+pub fn test01(map: &VecMap<i32, i32>) -> Option<&i32> {
+    map.get(&1)
+}
+
+pub fn test02(map: &mut VecMap<i32, i32>) {
+    if let Some(v) = map.get_mut(&1) {
+        *v += 1;
+    }
+}
+
+#[flux::sig(fn() -> usize[1])]
+pub fn test03() -> usize {
+    let mut map = VecMap::new();
+    map.insert(1, 10);
+    map.into_len()
+}
+
+#[flux::sig(fn(x: &i32[@n]) -> &i32[n])]
+pub fn test04(x: &i32) -> &i32 {
+    let slot = Slot::new(x);
+    slot.get()
+}
+
+#[flux::sig(fn(x: &i32{v: v > 0}, y: &i32) -> &i32{v: v > 0})]
+pub fn test05<'a>(x: &'a i32, y: &i32) -> &'a i32 {
+    pick(x, y)
+}
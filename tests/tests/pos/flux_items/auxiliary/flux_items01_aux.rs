@@ -0,0 +1,6 @@
+#![flux::defs {
+    fn is_pos(x: int) -> bool { x > 0 }
+    fn hash(x: int) -> int;
+    opaque sort Id;
+    qualifier Bound10(x: int, y: int, z: int) { x + y <= z + 10 }
+}]
@@ -0,0 +1,29 @@
+//@aux-build:flux_items00_aux.rs
+
+#![flux::defs {
+    fn same_token(a: flux_items00_aux::TokenId, b: flux_items00_aux::TokenId) -> bool { a == b }
+}]
+
+extern crate flux_items00_aux;
+
+use flux_items00_aux::{clamp, Token};
+
+#[flux::sig(fn(i32{v: flux_items00_aux::in_range(v, 10, 20)}) -> i32{v: flux_items00_aux::percent(v)})]
+pub fn test00(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn(i32) -> i32{v: v <= 100})]
+pub fn test01(x: i32) -> i32 {
+    clamp(x)
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: flux_items00_aux::hash(v) == flux_items00_aux::hash(x)})]
+pub fn test02(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn(&Token[@a]) -> Token{b: same_token(a, b)})]
+pub fn test03(token: &Token) -> Token {
+    token.dup()
+}
@@ -0,0 +1,47 @@
+//@aux-build:flux_items01_aux.rs
+
+// Items exported by a crate are namespaced by the crate, so the same names can be defined locally.
+#![flux::defs {
+    fn is_pos(x: int) -> bool { x >= 0 }
+    fn hash(x: int) -> int;
+    opaque sort Id;
+}]
+
+extern crate flux_items01_aux;
+
+#[path = "../../lib/rvec.rs"]
+pub mod rvec;
+
+use rvec::RVec;
+
+#[flux::sig(fn(i32{v: flux_items01_aux::is_pos(v)}) -> i32{v: is_pos(v)})]
+pub fn test00(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: hash(v) == hash(x) && flux_items01_aux::hash(v) == flux_items01_aux::hash(x)})]
+pub fn test01(x: i32) -> i32 {
+    x
+}
+
+#[flux::refined_by(x: int, y: int)]
+pub struct Pair {
+    #[flux::field(i32[x])]
+    pub x: i32,
+    #[flux::field(i32[y])]
+    pub y: i32,
+}
+
+// Qualifiers exported by a dependency are only used when named explicitly.
+#[flux::qualifiers(flux_items01_aux::Bound10)]
+#[flux::sig(fn (a: i32) -> RVec<Pair{v : v.x + v.y <= a + 10 }>)]
+pub fn test02(a: i32) -> RVec<Pair> {
+    let mut i = 0;
+    let mut res = RVec::new();
+    while i < a {
+        let p = Pair { x: i + 10, y: a - i };
+        res.push(p);
+        i += 1;
+    }
+    return res;
+}
@@ -0,0 +1,6 @@
+#![flux::cfg(prelude = true)]
+
+#[flux::sig(fn(usize[@n]) -> Vec<i32>[n])]
+pub fn zeros(n: usize) -> Vec<i32> {
+    vec![0; n]
+}
@@ -0,0 +1,75 @@
+#![flux::cfg(prelude = true)]
+
+use std::collections::VecDeque;
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::sig(fn() -> Vec<i32>[3])]
+pub fn test_vec_macro() -> Vec<i32> {
+    vec![10, 20, 30]
+}
+
+#[flux::sig(fn(usize[@n]) -> Vec<i32>[n])]
+pub fn test_from_elem(n: usize) -> Vec<i32> {
+    vec![0; n]
+}
+
+pub fn test_push_pop() {
+    let mut v = Vec::new();
+    v.push(1);
+    v.push(2);
+    assert(v.len() == 2);
+    v.pop().unwrap();
+    v.pop().unwrap();
+    assert(v.is_empty());
+    assert(v.pop().is_none());
+}
+
+#[flux::sig(fn(&Vec<i32>[@n]) -> i32)]
+pub fn test_index(v: &Vec<i32>) -> i32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < v.len() {
+        sum += v[i];
+        i += 1;
+    }
+    sum
+}
+
+#[flux::sig(fn(&mut Vec<i32>{n: n > 1}))]
+pub fn test_index_mut(v: &mut Vec<i32>) {
+    v[0] = v[1];
+}
+
+pub fn test_remove() {
+    let mut v = vec![1, 2, 3];
+    v.insert(3, 4);
+    v.swap_remove(0);
+    v.remove(2);
+    v.truncate(1);
+    assert(v.len() == 1);
+    v.clear();
+    assert(v.is_empty());
+}
+
+#[flux::sig(fn(&[i32][@n]) -> Option<&i32>[n > 0])]
+pub fn test_slice(s: &[i32]) -> Option<&i32> {
+    if s.len() > 1 {
+        let (a, b) = s.split_at(1);
+        assert(a.len() == 1);
+        s.get(b.len())
+    } else {
+        s.first()
+    }
+}
+
+pub fn test_vec_deque() {
+    let mut q = VecDeque::new();
+    q.push_back(1);
+    q.push_front(0);
+    assert(q.len() == 2);
+    q.pop_front().unwrap();
+    q.pop_back().unwrap();
+    assert(q.front().is_none());
+}
@@ -0,0 +1,39 @@
+#![flux::cfg(prelude = true)]
+
+use std::collections::{HashMap, HashSet};
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+pub fn test_map() {
+    let (one, two) = (1, 2);
+    let mut m = HashMap::new();
+    m.insert(one, "one");
+    m.insert(two, "two");
+    assert(m.contains_key(&one));
+    m.get(&two).unwrap();
+    m.remove(&one).unwrap();
+    assert(!m.contains_key(&one));
+}
+
+pub fn test_set() {
+    let x = 1;
+    let mut s = HashSet::new();
+    assert(s.insert(x));
+    assert(!s.insert(x));
+    assert(s.contains(&x));
+    assert(s.remove(&x));
+    assert(!s.contains(&x));
+}
+
+pub fn test_char_keys() {
+    let mut s = HashSet::new();
+    s.insert('a');
+    assert(s.contains(&'a'));
+    assert(!s.contains(&'b'));
+}
+
+#[flux::sig(fn(String[@s]) -> Vec<u8>[str_len(s)])]
+pub fn test_into_bytes(s: String) -> Vec<u8> {
+    s.into_bytes()
+}
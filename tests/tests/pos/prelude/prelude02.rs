@@ -0,0 +1,11 @@
+//@aux-build:prelude02_aux.rs
+
+// The prelude is loaded because the dependency was checked with it
+
+extern crate prelude02_aux;
+
+#[flux::sig(fn() -> i32)]
+pub fn test() -> i32 {
+    let v = prelude02_aux::zeros(3);
+    v[2]
+}
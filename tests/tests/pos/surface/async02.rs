@@ -0,0 +1,59 @@
+use std::future::Future;
+
+#[flux::sig(async fn(x: &i32{v: v > 0}) -> i32{v: v > 0})]
+pub async fn by_ref(x: &i32) -> i32 {
+    *x
+}
+
+#[flux::sig(fn() -> impl Future<Output = i32{v: v > 0}>)]
+pub fn one() -> impl Future<Output = i32> {
+    async { 1 }
+}
+
+#[flux::sig(async fn() -> i32{v: v > 1})]
+pub async fn two() -> i32 {
+    let x = one().await;
+    let y = by_ref(&x).await;
+    x + y
+}
+
+pub trait Counter {
+    #[flux::sig(async fn(&Self) -> i32{v: v >= 0})]
+    async fn count(&self) -> i32;
+
+    #[flux::sig(fn(&Self) -> impl Future<Output = i32{v: v > 0}>)]
+    fn step(&self) -> impl Future<Output = i32>;
+}
+
+pub struct Zero;
+
+impl Zero {
+    #[flux::sig(async fn(&Self) -> i32[0])]
+    pub async fn zero(&self) -> i32 {
+        0
+    }
+}
+
+impl Counter for Zero {
+    #[flux::sig(async fn(&Self) -> i32{v: v >= 0})]
+    async fn count(&self) -> i32 {
+        self.zero().await
+    }
+
+    #[flux::sig(fn(&Self) -> impl Future<Output = i32{v: v > 0}>)]
+    fn step(&self) -> impl Future<Output = i32> {
+        one()
+    }
+}
+
+#[flux::sig(async fn(&Zero) -> i32{v: v >= 0})]
+pub async fn use_zero(z: &Zero) -> i32 {
+    z.count().await
+}
+
+#[flux::sig(async fn(&T) -> i32{v: v > 0})]
+pub async fn use_counter<T: Counter>(c: &T) -> i32 {
+    let n = c.count().await;
+    let s = c.step().await;
+    n + s
+}
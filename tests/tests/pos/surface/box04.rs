@@ -0,0 +1,35 @@
+// Loops that walk a list of boxes with a cursor. At the loop head the cursor can point to the
+// argument or into any of the boxes, so it's weakened to a `&mut` borrow.
+pub enum List<T> {
+    Nil,
+    Cons(T, Box<List<T>>),
+}
+
+impl<T> List<T> {
+    #[flux::sig(fn(self: &strg List<T>, List<T>) ensures self: List<T>)]
+    pub fn append(&mut self, other: List<T>) {
+        let mut cur = self;
+        while let List::Cons(_, tl) = cur {
+            cur = tl;
+        }
+        *cur = other;
+    }
+}
+
+#[flux::sig(fn(list: &strg List<i32{v: v > 0}>, List<i32{v: v > 0}>) ensures list: List<i32{v: v > 0}>)]
+pub fn append_pos(list: &mut List<i32>, other: List<i32>) {
+    let mut cur = list;
+    while let List::Cons(_, tl) = cur {
+        cur = tl;
+    }
+    *cur = other;
+}
+
+#[flux::sig(fn(list: &strg List<i32{v: v > 0}>) ensures list: List<i32{v: v > 0}>)]
+pub fn push_back_pos(list: &mut List<i32>) {
+    let mut cur = list;
+    while let List::Cons(_, tl) = cur {
+        cur = &mut **tl;
+    }
+    *cur = List::Cons(1, Box::new(List::Nil));
+}
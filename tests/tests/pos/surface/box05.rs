@@ -0,0 +1,29 @@
+// Cursor loops over an indexed list of boxes. The cursor is weakened to a `&mut List<T>` borrow at
+// the loop head, which forgets the length, so postconditions can only mention the element type.
+#[flux::refined_by(len: int)]
+pub enum List<T> {
+    #[flux::variant(List<T>[0])]
+    Nil,
+    #[flux::variant((T, Box<List<T>[@n]>) -> List<T>[n + 1])]
+    Cons(T, Box<List<T>>),
+}
+
+impl<T> List<T> {
+    #[flux::sig(fn(self: &strg List<T>[@n], List<T>[@m]) ensures self: List<T>)]
+    pub fn append(&mut self, other: List<T>) {
+        let mut cur = self;
+        while let List::Cons(_, tl) = cur {
+            cur = tl;
+        }
+        *cur = other;
+    }
+}
+
+#[flux::sig(fn(list: &strg List<i32{v: v > 0}>[@n]) ensures list: List<i32{v: v > 0}>)]
+pub fn push_back_pos(list: &mut List<i32>) {
+    let mut cur = list;
+    while let List::Cons(_, tl) = cur {
+        cur = &mut **tl;
+    }
+    *cur = List::Cons(1, Box::new(List::Nil));
+}
@@ -0,0 +1,36 @@
+// Test refinements in `Fn` bounds mentioning refinement parameters of the signature
+
+#[flux::trusted]
+#[flux::sig(fn[hrn p: int -> bool](x: i32{v: p(v)}, f: F) -> i32{v: p(v)} where F: FnOnce(i32{v: p(v)}) -> i32{v: p(v)})]
+fn apply<F: FnOnce(i32) -> i32>(x: i32, f: F) -> i32 {
+    f(x)
+}
+
+#[flux::sig(fn() -> i32{v: v > 0})]
+pub fn test_hrn() -> i32 {
+    apply(5, |x| x + 1)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(x: i32, f: F) -> i32{v: v > x} where F: Fn(i32[x]) -> i32{v: v > x})]
+fn bump<F: Fn(i32) -> i32>(x: i32, f: F) -> i32 {
+    f(x)
+}
+
+#[flux::sig(fn(n: i32) -> i32{v: v > n})]
+pub fn test_param(n: i32) -> i32 {
+    bump(n, |y| y + 1)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(f: F) where F: FnMut(i32{v: v > 0}))]
+fn call_pos<F: FnMut(i32)>(mut f: F) {
+    f(1);
+}
+
+#[flux::sig(fn(i32{v: v > 0}))]
+fn need_pos(_x: i32) {}
+
+pub fn test_unit() {
+    call_pos(|x| need_pos(x));
+}
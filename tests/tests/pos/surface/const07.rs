@@ -0,0 +1,30 @@
+// Test const generics and const items as array lengths and refinement variables
+
+pub const BUFLEN: usize = 100;
+
+#[flux::sig(fn(&[i32; N]) -> i32 requires N > 0)]
+pub fn first<const N: usize>(arr: &[i32; N]) -> i32 {
+    arr[0]
+}
+
+#[flux::sig(fn() -> usize[N])]
+pub fn len<const N: usize>() -> usize {
+    N
+}
+
+#[flux::sig(fn(&[i32; BUFLEN]) -> i32)]
+pub fn last(buf: &[i32; BUFLEN]) -> i32 {
+    buf[BUFLEN - 1]
+}
+
+#[flux::sig(fn(&[i32; BUFLEN * 2]) -> i32)]
+pub fn middle(buf: &[i32; 2 * BUFLEN]) -> i32 {
+    buf[BUFLEN]
+}
+
+#[flux::sig(fn() -> usize[3])]
+pub fn test() -> usize {
+    let arr = [1, 2, 3];
+    first(&arr);
+    len::<3>()
+}
@@ -0,0 +1,17 @@
+// Test arithmetic over const generics in array lengths
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+#[flux::sig(fn(&[i32; N + 1]) -> i32)]
+pub fn last<const N: usize>(arr: &[i32; N + 1]) -> i32 {
+    arr[N]
+}
+
+#[flux::sig(fn(&[i32; 2 * N]) -> i32 requires N > 0)]
+pub fn second_half<const N: usize>(arr: &[i32; 2 * N]) -> i32 {
+    arr[N]
+}
+
+pub fn test() -> i32 {
+    last::<2>(&[1, 2, 3]) + second_half::<1>(&[1, 2])
+}
@@ -0,0 +1,28 @@
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[flux::refined_by(n: int)]
+pub enum Proto {
+    #[flux::variant(Proto[0])]
+    Tcp,
+    #[flux::variant(Proto[1])]
+    Udp,
+}
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+pub fn test00(p: Proto) {
+    let tcp = Proto::Tcp;
+    let udp = Proto::Udp;
+    assert(p == p);
+    assert(!(tcp == udp));
+}
+
+#[flux::sig(fn(p: Proto) -> i32{v: p.n == 0 => v > 0})]
+pub fn test01(p: Proto) -> i32 {
+    let tcp = Proto::Tcp;
+    if p == tcp {
+        1
+    } else {
+        0
+    }
+}
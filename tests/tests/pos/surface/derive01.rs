@@ -0,0 +1,29 @@
+#[flux::sig(fn(x: i32{x > 0}) -> i32)]
+fn pos(x: i32) -> i32 {
+    x
+}
+
+#[flux::trusted]
+macro_rules! call_pos {
+    ($name:ident) => {
+        pub fn $name() -> i32 {
+            pos(0)
+        }
+    };
+}
+
+call_pos!(test00);
+
+#[derive(Debug, Clone, Hash)]
+#[flux::trusted_derive(Debug)]
+#[flux::refined_by(n: int)]
+pub struct Nat {
+    #[flux::field(i32[n])]
+    pub n: i32,
+}
+
+#[derive(Hash)]
+#[flux::ignore_derive(Hash)]
+pub struct Wrapper {
+    pub inner: Nat,
+}
@@ -0,0 +1,35 @@
+// Test refinements on floats
+
+#[flux::sig(fn(f64) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn clamp(x: f64) -> f64 {
+    if x >= 0.0 && x <= 1.0 {
+        x
+    } else if x > 1.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+#[flux::sig(fn(x: f64) -> f64[-x])]
+pub fn neg(x: f64) -> f64 {
+    -x
+}
+
+// Arithmetic results are unrefined but can still be checked against unrefined signatures
+#[flux::sig(fn(x: f64) -> f64)]
+pub fn quarter(x: f64) -> f64 {
+    x / 4.0
+}
+
+#[flux::sig(fn() -> f32[1.5e-3])]
+pub fn small() -> f32 {
+    0.0015
+}
+
+pub fn test() {
+    let x = clamp(quarter(2.0));
+    if x < 0.5 {
+        clamp(neg(x));
+    }
+}
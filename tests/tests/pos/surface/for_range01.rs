@@ -0,0 +1,31 @@
+// Loops whose invariants only relate the range to variables in scope verify with the default
+// qualifiers. Loops over ranges with literal bounds are in `for_range02.rs`.
+
+#[flux_rs::sig(fn(bool[true]))]
+pub fn assert(_b: bool) {}
+
+#[flux_rs::sig(fn(n: usize))]
+pub fn test_inclusive(n: usize) {
+    for i in 0..=n {
+        assert(i <= n);
+    }
+}
+
+#[flux_rs::sig(fn(&[i32][@n]) -> i32)]
+pub fn test_enumerate(slice: &[i32]) -> i32 {
+    let mut sum = 0;
+    for (i, _) in (0..slice.len()).enumerate() {
+        sum += slice[i];
+    }
+    sum
+}
+
+#[flux_rs::sig(fn(n: usize))]
+pub fn test_step_by(n: usize) {
+    for _ in (0..n).step_by(2) {}
+}
+
+#[flux_rs::sig(fn(n: usize))]
+pub fn test_rev_zip(n: usize) {
+    for _ in (0..n).rev().zip(0..n) {}
+}
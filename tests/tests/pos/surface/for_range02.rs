@@ -0,0 +1,25 @@
+// The invariants of these loops mention the literal bounds of the range, which can only be inferred
+// by scraping qualifiers.
+#![flux::cfg(scrape_quals = true)]
+
+#[path = "../../lib/rrange.rs"]
+mod range;
+use range::Rng;
+
+#[flux::sig(fn (bool[true]))]
+fn assert(_b: bool) {}
+
+pub fn test_loop0() -> i32 {
+    for i in 10..20 {
+        assert(10 <= i);
+    }
+    110
+}
+
+pub fn test_loop1() -> i32 {
+    for i in Rng::new(10, 20) {
+        assert(10 <= i);
+        assert(i < 20);
+    }
+    110
+}
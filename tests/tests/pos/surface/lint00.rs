@@ -0,0 +1,24 @@
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+fn pos(x: i32) -> i32 {
+    x
+}
+
+#[flux::allow(precondition)]
+pub fn test00() -> i32 {
+    pos(0)
+}
+
+#[flux::allow(all)]
+pub mod foo {
+    #[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+    pub fn test01(x: i32) -> i32 {
+        x
+    }
+}
+
+#[flux::warn(all)]
+#[flux::allow(overflow)]
+#[flux::check_overflow]
+pub fn test02(x: u32, y: u32) -> u32 {
+    x + y
+}
@@ -0,0 +1,44 @@
+// Test the built-in specs for `Option` combinators
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_b: bool) {}
+
+#[flux::sig(fn(Option<i32{v: v > 0}>[true]) -> i32{v: v > 1})]
+pub fn test_map(x: Option<i32>) -> i32 {
+    x.map(|n| n + 1).unwrap()
+}
+
+#[flux::sig(fn(Option<i32{v: v >= 0}>) -> i32{v: v >= 0})]
+pub fn test_unwrap_or(x: Option<i32>) -> i32 {
+    x.unwrap_or(0)
+}
+
+#[flux::sig(fn(Option<i32{v: v >= 0}>) -> i32{v: v > 0})]
+pub fn test_map_or(x: Option<i32>) -> i32 {
+    x.map_or(1, |n| n + 1)
+}
+
+#[flux::sig(fn(Option<i32>[false]) -> Option<i32>[false])]
+pub fn test_and_then(x: Option<i32>) -> Option<i32> {
+    x.and_then(|n| if n > 0 { Some(n) } else { None })
+}
+
+#[flux::sig(fn(Option<i32>[@b]) -> Result<i32, ()>[b])]
+pub fn test_ok_or(x: Option<i32>) -> Result<i32, ()> {
+    x.ok_or(())
+}
+
+pub fn test_is_some() {
+    let x = Some(1);
+    assert(x.is_some());
+    assert(!x.is_none());
+    let y = x.filter(|n| *n > 0);
+    assert(y.or(x).is_some());
+}
+
+pub fn test_take() {
+    let mut x = Some(1);
+    let y = x.take();
+    assert(y.is_some());
+    assert(x.is_none());
+}
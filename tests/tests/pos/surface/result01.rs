@@ -0,0 +1,38 @@
+// Test the built-in specs for `Result` combinators
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_b: bool) {}
+
+#[flux::sig(fn(i32) -> Result<i32{v: v >= 0}, i32{v: v < 0}>)]
+fn check(n: i32) -> Result<i32, i32> {
+    if n >= 0 { Ok(n) } else { Err(n) }
+}
+
+#[flux::sig(fn(i32) -> Result<i32{v: v > 0}, bool[true]>)]
+pub fn test_map_err(n: i32) -> Result<i32, bool> {
+    let m = check(n).map_err(|e| e < 0)?;
+    Ok(m + 1)
+}
+
+#[flux::sig(fn(i32) -> Result<i32{v: v > 0}, i32{v: v < 0}>)]
+pub fn test_map(n: i32) -> Result<i32, i32> {
+    check(n).map(|m| m + 1)
+}
+
+#[flux::sig(fn(i32) -> Result<i32{v: v > 0}, i32{v: v < 0}>)]
+pub fn test_and_then(n: i32) -> Result<i32, i32> {
+    check(n).and_then(|m| if m > 0 { Ok(m) } else { Err(-1) })
+}
+
+#[flux::sig(fn(i32) -> i32{v: v >= 0})]
+pub fn test_unwrap_or_else(n: i32) -> i32 {
+    check(n).unwrap_or_else(|e| -e)
+}
+
+pub fn test_ok() {
+    let r: Result<i32, ()> = Ok(1);
+    assert(r.is_ok());
+    assert(r.ok().is_some());
+    let m = r.map(|n| n + 1).unwrap();
+    assert(m == 2);
+}
@@ -0,0 +1,45 @@
+// Test the string theory functions and the built-in specs for `str` and `String`
+
+#[flux::sig(fn(&str{s: str_prefix_of("GET ", s)}) -> &str)]
+fn get_request(s: &str) -> &str {
+    s
+}
+
+#[flux::sig(fn(&str[@s]) -> String[str_concat(s, "\r\n")])]
+pub fn line(s: &str) -> String {
+    let mut line = String::new();
+    line.push_str(s);
+    line.push_str("\r\n");
+    line
+}
+
+#[flux::sig(fn(&str[@s]) -> usize[str_len(s) + 2])]
+pub fn line_len(s: &str) -> usize {
+    line(s).len()
+}
+
+pub fn test_get() {
+    get_request("GET /index.html");
+}
+
+#[flux::sig(fn() -> bool[true])]
+pub fn test_eq() -> bool {
+    let s = "cat".to_owned();
+    let t = "cat";
+    s == t
+}
+
+#[flux::sig(fn() -> bool[true])]
+pub fn test_is_empty() -> bool {
+    let s = String::new();
+    s.is_empty() && s.as_bytes().len() == 0
+}
+
+#[flux::sig(fn() -> bool[
+    str_contains("protocol", "toc")
+    && str_suffix_of("col", "protocol")
+    && str_substr("protocol", 0, 5) == "proto"
+])]
+pub fn test_theory() -> bool {
+    true
+}
@@ -8,17 +8,12 @@ enum List<T> {
     Cons(T, Box<List<T>>),
 }
 
-// Panics when joining environments because we are closing a box with an outstanding
-// ptr(mut) to the box's location, i.e.,
-// we start with
-//   r1: box(a0), r2: ptr(mut, a0), a0: T,
-// and closing the box produces
-//   r1: Box<T>, r2: ptr(mut, a0)
-//
-// after closing the box we should have
-//   r1: Box<T>, r2: &mut T
-//
-impl List<T> {
+// At the loop head `cur` can point to `self` or into any of the boxes, so it is weakened to a
+// `&mut List<T>` borrow (see `pos/surface/box04.rs` and `pos/surface/box05.rs`). The weakening
+// forgets the length of the list, so the postcondition `self: List<T>[n + m]` cannot be proven.
+// Verifying this requires relating the final value behind the borrow to the length of the prefix
+// walked so far, which mutable borrows can't express.
+impl<T> List<T> {
     #[flux::sig(fn(self: &strg List<T>[@n], List<T>[@m]) ensures self: List<T>[n + m])]
     fn append(&mut self, other: List<T>) {
         let mut cur = self;
@@ -1,31 +1,7 @@
-use std::ops::Range;
-
-use flux_rs::extern_spec;
-
-#[path = "../../tests/lib/rrange.rs"]
-mod range;
-use range::Rng;
-
-#[extern_spec]
-#[flux::refined_by(lo: Idx, hi: Idx)]
-struct Range<Idx>;
 #[flux::sig(fn (bool[true]))]
 fn assert(_b: bool) {}
 
-pub fn test_loop0() -> i32 {
-    for i in 10..20 {
-        assert(10 <= i); //~ ERROR refinement type
-    }
-    110
-}
-
-pub fn test_loop1() -> i32 {
-    for i in Rng::new(10, 20) {
-        assert(10 <= i);
-        assert(i < 20);
-    }
-    110
-}
+// `test_loop0` and `test_loop1` were moved to `pos/surface/for_range02.rs`
 
 // pub fn test_loop<const N: usize>(arr: &[i32; N]) -> i32 {
 //     let mut x = 0;
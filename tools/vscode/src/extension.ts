@@ -260,22 +260,18 @@ function collapseBindings(bindings: RcxBind[]): RcxBind[] {
     return binds;
 }
 
+// `rcx` and `env` follow the schema in `flux_common::trace` (`RefineCtxtTrace` and `TypeEnvTrace`)
 function parseRcx(rcx: string): Rcx {
     const rcxObj = JSON.parse(rcx);
     rcxObj.bindings = collapseBindings(rcxObj.bindings);
-    rcxObj.exprs = rcxObj.exprs.map((s:any) => parseNestedString(s));
     return rcxObj;
 }
 
-function parseNestedString(s: string): NestedString {
-    return JSON.parse(s);
-}
-
 function parseEnv(env: string): TypeEnv {
     return JSON.parse(env)
             .filter((bind: TypeEnvBind) => bind.name)
             .map((b:any) => {
-                return {name: b.name, kind: b.kind, ty: parseNestedString(b.ty) }
+                return {name: b.name, kind: b.kind, ty: b.ty }
             });
 }
 
@@ -381,7 +377,7 @@ class FluxViewProvider implements vscode.WebviewViewProvider {
           `;
           }).join('');
 
-        const rcxExprs = this._currentRcx?.exprs.map(expr => `
+        const rcxExprs = this._currentRcx?.exprs.concat(this._currentRcx.kvars).map(expr => `
             <tr>
                 <td>${nestedStringHtml(expr)}</td>
             </tr>
@@ -609,6 +605,7 @@ type RcxBind = {
 type Rcx = {
     bindings: RcxBind[],
     exprs: NestedString[],
+    kvars: NestedString[],
 }
 
 type StmtSpan = {
@@ -1,9 +1,12 @@
 use std::{
-    env,
+    env::{
+        self,
+        consts::{DLL_PREFIX, DLL_SUFFIX},
+    },
     path::{Path, PathBuf},
 };
 
-use tests::{find_flux_path, FLUX_SYSROOT};
+use tests::{find_flux_path, FLUX_FULL_COMPILATION, FLUX_SYSROOT};
 use xshell::{cmd, Shell};
 
 xflags::xflags! {
@@ -162,6 +165,27 @@ fn install_libs(sh: &Shell, args: &Install, extra: &[&str]) -> anyhow::Result<()
         cmd!(sh, "cargo build -Zunstable-options -p flux-rs --artifact-dir {out_dir} {extra...}")
             .run()?;
     }
+    // `rustc-flux` was installed to `~/.cargo/bin` by `install`
+    build_flux_core(sh, Path::new("rustc-flux"), &out_dir)
+}
+
+/// Compile the specs for `core` and the prelude with the specs for the standard collections with
+/// flux. This generates a `.rlib` and a `.fluxmeta` file for each in `{sysroot}/flux_core` which
+/// `flux-driver` loads when checking any crate. We keep a copy of the `flux_rs` used to compile them
+/// in the same directory such that rebuilding `flux_rs` doesn't invalidate them.
+fn build_flux_core(sh: &Shell, rustc_flux: &Path, sysroot: &Path) -> anyhow::Result<()> {
+    let out_dir = sysroot.join("flux_core");
+    let flux_rs = format!("{DLL_PREFIX}flux_rs{DLL_SUFFIX}");
+    sh.create_dir(&out_dir)?;
+    sh.copy_file(sysroot.join(&flux_rs), out_dir.join(&flux_rs))?;
+
+    let _sysroot = sh.push_env(FLUX_SYSROOT, sysroot);
+    let _full = sh.push_env(FLUX_FULL_COMPILATION, "1");
+    cmd!(sh, "{rustc_flux} --crate-name flux_core --crate-type=rlib --edition=2021 --emit=metadata,link --out-dir {out_dir} lib/flux-core/src/lib.rs")
+        .run()?;
+    // The prelude is compiled after `flux_core` because it depends on it
+    cmd!(sh, "{rustc_flux} --crate-name flux_prelude --crate-type=rlib --edition=2021 --emit=metadata,link --out-dir {out_dir} lib/flux-prelude/src/lib.rs")
+        .run()?;
     Ok(())
 }
 
@@ -195,7 +219,9 @@ fn build_sysroot(sh: &Shell) -> anyhow::Result<()> {
     let _env = sh.push_env("FLUX_BUILD_SYSROOT", "1");
     println!("$ export FLUX_BUILD_SYSROOT=1");
     cmd!(sh, "cargo build -p flux-rs").run()?;
-    Ok(())
+    cmd!(sh, "cargo build -p flux-bin -p flux-driver").run()?;
+    let flux_path = find_flux_path();
+    build_flux_core(sh, &flux_path, flux_path.parent().unwrap())
 }
 
 impl Install {